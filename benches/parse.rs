@@ -0,0 +1,355 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use twitchchat::{
+    irc::TagIndices,
+    messages::Privmsg,
+    twitch::{Badge, Emotes},
+    Decoder, FromIrcMessage as _, IntoOwned as _, IrcMessage, Validator as _,
+};
+
+// counts allocations so the benches can report allocations per message
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations_per(iterations: usize, mut f: impl FnMut()) -> f64 {
+    let start = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..iterations {
+        f()
+    }
+    (ALLOCATIONS.load(Ordering::Relaxed) - start) as f64 / iterations as f64
+}
+
+const PLAIN: &str = ":museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello world\r\n";
+
+const TAG_HEAVY: &str = "@badge-info=subscriber/8;badges=broadcaster/1,subscriber/6,partner/1;client-nonce=4e160a535482;color=#FF69B4;display-name=museun;emotes=;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=1;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello world\r\n";
+
+const EMOTE_HEAVY: &str = "@badge-info=;badges=;color=#FF69B4;display-name=museun;emotes=25:0-4,12-16,24-28,36-40/1902:6-10,18-22,30-34,42-46;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :Kappa Keepo Kappa Keepo Kappa Keepo Kappa Keepo\r\n";
+
+const FIXTURES: &[(&str, &str)] = &[
+    ("plain", PLAIN),
+    ("tag_heavy", TAG_HEAVY),
+    ("emote_heavy", EMOTE_HEAVY),
+];
+
+fn parse(input: &str) -> IrcMessage<'_> {
+    IrcMessage::parse_lossy(input.as_bytes()).unwrap()
+}
+
+fn irc_message(c: &mut Criterion) {
+    let mut group = c.benchmark_group("IrcMessage::parse");
+    for (name, input) in FIXTURES {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_function(*name, |b| b.iter(|| parse(black_box(input))));
+    }
+    group.finish();
+}
+
+fn privmsg(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Privmsg::from_irc");
+    for (name, input) in FIXTURES {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_function(*name, |b| {
+            b.iter(|| Privmsg::from_irc(parse(black_box(input))).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn tags(c: &mut Criterion) {
+    let msg = Privmsg::from_irc(parse(EMOTE_HEAVY)).unwrap();
+    let emotes = msg.tags().get("emotes").unwrap().to_string();
+    c.bench_function("Emotes::parse", |b| {
+        b.iter(|| Emotes::parse(black_box(&emotes)).count())
+    });
+
+    let msg = Privmsg::from_irc(parse(TAG_HEAVY)).unwrap();
+    let badges = msg.tags().get("badges").unwrap().to_string();
+    c.bench_function("Badge::parse", |b| {
+        b.iter(|| {
+            black_box(&badges)
+                .split(',')
+                .filter_map(Badge::parse)
+                .count()
+        })
+    });
+}
+
+fn into_owned(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Privmsg::into_owned");
+    for (name, input) in FIXTURES {
+        let msg = Privmsg::from_irc(parse(input)).unwrap();
+        group.bench_function(*name, |b| b.iter(|| black_box(&msg).clone().into_owned()));
+    }
+    group.finish();
+}
+
+// a realistic chat message, with 18 tags
+const EIGHTEEN_TAGS: &str = "@badge-info=subscriber/14;badges=moderator/1,subscriber/12,glhf-pledge/1;client-nonce=a1b2c3d4e5f60718;color=#1E90FF;display-name=Someone;emotes=25:0-4;first-msg=0;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=1;returning-chatter=0;room-id=23196011;subscriber=1;tmi-sent-ts=1601079032426;turbo=0;user-id=12345678;user-type=mod;vip=0";
+
+// the previous implementation, which allocated each key and value, kept here to compare against
+fn build_indices_baseline(input: &str) -> Vec<(String, String)> {
+    let input = &input[1..];
+    let mut map = Vec::with_capacity(input.split_terminator(';').count());
+    for input in input.split_terminator(';') {
+        let mut iter = input.split('=');
+        let key = iter.next().unwrap().to_string();
+        let value = iter.next().unwrap().to_string();
+        map.push((key, value));
+    }
+    map
+}
+
+fn tag_indices(c: &mut Criterion) {
+    let mut group = c.benchmark_group("TagIndices::build_indices");
+    let fixtures = FIXTURES
+        .iter()
+        .map(|(name, input)| {
+            (
+                *name,
+                parse(input).get_tags().unwrap_or_default().to_string(),
+            )
+        })
+        .chain(std::iter::once((
+            "eighteen_tags",
+            EIGHTEEN_TAGS.to_string(),
+        )));
+
+    for (name, tags) in fixtures {
+        let allocs = allocations_per(1000, || {
+            black_box(TagIndices::build_indices(black_box(&tags)).unwrap());
+        });
+        eprintln!(
+            "TagIndices::build_indices/{}: {} allocations per message",
+            name, allocs
+        );
+
+        group.throughput(Throughput::Bytes(tags.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| TagIndices::build_indices(black_box(&tags)).unwrap())
+        });
+
+        if tags.is_empty() {
+            continue;
+        }
+
+        let allocs = allocations_per(1000, || {
+            black_box(build_indices_baseline(black_box(&tags)));
+        });
+        eprintln!(
+            "TagIndices::build_indices/{}/baseline: {} allocations per message",
+            name, allocs
+        );
+        group.bench_function(format!("{}/baseline", name), |b| {
+            b.iter(|| build_indices_baseline(black_box(&tags)))
+        });
+    }
+    group.finish();
+}
+
+fn decoder(c: &mut Criterion) {
+    const LINES: usize = 100;
+
+    let mut group = c.benchmark_group("Decoder");
+    for (name, input) in FIXTURES {
+        let data = input.repeat(LINES);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+
+        // the decoder reuses its line buffer, so borrowed messages shouldn't allocate
+        let read_all = |data: &[u8]| {
+            let mut decoder = Decoder::new(data);
+            while let Ok(msg) = decoder.read_message() {
+                black_box(msg);
+            }
+        };
+        let allocs = allocations_per(10, || read_all(data.as_bytes())) / LINES as f64;
+        eprintln!(
+            "Decoder::read_message/{}: {} allocations per message",
+            name, allocs
+        );
+        group.bench_function(format!("read_message/{}", name), |b| {
+            b.iter(|| read_all(black_box(data.as_bytes())))
+        });
+
+        // the decoder rebuilds the indices in place, the baseline builds new ones for each message
+        let with_tags = |data: &[u8]| {
+            let mut decoder = Decoder::new(data);
+            while let Ok((msg, indices)) = decoder.read_message_with_tags() {
+                black_box((msg, indices));
+            }
+        };
+        let with_tags_baseline = |data: &[u8]| {
+            let mut decoder = Decoder::new(data);
+            while let Ok(msg) = decoder.read_message() {
+                let indices = msg.parse_tags();
+                black_box((msg, indices));
+            }
+        };
+        for (kind, f) in [
+            ("read_message_with_tags", &with_tags as &dyn Fn(&[u8])),
+            ("read_message_with_tags/baseline", &with_tags_baseline),
+        ]
+        .iter()
+        {
+            let allocs = allocations_per(10, || f(data.as_bytes())) / LINES as f64;
+            eprintln!(
+                "Decoder::{}/{}: {} allocations per message",
+                kind, name, allocs
+            );
+            group.bench_function(format!("{}/{}", kind, name), |b| {
+                b.iter(|| f(black_box(data.as_bytes())))
+            });
+        }
+
+        let owned_all = |data: &[u8]| Decoder::new(data).map(Result::unwrap).count();
+        let allocs = allocations_per(10, || {
+            black_box(owned_all(data.as_bytes()));
+        }) / LINES as f64;
+        eprintln!("Decoder::next/{}: {} allocations per message", name, allocs);
+        group.bench_function(format!("next/{}", name), |b| {
+            b.iter(|| owned_all(black_box(data.as_bytes())))
+        });
+    }
+    group.finish();
+}
+
+fn line_splitting(c: &mut Criterion) {
+    // ~1MB of concatenated messages, like a busy connection's read buffer
+    let line = FIXTURES.iter().map(|(_, input)| *input).collect::<String>();
+    let data = line.repeat((1024 * 1024) / line.len());
+
+    // run with and without `--features memchr` to compare the scanners
+    let scanner = if cfg!(feature = "memchr") {
+        "memchr"
+    } else {
+        "str::find"
+    };
+
+    let mut group = c.benchmark_group("irc::parse");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function(format!("1mb/{}", scanner), |b| {
+        b.iter(|| twitchchat::irc::parse(black_box(&data)).count())
+    });
+    group.finish();
+}
+
+// the runner needs the test connector
+#[cfg(feature = "testing")]
+fn runner(c: &mut Criterion) {
+    use criterion::BatchSize;
+    use futures_lite::future::block_on;
+    use twitchchat::{runner::MessageArena, test::TestConnector, AsyncRunner, Status, UserConfig};
+
+    const LINES: usize = 100;
+
+    let connect = |data: &str| {
+        block_on(async {
+            const READY: &str = ":tmi.twitch.tv 001 justinfan1234 :Welcome, GLHF!\r\n\
+                                 :tmi.twitch.tv 376 justinfan1234 :>\r\n";
+
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(format!("{}{}", READY, data))
+                .await;
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(connector, &user_config).await.unwrap();
+            // skip the handshake messages
+            for _ in 0..2 {
+                runner.next_message().await.unwrap();
+            }
+            runner
+        })
+    };
+
+    let owned_all = |runner: &mut AsyncRunner| {
+        block_on(async {
+            while let Status::Message(msg) = runner.next_message().await.unwrap() {
+                black_box(msg);
+            }
+        })
+    };
+
+    let arena_all = |runner: &mut AsyncRunner, arena: &mut MessageArena| {
+        block_on(async {
+            while let Status::Message(msg) = runner.next_message_in(arena).await.unwrap() {
+                black_box(msg);
+            }
+        })
+    };
+
+    let mut group = c.benchmark_group("AsyncRunner");
+    for (name, input) in FIXTURES {
+        let data = input.repeat(LINES);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+
+        let mut runner = connect(&data);
+        let allocs = allocations_per(1, || owned_all(&mut runner)) / LINES as f64;
+        eprintln!(
+            "AsyncRunner::next_message/{}: {} allocations per message",
+            name, allocs
+        );
+        group.bench_function(format!("next_message/{}", name), |b| {
+            b.iter_batched(
+                || connect(&data),
+                |mut runner| owned_all(&mut runner),
+                BatchSize::SmallInput,
+            )
+        });
+
+        let mut arena = MessageArena::new();
+        let mut runner = connect(&data);
+        let allocs = allocations_per(1, || arena_all(&mut runner, &mut arena)) / LINES as f64;
+        eprintln!(
+            "AsyncRunner::next_message_in/{}: {} allocations per message",
+            name, allocs
+        );
+        group.bench_function(format!("next_message_in/{}", name), |b| {
+            b.iter_batched(
+                || connect(&data),
+                |mut runner| arena_all(&mut runner, &mut arena),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "testing"))]
+fn runner(_: &mut Criterion) {}
+
+criterion_group!(
+    benches,
+    irc_message,
+    privmsg,
+    tags,
+    into_owned,
+    tag_indices,
+    decoder,
+    line_splitting,
+    runner
+);
+criterion_main!(benches);
@@ -8,15 +8,11 @@ fn get_env_var(key: &str) -> anyhow::Result<String> {
 }
 
 pub fn get_user_config() -> anyhow::Result<twitchchat::UserConfig> {
-    let name = get_env_var("TWITCH_NAME")?;
-    let token = get_env_var("TWITCH_TOKEN")?;
-
     // you need a `UserConfig` to connect to Twitch
-    let config = UserConfig::builder()
-        // the name of the associated twitch account
-        .name(name)
-        // and the provided OAuth token
-        .token(token)
+    //
+    // this reads the name of the associated twitch account from `TWITCH_NAME`
+    // and the OAuth token from `TWITCH_OAUTH` (or `TWITCH_TOKEN`)
+    let config = UserConfig::from_env()?
         // and enable all of the advanced message signaling from Twitch
         .enable_all_capabilities()
         .build()?;
@@ -37,7 +37,7 @@ fn parse_demo() {
         let msg: twitchchat::maybe_owned::MaybeOwned<'_> = pm.into_inner();
 
         // `MaybeOwned<'a>` can be used as a `&'a str`.
-        let msg = twitchchat::irc::parse(&*msg)
+        let msg = twitchchat::irc::parse(&msg)
             .next()
             .map(|s| s.unwrap())
             .unwrap();
@@ -76,7 +76,7 @@ fn parse_demo() {
     // you can get the string value for a key
     assert_eq!(msg.tags().get("key1").unwrap(), "val1");
     // or it as a 'truthy' value
-    assert_eq!(msg.tags().get_as_bool("key2"), true);
+    assert!(msg.tags().get_as_bool("key2"));
     // or as a FromStr parsed value
     assert_eq!(msg.tags().get_parsed::<_, i32>("key3").unwrap(), 42);
 
@@ -34,11 +34,9 @@ fn main() -> anyhow::Result<()> {
             args.writer.say(args.msg, &output).unwrap();
         })
         .with_command("!quit", move |args: Args| {
-            // because we're using sync stuff, turn async into sync with smol!
-            smol::block_on(async move {
-                // calling this will cause read_message() to eventually return Status::Quit
-                args.quit.notify().await
-            });
+            // calling this will cause read_message() to eventually return Status::Quit
+            // this doesn't block, so it can be used from this sync callback
+            args.quit.notify_sync();
         });
 
     // run the bot in the executor
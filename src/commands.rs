@@ -14,14 +14,14 @@ pub(crate) use super::Encodable;
 
 macro_rules! write_cmd {
     ($w:expr, $chan:expr => $data:expr) => {{
-        write!($w, "PRIVMSG {} :", $chan)?;
-        write!($w, "{}", $data)?;
+        write!($w, "PRIVMSG {} :", $crate::commands::StripNewlines($chan))?;
+        write!($w, "{}", $crate::commands::StripNewlines($data))?;
         write!($w, "\r\n")
     }};
 
     ($w:expr, $chan:expr => $fmt:expr, $($args:expr),* $(,)?) => {{
-        write!($w, "PRIVMSG {} :", $chan)?;
-        write!($w, $fmt, $($args),*)?;
+        write!($w, "PRIVMSG {} :", $crate::commands::StripNewlines($chan))?;
+        write!($w, $fmt, $($crate::commands::StripNewlines($args)),*)?;
         write!($w, "\r\n")
     }};
 }
@@ -38,7 +38,7 @@ macro_rules! write_jtv_cmd {
 
 macro_rules! write_nl {
     ($w:expr, $fmt:expr, $($args:expr),* $(,)?) => {{
-        write!($w, $fmt, $($args),*)?;
+        write!($w, $fmt, $($crate::commands::StripNewlines($args)),*)?;
         write!($w, "\r\n")
     }};
 }
@@ -60,10 +60,12 @@ macro_rules! export_commands {
 
 export_commands! {
     ban             => Ban
+    cap_req         => CapReq
     clear           => Clear
     color           => Color
     command         => Command
     commercial      => Commercial
+    delete          => Delete
     disconnect      => Disconnect
     emote_only      => EmoteOnly
     emote_only_off  => EmoteOnlyOff
@@ -73,11 +75,14 @@ export_commands! {
     help            => Help
     host            => Host
     join            => Join
+    join_many       => JoinMany
     jtv_command     => JtvCommand
     marker          => Marker
     me              => Me
     mods            => Mods
+    nick            => Nick
     part            => Part
+    pass            => Pass
     ping            => Ping
     pong            => Pong
     privmsg         => Privmsg
@@ -85,6 +90,7 @@ export_commands! {
     r9k_beta_off    => R9kBetaOff
     raid            => Raid
     raw             => Raw
+    raw_line        => RawLine
     register        => Register
     reply           => Reply
     slow            => Slow
@@ -103,6 +109,13 @@ export_commands! {
     whisper         => Whisper
 }
 
+mod info;
+pub use info::{all, CommandInfo};
+
+pub use privmsg::try_privmsg;
+pub use raw_line::InvalidRawLine;
+pub use register::HandshakeCommand;
+
 macro_rules! serde_for_commands {
     (@one $($x:tt)*) => { () };
     (@len $($e:expr),*) => { <[()]>::len(&[$(serde_for_commands!(@one $e)),*]) };
@@ -136,31 +149,37 @@ macro_rules! serde_for_commands {
 
 serde_for_commands! {
     Ban { channel, username, reason };
+    CapReq { capabilities };
     Clear { channel };
     Color { color };
     Command { channel, data };
     JtvCommand { data };
     Commercial { channel, length };
+    Delete { channel, msg_id };
     Disconnect { };
     EmoteOnly { channel };
     EmoteOnlyOff { channel };
     Followers { channel, duration };
     FollowersOff { channel };
     GiveMod { channel, username };
-    Help { channel };
+    Help { channel, topic };
     Host { source, target };
     Join { channel };
+    JoinMany { channels, max_per_line };
     Marker { channel, comment };
     Me { channel, msg };
     Mods { channel };
+    Nick { name };
     Ping { token };
     Part { channel };
+    Pass { token };
     Pong { token };
     Privmsg { channel, msg };
     R9kBeta { channel };
     R9kBetaOff { channel };
     Raid { source, target };
     Raw { data };
+    RawLine { data };
     Register { user_config };
     Reply { channel, msg_id, msg };
     Slow { channel, duration };
@@ -219,6 +238,46 @@ where
     }
 }
 
+/// Writes the value with any run of `\r` or `\n` replaced by a single space, so it can't produce another line
+///
+/// The encoders wrap every argument in this.
+pub(crate) struct StripNewlines<T>(pub T);
+
+impl<T: Display> Display for StripNewlines<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        struct Replace<'a, 'b> {
+            f: &'a mut std::fmt::Formatter<'b>,
+            in_newline: bool,
+        }
+
+        impl std::fmt::Write for Replace<'_, '_> {
+            fn write_str(&mut self, s: &str) -> std::fmt::Result {
+                for (i, part) in s.split(['\r', '\n']).enumerate() {
+                    // every part after the first was preceded by a newline
+                    if i > 0 && !std::mem::replace(&mut self.in_newline, true) {
+                        self.f.write_str(" ")?;
+                    }
+                    if !part.is_empty() {
+                        self.in_newline = false;
+                        self.f.write_str(part)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        use std::fmt::Write as _;
+        write!(
+            Replace {
+                f,
+                in_newline: false
+            },
+            "{}",
+            self.0
+        )
+    }
+}
+
 /// A channel wrapper that when `.to_string()` is called will ensure a leading `#` is added.
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 pub struct Channel<'a>(pub(crate) &'a str);
@@ -232,12 +291,7 @@ impl<'a> Channel<'a> {
 
 impl<'a> Display for Channel<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // TODO IRC has special casing rules. We should implement it
-        if self.0.starts_with('#') {
-            write!(f, "{}", self.0.to_lowercase())
-        } else {
-            write!(f, "#{}", self.0.to_lowercase())
-        }
+        f.write_str(&crate::twitch::normalize_channel(self.0))
     }
 }
 
@@ -0,0 +1,83 @@
+use super::Encodable;
+use crate::twitch::Capability;
+use std::{
+    borrow::Cow,
+    io::{Result, Write},
+};
+
+/// Request capabilities from the server. This should be sent before [pass](super::pass)
+///
+/// You'll only need this if you're driving the handshake yourself, otherwise see [register](super::register)
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct CapReq<'a> {
+    pub(crate) capabilities: Cow<'a, [Capability]>,
+}
+
+/// Request capabilities from the server. This should be sent before [pass](super::pass)
+///
+/// All of the capabilities are requested in a single `CAP REQ` line.
+///
+/// # NOTE
+/// If no capabilities are provided, nothing will be written.
+pub fn cap_req(capabilities: &[Capability]) -> CapReq<'_> {
+    CapReq {
+        capabilities: Cow::Borrowed(capabilities),
+    }
+}
+
+impl<'a> Encodable for CapReq<'a> {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        if self.capabilities.is_empty() {
+            return Ok(());
+        }
+
+        write!(buf, "CAP REQ :")?;
+        for (i, cap) in self.capabilities.iter().enumerate() {
+            if i > 0 {
+                write!(buf, " ")?;
+            }
+            write!(buf, "{}", cap.as_str())?;
+        }
+        write!(buf, "\r\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn cap_req_encode() {
+        test_encode(
+            cap_req(&[Capability::Tags]),
+            "CAP REQ :twitch.tv/tags\r\n",
+        );
+
+        test_encode(
+            cap_req(&[
+                Capability::Membership,
+                Capability::Tags,
+                Capability::Commands,
+            ]),
+            "CAP REQ :twitch.tv/membership twitch.tv/tags twitch.tv/commands\r\n",
+        );
+
+        test_encode(cap_req(&[]), "");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn cap_req_serde() {
+        test_serde(
+            cap_req(&[Capability::Membership, Capability::Tags]),
+            "CAP REQ :twitch.tv/membership twitch.tv/tags\r\n",
+        );
+    }
+}
@@ -14,7 +14,25 @@ pub struct Color<'a> {
 }
 
 /// Change your username `color`.
-// TODO documentation tests to show how you can use this with different types
+///
+/// Named colors are sent by their name, anything else is sent as a `#RRGGBB` hex string.
+///
+/// # NOTE
+/// Hex colors require Turbo or Prime
+///
+/// ```rust
+/// use twitchchat::{commands::color, twitch::color::{RGB, TwitchColor}};
+///
+/// // from a string, either a name or a hex string
+/// let cmd = color("blue violet").unwrap();
+/// let cmd = color("#FF00FF").unwrap();
+///
+/// // from a named color
+/// let cmd = color(TwitchColor::Coral).unwrap();
+///
+/// // from a hex color
+/// let cmd = color(RGB(0xFF, 0x00, 0xFF)).unwrap();
+/// ```
 pub fn color<T>(color: T) -> std::result::Result<Color<'static>, T::Error>
 where
     T: TryInto<crate::twitch::Color>,
@@ -48,6 +66,31 @@ mod tests {
         )
     }
 
+    #[test]
+    fn color_encode_named() {
+        use crate::twitch::color::TwitchColor;
+        test_encode(
+            color(TwitchColor::BlueViolet).unwrap(),
+            "PRIVMSG jtv :/color BlueViolet\r\n",
+        );
+        test_encode(color("blue").unwrap(), "PRIVMSG jtv :/color Blue\r\n");
+        test_encode(
+            color("hot pink").unwrap(),
+            "PRIVMSG jtv :/color HotPink\r\n",
+        );
+    }
+
+    #[test]
+    fn color_encode_hex() {
+        use crate::twitch::color::RGB;
+        test_encode(
+            color(RGB(0x12, 0x34, 0xAB)).unwrap(),
+            "PRIVMSG jtv :/color #1234AB\r\n",
+        );
+        test_encode(color("#c0ffee").unwrap(), "PRIVMSG jtv :/color #C0FFEE\r\n");
+        assert!(color("not a color").is_err());
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn color_serde() {
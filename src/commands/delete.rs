@@ -0,0 +1,59 @@
+use super::{Channel, Encodable};
+use std::io::{Result, Write};
+
+/// Deletes a single message from the chat.
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct Delete<'a> {
+    pub(crate) channel: &'a str,
+    pub(crate) msg_id: &'a str,
+}
+
+/// Deletes a single message from the chat.
+///
+/// The `msg_id` is the `id` tag of the message to delete.
+pub const fn delete<'a>(channel: &'a str, msg_id: &'a str) -> Delete<'a> {
+    Delete { channel, msg_id }
+}
+
+impl<'a> Encodable for Delete<'a> {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        write_cmd!(buf, Channel(self.channel) => "/delete {}", self.msg_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn delete_encode() {
+        test_encode(
+            delete("#museun", "abc-123"),
+            "PRIVMSG #museun :/delete abc-123\r\n",
+        );
+    }
+
+    #[test]
+    fn delete_ensure_channel_encode() {
+        test_encode(
+            delete("museun", "abc-123"),
+            "PRIVMSG #museun :/delete abc-123\r\n",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn delete_serde() {
+        test_serde(
+            delete("#museun", "abc-123"),
+            "PRIVMSG #museun :/delete abc-123\r\n",
+        );
+    }
+}
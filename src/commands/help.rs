@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{Channel, Encodable, MaybeEmpty};
 use std::io::{Result, Write};
 
 /// Lists the commands available to you in this room.
@@ -8,11 +8,25 @@ use std::io::{Result, Write};
 #[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
 pub struct Help<'a> {
     pub(crate) channel: &'a str,
+    pub(crate) topic: Option<&'a str>,
+}
+
+impl<'a> Help<'a> {
+    /// Get help for a specific command, e.g. `ban` sends `/help ban`
+    pub const fn topic(mut self, topic: &'a str) -> Self {
+        self.topic = Some(topic);
+        self
+    }
 }
 
 /// Lists the commands available to you in this room.
+///
+/// Use [Help::topic] to get help for a specific command.
 pub const fn help(channel: &str) -> Help<'_> {
-    Help { channel }
+    Help {
+        channel,
+        topic: None,
+    }
 }
 
 impl<'a> Encodable for Help<'a> {
@@ -20,7 +34,7 @@ impl<'a> Encodable for Help<'a> {
     where
         W: Write + ?Sized,
     {
-        write_cmd!(buf, Channel(self.channel) => "/help")
+        write_cmd!(buf, Channel(self.channel) => "/help{}", MaybeEmpty(self.topic))
     }
 }
 
@@ -34,6 +48,15 @@ mod tests {
         test_encode(help("#museun"), "PRIVMSG #museun :/help\r\n");
     }
 
+    #[test]
+    fn help_topic_encode() {
+        test_encode(
+            help("#museun").topic("ban"),
+            "PRIVMSG #museun :/help ban\r\n",
+        );
+        test_encode(help("#museun").topic(""), "PRIVMSG #museun :/help\r\n");
+    }
+
     #[test]
     fn help_ensure_channel_encode() {
         test_encode(help("museun"), "PRIVMSG #museun :/help\r\n");
@@ -43,6 +66,10 @@ mod tests {
     #[cfg(feature = "serde")]
     fn help_serde() {
         test_serde(help("#museun"), "PRIVMSG #museun :/help\r\n");
+        test_serde(
+            help("#museun").topic("ban"),
+            "PRIVMSG #museun :/help ban\r\n",
+        );
     }
 
     #[test]
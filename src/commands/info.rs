@@ -0,0 +1,119 @@
+/// Information about a Twitch chat command that can be built in this module
+///
+/// This is useful for generating help text. See [all] for the list.
+///
+/// [all]: super::all()
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CommandInfo {
+    /// The name of the command, without the leading `/`. e.g. `mod`
+    pub name: &'static str,
+    /// The function in this module that builds the command. e.g. `give_mod`
+    pub function: &'static str,
+    /// A short description of the command
+    pub description: &'static str,
+    /// The names of the arguments the command takes, in order
+    pub args: &'static [&'static str],
+    /// How the command is typed in chat, with `<required>` and `[optional]` arguments
+    ///
+    /// e.g. `/ban <username> [reason]`
+    pub usage: &'static str,
+}
+
+macro_rules! command_info {
+    ($($name:literal => $function:ident [$($arg:literal)*] $usage:literal $description:literal)*) => {
+        &[$(
+            CommandInfo {
+                name: $name,
+                function: stringify!($function),
+                description: $description,
+                args: &[$($arg),*],
+                usage: $usage,
+            },
+        )*]
+    };
+}
+
+/// All of the Twitch chat commands that can be built in this module
+///
+/// This doesn't include the IRC commands (e.g. [join](super::join()) or
+/// [ping](super::ping())), only the ones that are typed in chat.
+///
+/// ```rust
+/// for info in twitchchat::commands::all() {
+///     println!("{:<40} {}", info.usage, info.description);
+/// }
+/// ```
+pub fn all() -> &'static [CommandInfo] {
+    command_info! {
+        "ban"            => ban             ["username" "reason"] "/ban <username> [reason]"                  "Permanently prevent a user from chatting."
+        "clear"          => clear           []                    "/clear"                                    "Clear chat history for all users in the channel."
+        "color"          => color           ["color"]             "/color <color>"                            "Change your username color."
+        "commercial"     => commercial      ["length"]            "/commercial [length]"                      "Triggers a commercial."
+        "delete"         => delete          ["msg_id"]            "/delete <msg_id>"                          "Deletes a single message from the chat."
+        "disconnect"     => disconnect      []                    "/disconnect"                               "Reconnects to chat."
+        "emoteonly"      => emote_only      []                    "/emoteonly"                                "Enables emote-only mode (only emoticons may be used in chat)."
+        "emoteonlyoff"   => emote_only_off  []                    "/emoteonlyoff"                             "Disables emote-only mode."
+        "followers"      => followers       ["duration"]          "/followers <duration>"                     "Enables followers-only mode (only users who have followed for `duration` may chat)."
+        "followersoff"   => followers_off   []                    "/followersoff"                             "Disables followers-only mode."
+        "help"           => help            ["command"]           "/help [command]"                           "Lists the commands available to you in this room."
+        "host"           => host            ["channel"]           "/host <channel>"                           "Host another channel."
+        "marker"         => marker          ["comment"]           "/marker [comment]"                         "Adds a stream marker (with an optional comment, max 140 characters) at the current timestamp."
+        "me"             => me              ["message"]           "/me <message>"                             "Sends an \"emote\" message in the third person to the channel."
+        "mod"            => give_mod        ["username"]          "/mod <username>"                           "Grant moderator status to a user."
+        "mods"           => mods            []                    "/mods"                                     "Lists the moderators of this channel."
+        "r9kbeta"        => r9k_beta        []                    "/r9kbeta"                                  "Enables r9k mode."
+        "r9kbetaoff"     => r9k_beta_off    []                    "/r9kbetaoff"                               "Disables r9k mode."
+        "raid"           => raid            ["channel"]           "/raid <channel>"                           "Raid another channel."
+        "slow"           => slow            ["duration"]          "/slow [duration]"                          "Enables slow mode (limit how often users may send messages)."
+        "slowoff"        => slow_off        []                    "/slowoff"                                  "Disables slow mode."
+        "subscribers"    => subscribers     []                    "/subscribers"                              "Enables subscribers-only mode (only subscribers may chat in this channel)."
+        "subscribersoff" => subscribers_off []                    "/subscribersoff"                           "Disables subscribers-only mode."
+        "timeout"        => timeout         ["username" "duration" "reason"] "/timeout <username> [duration] [reason]" "Temporarily prevent a user from chatting."
+        "unban"          => unban           ["username"]          "/unban <username>"                         "Removes a ban on a user."
+        "unhost"         => unhost          []                    "/unhost"                                   "Stop hosting another channel."
+        "unmod"          => unmod           ["username"]          "/unmod <username>"                         "Revoke moderator status from a user."
+        "unraid"         => unraid          []                    "/unraid"                                   "Cancel the raid."
+        "untimeout"      => untimeout       ["username"]          "/untimeout <username>"                     "Removes a timeout on a user."
+        "unvip"          => unvip           ["username"]          "/unvip <username>"                         "Revoke VIP status from a user."
+        "vip"            => vip             ["username"]          "/vip <username>"                           "Grant VIP status to a user."
+        "vips"           => vips            []                    "/vips"                                     "Lists the VIPs of this channel."
+        "w"              => whisper         ["username" "message"] "/w <username> <message>"                  "Whispers a message to the username."
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_commands() {
+        let all = all();
+        assert!(!all.is_empty());
+
+        for name in &["ban", "timeout", "mod", "unmod", "vip", "slow", "w"] {
+            assert!(
+                all.iter().any(|info| info.name == *name),
+                "missing {}",
+                name
+            );
+        }
+
+        let mut names = all.iter().map(|info| info.name).collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), all.len(), "duplicate names");
+
+        for info in all {
+            assert!(info.usage.starts_with(&format!("/{}", info.name)));
+            assert!(!info.description.is_empty());
+            for arg in info.args {
+                assert!(info.usage.contains(arg), "{} in {}", arg, info.usage);
+            }
+        }
+
+        let info = all.iter().find(|info| info.name == "mod").unwrap();
+        assert_eq!(info.function, "give_mod");
+        assert_eq!(info.args, &["username"]);
+    }
+}
@@ -0,0 +1,119 @@
+use super::{Channel, Encodable, StripNewlines};
+use std::io::{Result, Write};
+
+/// Join many channels. This handles prepending a leading '#' for you if you omit it.
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct JoinMany<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub(crate) channels: Vec<&'a str>,
+    pub(crate) max_per_line: usize,
+}
+
+impl<'a> JoinMany<'a> {
+    /// The default maximum number of channels sent in a single `JOIN` line.
+    pub const DEFAULT_MAX_PER_LINE: usize = 10;
+
+    /// Set the maximum number of channels sent in a single `JOIN` line.
+    ///
+    /// If there are more channels than this, multiple `JOIN` lines will be produced.
+    ///
+    /// # NOTE
+    /// A value of `0` is treated as `1`
+    pub fn max_per_line(mut self, max: usize) -> Self {
+        self.max_per_line = max.max(1);
+        self
+    }
+}
+
+/// Join many channels. This handles prepending a leading '#' for you if you omit it.
+///
+/// This will produce a single `JOIN #a,#b,#c` line, removing duplicate channels.
+///
+/// If more than [JoinMany::DEFAULT_MAX_PER_LINE] channels are provided, this
+/// will produce multiple lines. You can change this with [JoinMany::max_per_line].
+pub fn join_many<'a, I>(channels: I) -> JoinMany<'a>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut seen = std::collections::HashSet::new();
+    let channels = channels
+        .into_iter()
+        .filter(|channel| seen.insert(Channel(channel).to_string()))
+        .collect();
+
+    JoinMany {
+        channels,
+        max_per_line: JoinMany::DEFAULT_MAX_PER_LINE,
+    }
+}
+
+impl<'a> Encodable for JoinMany<'a> {
+    fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        // this can be 0 if it was deserialized rather than built
+        for chunk in self.channels.chunks(self.max_per_line.max(1)) {
+            write!(buf, "JOIN ")?;
+            for (i, channel) in chunk.iter().enumerate() {
+                if i > 0 {
+                    write!(buf, ",")?;
+                }
+                write!(buf, "{}", StripNewlines(Channel(channel)))?;
+            }
+            write!(buf, "\r\n")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn join_many_encode() {
+        test_encode(
+            join_many(vec!["#museun", "shaken_bot", "#Twitch"]),
+            "JOIN #museun,#shaken_bot,#twitch\r\n",
+        );
+    }
+
+    #[test]
+    fn join_many_dedup_encode() {
+        test_encode(
+            join_many(vec!["#museun", "museun", "MUSEUN", "#shaken_bot"]),
+            "JOIN #museun,#shaken_bot\r\n",
+        );
+    }
+
+    #[test]
+    fn join_many_split_encode() {
+        let channels = (0..12).map(|i| format!("#chan{}", i)).collect::<Vec<_>>();
+        test_encode(
+            join_many(channels.iter().map(|s| s.as_str())),
+            "JOIN #chan0,#chan1,#chan2,#chan3,#chan4,#chan5,#chan6,#chan7,#chan8,#chan9\r\n\
+             JOIN #chan10,#chan11\r\n",
+        );
+
+        test_encode(
+            join_many(vec!["a", "b", "c"]).max_per_line(2),
+            "JOIN #a,#b\r\nJOIN #c\r\n",
+        );
+        let zero = JoinMany {
+            channels: vec!["a", "b"],
+            max_per_line: 0,
+        };
+        test_encode(zero, "JOIN #a\r\nJOIN #b\r\n");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn join_many_serde() {
+        test_serde(
+            join_many(vec!["#museun", "shaken_bot"]),
+            "JOIN #museun,#shaken_bot\r\n",
+        );
+    }
+}
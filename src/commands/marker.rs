@@ -16,7 +16,7 @@ pub struct Marker<'a> {
 /// You can use markers in the Highlighter for easier editing.
 ///
 /// If the string exceeds 140 characters then it will be truncated
-pub fn marker<'a>(channel: &'a str, comment: impl Into<Option<&'a str>>) -> Marker<'_> {
+pub fn marker<'a>(channel: &'a str, comment: impl Into<Option<&'a str>>) -> Marker<'a> {
     Marker {
         channel,
         comment: comment.into(),
@@ -25,19 +25,10 @@ pub fn marker<'a>(channel: &'a str, comment: impl Into<Option<&'a str>>) -> Mark
 
 impl<'a> Encodable for Marker<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        // the limit is in characters, not bytes
         fn truncate(s: &str) -> &str {
             const MAX: usize = 140;
-            if s.len() <= MAX {
-                return s;
-            }
-
-            for n in (0..=MAX).rev() {
-                if s.is_char_boundary(n) {
-                    return &s[..n];
-                }
-            }
-
-            ""
+            s.char_indices().nth(MAX).map_or(s, |(n, _)| &s[..n])
         }
 
         write_cmd!(buf, Channel(self.channel) => "/marker{}", MaybeEmpty(self.comment.map(truncate)))
@@ -66,6 +57,19 @@ mod tests {
         test_encode(marker("#museun", None), "PRIVMSG #museun :/marker\r\n");
     }
 
+    #[test]
+    fn marker_verbatim_encode() {
+        test_encode(
+            marker("#museun", "  that was a  clutch play: 1v4!  "),
+            "PRIVMSG #museun :/marker   that was a  clutch play: 1v4!  \r\n",
+        );
+        // 140 characters, not bytes
+        test_encode(
+            marker("#museun", "é".repeat(200).as_str()),
+            format!("PRIVMSG #museun :/marker {}\r\n", "é".repeat(140)),
+        );
+    }
+
     #[test]
     fn marker_ensure_channel_encode() {
         test_encode(
@@ -12,6 +12,10 @@ pub struct Me<'a> {
 }
 
 /// Sends an "emote" message in the third person to the channel
+///
+/// This is sent as a CTCP action (`\x01ACTION msg\x01`), which is what
+/// [Privmsg::is_action](crate::messages::Privmsg::is_action) detects.
+/// A leading `/me ` is removed from the `msg`.
 pub const fn me<'a>(channel: &'a str, msg: &'a str) -> Me<'a> {
     Me { channel, msg }
 }
@@ -21,7 +25,8 @@ impl<'a> Encodable for Me<'a> {
     where
         W: Write + ?Sized,
     {
-        write_cmd!(buf, Channel(self.channel) => "/me {}", self.msg)
+        let msg = self.msg.strip_prefix("/me ").unwrap_or(self.msg);
+        write_cmd!(buf, Channel(self.channel) => "\x01ACTION {}\x01", msg)
     }
 }
 
@@ -34,15 +39,41 @@ mod tests {
     fn me_encode() {
         test_encode(
             me("#museun", "some emote"),
-            "PRIVMSG #museun :/me some emote\r\n",
+            "PRIVMSG #museun :\x01ACTION some emote\x01\r\n",
         );
     }
 
+    #[test]
+    fn me_strip_encode() {
+        test_encode(
+            me("#museun", "/me some emote"),
+            "PRIVMSG #museun :\x01ACTION some emote\x01\r\n",
+        );
+        test_encode(
+            me("#museun", "some\r\nemote"),
+            "PRIVMSG #museun :\x01ACTION some emote\x01\r\n",
+        );
+    }
+
+    #[test]
+    fn me_is_action() {
+        use crate::{messages::Privmsg, FromIrcMessage as _, IrcMessage, MaybeOwned};
+
+        let mut buf = String::from(":museun!museun@museun.tmi.twitch.tv ");
+        me("Museun", "waves").encode_str(&mut buf).unwrap();
+
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(&buf)).unwrap();
+        let msg = Privmsg::from_irc(msg).unwrap();
+        assert_eq!(msg.channel(), "#museun");
+        assert!(msg.is_action());
+        assert_eq!(msg.data(), "waves");
+    }
+
     #[test]
     fn me_ensure_channel_encode() {
         test_encode(
             me("museun", "some emote"),
-            "PRIVMSG #museun :/me some emote\r\n",
+            "PRIVMSG #museun :\x01ACTION some emote\x01\r\n",
         );
     }
 
@@ -51,7 +82,7 @@ mod tests {
     fn me_serde() {
         test_serde(
             me("#museun", "some emote"),
-            "PRIVMSG #museun :/me some emote\r\n",
+            "PRIVMSG #museun :\x01ACTION some emote\x01\r\n",
         );
     }
 
@@ -60,7 +91,7 @@ mod tests {
     fn me_ensure_channel_serde() {
         test_serde(
             me("museun", "some emote"),
-            "PRIVMSG #museun :/me some emote\r\n",
+            "PRIVMSG #museun :\x01ACTION some emote\x01\r\n",
         );
     }
 }
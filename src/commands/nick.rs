@@ -0,0 +1,44 @@
+use super::Encodable;
+use std::io::{Result, Write};
+
+/// Send your name to the server. This should be sent after [pass](super::pass)
+///
+/// You'll only need this if you're driving the handshake yourself, otherwise see [register](super::register)
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct Nick<'a> {
+    pub(crate) name: &'a str,
+}
+
+/// Send your name to the server. This should be sent after [pass](super::pass)
+pub const fn nick(name: &str) -> Nick<'_> {
+    Nick { name }
+}
+
+impl<'a> Encodable for Nick<'a> {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        write_nl!(buf, "NICK {}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn nick_encode() {
+        test_encode(nick("museun"), "NICK museun\r\n");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn nick_serde() {
+        test_serde(nick("museun"), "NICK museun\r\n");
+    }
+}
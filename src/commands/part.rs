@@ -17,7 +17,7 @@ pub const fn part(channel: &str) -> Part<'_> {
 
 impl<'a> Encodable for Part<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
-        write!(buf, "PART {}\r\n", super::Channel(self.channel))
+        write_nl!(buf, "PART {}", super::Channel(self.channel))
     }
 }
 
@@ -0,0 +1,54 @@
+use super::Encodable;
+use std::io::{Result, Write};
+
+/// Send your OAuth token to the server. This is the first step of the handshake.
+///
+/// You'll only need this if you're driving the handshake yourself, otherwise see [register](super::register)
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct Pass<'a> {
+    pub(crate) token: &'a str,
+}
+
+impl<'a> std::fmt::Debug for Pass<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pass").field("token", &"oauth:***").finish()
+    }
+}
+
+/// Send your OAuth token to the server. This is the first step of the handshake.
+///
+/// The token should be in the form of `oauth:<token>`
+pub const fn pass(token: &str) -> Pass<'_> {
+    Pass { token }
+}
+
+impl<'a> Encodable for Pass<'a> {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        write_nl!(buf, "PASS {}", self.token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn pass_encode() {
+        let token = format!("oauth:{}", "a".repeat(30));
+        test_encode(pass(&token), format!("PASS {}\r\n", token));
+        assert!(!format!("{:?}", pass(&token)).contains(&token));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn pass_serde() {
+        test_serde(pass("justinfan1234"), "PASS justinfan1234\r\n");
+    }
+}
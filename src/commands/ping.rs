@@ -20,7 +20,7 @@ impl<'a> Encodable for Ping<'a> {
     where
         W: Write + ?Sized,
     {
-        write_nl!(buf, "PING {}", self.token)
+        write_nl!(buf, "PING :{}", self.token)
     }
 }
 
@@ -31,12 +31,25 @@ mod tests {
 
     #[test]
     fn ping_encode() {
-        test_encode(ping("123456789"), "PING 123456789\r\n");
+        test_encode(ping("123456789"), "PING :123456789\r\n");
+    }
+
+    #[test]
+    fn ping_round_trip() {
+        use crate::{messages, FromIrcMessage as _, IrcMessage, MaybeOwned};
+
+        let mut data = vec![];
+        ping("1234567890").encode(&mut data).unwrap();
+        let data = std::str::from_utf8(&data).unwrap();
+
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(data)).unwrap();
+        let msg = messages::Ping::from_irc(msg).unwrap();
+        assert_eq!(msg.token(), "1234567890");
     }
 
     #[test]
     #[cfg(feature = "serde")]
     fn ping_serde() {
-        test_serde(ping("123456789"), "PING 123456789\r\n");
+        test_serde(ping("123456789"), "PING :123456789\r\n");
     }
 }
@@ -34,6 +34,25 @@ mod tests {
         test_encode(pong("123456789"), "PONG :123456789\r\n");
     }
 
+    #[test]
+    fn pong_round_trip() {
+        use crate::{messages, FromIrcMessage as _, IrcMessage, MaybeOwned};
+
+        let input = "PING :1234567890\r\n";
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        let ping = messages::Ping::from_irc(msg).unwrap();
+
+        // echo the token back
+        let mut data = vec![];
+        pong(ping.token()).encode(&mut data).unwrap();
+        let data = std::str::from_utf8(&data).unwrap();
+        assert_eq!(data, "PONG :1234567890\r\n");
+
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(data)).unwrap();
+        let msg = messages::Pong::from_irc(msg).unwrap();
+        assert_eq!(msg.token(), ping.token());
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn pong_serde() {
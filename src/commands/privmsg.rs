@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{Channel, Encodable, InvalidRawLine};
 use std::io::{Result, Write};
 
 /// Send a normal message to a channel
@@ -12,16 +12,39 @@ pub struct Privmsg<'a> {
 }
 
 /// Send a normal message to a channel
+///
+/// Any `\r` or `\n` in the message are removed when its encoded, so untrusted
+/// input can't be used to send another command. Use [try_privmsg] if you'd rather reject them.
 pub const fn privmsg<'a>(channel: &'a str, msg: &'a str) -> Privmsg<'a> {
     Privmsg { channel, msg }
 }
 
+/// Send a normal message to a channel, rejecting it if the message contains a `\r` or `\n`
+///
+/// ```rust
+/// use twitchchat::commands::try_privmsg;
+///
+/// assert!(try_privmsg("#museun", "hello world").is_ok());
+/// assert!(try_privmsg("#museun", "hello\r\nJOIN #evil").is_err());
+/// ```
+pub fn try_privmsg<'a>(
+    channel: &'a str,
+    msg: &'a str,
+) -> std::result::Result<Privmsg<'a>, InvalidRawLine> {
+    InvalidRawLine::check(msg).map(|_| privmsg(channel, msg))
+}
+
 impl<'a> Encodable for Privmsg<'a> {
     fn encode<W>(&self, buf: &mut W) -> Result<()>
     where
         W: Write + ?Sized,
     {
-        write_nl!(buf, "PRIVMSG {} :{}", Channel(self.channel), self.msg)
+        write_nl!(
+            buf,
+            "PRIVMSG {} :{}",
+            Channel(self.channel),
+            self.msg
+        )
     }
 }
 
@@ -43,6 +66,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn privmsg_strips_newlines() {
+        test_encode(
+            privmsg("#museun", "hello\r\nJOIN #evil"),
+            "PRIVMSG #museun :hello JOIN #evil\r\n",
+        );
+        test_encode(
+            privmsg("#museun", "\nhello\rworld\n"),
+            "PRIVMSG #museun : hello world \r\n",
+        );
+
+        let mut data = vec![];
+        privmsg("#museun", "hello\r\nJOIN #evil\r\n")
+            .encode(&mut data)
+            .unwrap();
+        assert_eq!(crate::irc::parse(std::str::from_utf8(&data).unwrap()).count(), 1);
+    }
+
+    #[test]
+    fn try_privmsg_encode() {
+        test_encode(
+            try_privmsg("#museun", "this is a test of a line").unwrap(),
+            "PRIVMSG #museun :this is a test of a line\r\n",
+        );
+
+        assert_eq!(
+            try_privmsg("#museun", "hello\r\nJOIN #evil").unwrap_err(),
+            InvalidRawLine::EmbeddedNewline { position: 5 }
+        );
+    }
+
     #[test]
     fn privmsg_ensure_channel_encode() {
         test_encode(
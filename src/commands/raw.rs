@@ -11,6 +11,8 @@ pub struct Raw<'a> {
 }
 
 /// Send a raw IRC-style message. This appends a `\r\n` for you.
+///
+/// This isn't validated, if the message may contain untrusted input use [raw_line](super::raw_line) instead.
 pub const fn raw(data: &str) -> Raw<'_> {
     Raw { data }
 }
@@ -20,7 +22,8 @@ impl<'a> Encodable for Raw<'a> {
     where
         W: Write + ?Sized,
     {
-        write_nl!(buf, "{}", self.data)
+        // this is the escape hatch, so newlines are left alone
+        write!(buf, "{}\r\n", self.data)
     }
 }
 
@@ -0,0 +1,103 @@
+use super::Encodable;
+use std::io::{Result, Write};
+
+/// Send a single, validated, raw IRC-style line
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct RawLine<'a> {
+    pub(crate) data: &'a str,
+}
+
+/// An error returned by [raw_line] (or [try_privmsg](super::try_privmsg)) when the line would be unsafe to send
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidRawLine {
+    /// The line contained a `\r` or `\n` at this byte offset
+    EmbeddedNewline {
+        /// The byte offset of the newline
+        position: usize,
+    },
+}
+
+impl std::fmt::Display for InvalidRawLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmbeddedNewline { position } => {
+                write!(f, "line contains a newline at position {}", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvalidRawLine {}
+
+impl InvalidRawLine {
+    pub(crate) fn check(data: &str) -> std::result::Result<(), Self> {
+        match data.find(['\r', '\n']) {
+            Some(position) => Err(Self::EmbeddedNewline { position }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Send a single raw IRC-style line. This appends a `\r\n` for you.
+///
+/// Unlike [raw](super::raw), this rejects any line that contains a `\r` or `\n`.
+/// This prevents someone from injecting extra commands when the line contains
+/// untrusted input, such as text provided by another user.
+///
+/// ```rust
+/// use twitchchat::commands::raw_line;
+///
+/// assert!(raw_line("PRIVMSG #museun :hello world").is_ok());
+/// assert!(raw_line("PRIVMSG #museun :hello\r\nPART #museun").is_err());
+/// ```
+pub fn raw_line(data: &str) -> std::result::Result<RawLine<'_>, InvalidRawLine> {
+    InvalidRawLine::check(data).map(|_| RawLine { data })
+}
+
+impl<'a> Encodable for RawLine<'a> {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        write_nl!(buf, "{}", self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn raw_line_encode() {
+        test_encode(
+            raw_line("PRIVMSG #test :this is a test").unwrap(),
+            "PRIVMSG #test :this is a test\r\n",
+        );
+    }
+
+    #[test]
+    fn raw_line_rejects_newlines() {
+        assert_eq!(
+            raw_line("PRIVMSG #test :hello\nQUIT").unwrap_err(),
+            InvalidRawLine::EmbeddedNewline { position: 20 }
+        );
+        assert_eq!(
+            raw_line("PRIVMSG #test :hello\r\n").unwrap_err(),
+            InvalidRawLine::EmbeddedNewline { position: 20 }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn raw_line_serde() {
+        test_serde(
+            raw_line("PRIVMSG #test :this is a test").unwrap(),
+            "PRIVMSG #test :this is a test\r\n",
+        );
+    }
+}
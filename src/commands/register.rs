@@ -1,4 +1,8 @@
-use super::Encodable;
+use super::{
+    cap_req, nick, pass,
+    types::{CapReq, Nick, Pass},
+    Encodable,
+};
 use crate::twitch::UserConfig;
 
 use std::io::Write;
@@ -17,6 +21,8 @@ pub struct Register<'a> {
 /// Write the User Registration to the connection.
 ///
 /// This is required to be done before you do anything else.
+///
+/// All of the capabilities are requested in a single `CAP REQ` line.
 pub fn register(user_config: &UserConfig) -> Register<'_> {
     // TODO serde really doesn't like this type, so lets clone it
     let user_config = user_config.clone();
@@ -28,24 +34,64 @@ pub fn register(user_config: &UserConfig) -> Register<'_> {
 
 impl<'a> Encodable for Register<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> std::io::Result<()> {
-        let UserConfig {
-            name,
-            token,
-            capabilities,
-        } = &self.user_config;
-
-        // the caps have to be written first
-        for cap in capabilities {
-            let cap = cap.encode_as_str();
-            write!(buf, "{}\r\n", cap)?;
+        for cmd in self.user_config.handshake_commands() {
+            cmd.encode(buf)?;
         }
-
-        write!(buf, "PASS {}\r\n", token)?;
-        write!(buf, "NICK {}\r\n", name)?;
         buf.flush()
     }
 }
 
+/// A single step of the handshake, produced by [UserConfig::handshake_commands]
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandshakeCommand<'a> {
+    /// Request the capabilities
+    CapReq(CapReq<'a>),
+    /// Send the OAuth token
+    Pass(Pass<'a>),
+    /// Send the name
+    Nick(Nick<'a>),
+}
+
+impl<'a> Encodable for HandshakeCommand<'a> {
+    fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> std::io::Result<()> {
+        match self {
+            Self::CapReq(cmd) => cmd.encode(buf),
+            Self::Pass(cmd) => cmd.encode(buf),
+            Self::Nick(cmd) => cmd.encode(buf),
+        }
+    }
+}
+
+impl UserConfig {
+    /// Get the commands needed to register with Twitch, in the order they should be sent
+    ///
+    /// This is what [register] writes, so you can drive the handshake yourself.
+    ///
+    /// If there are no capabilities, no `CAP REQ` is produced.
+    ///
+    /// ```rust
+    /// use twitchchat::{commands::HandshakeCommand, UserConfig};
+    ///
+    /// let config = UserConfig::builder().anonymous().build().unwrap();
+    /// let mut iter = config.handshake_commands();
+    /// assert!(matches!(iter.next(), Some(HandshakeCommand::Pass(..))));
+    /// assert!(matches!(iter.next(), Some(HandshakeCommand::Nick(..))));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn handshake_commands(&self) -> impl Iterator<Item = HandshakeCommand<'_>> + '_ {
+        // the caps have to be written first. Twitch lets us request them all at once
+        let caps = Some(&self.capabilities)
+            .filter(|caps| !caps.is_empty())
+            .map(|caps| HandshakeCommand::CapReq(cap_req(caps)));
+
+        caps.into_iter()
+            .chain(std::iter::once(HandshakeCommand::Pass(pass(&self.token))))
+            .chain(std::iter::once(HandshakeCommand::Nick(nick(&self.name))))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
@@ -61,9 +107,7 @@ mod tests {
 
         test_encode(
             register(&config),
-            "CAP REQ :twitch.tv/membership\r\n\
-            CAP REQ :twitch.tv/tags\r\n\
-            CAP REQ :twitch.tv/commands\r\n\
+            "CAP REQ :twitch.tv/membership twitch.tv/tags twitch.tv/commands\r\n\
             PASS justinfan1234\r\n\
             NICK justinfan1234\r\n",
         )
@@ -80,11 +124,45 @@ mod tests {
 
         test_serde(
             register(&config),
-            "CAP REQ :twitch.tv/membership\r\n\
-            CAP REQ :twitch.tv/tags\r\n\
-            CAP REQ :twitch.tv/commands\r\n\
+            "CAP REQ :twitch.tv/membership twitch.tv/tags twitch.tv/commands\r\n\
             PASS justinfan1234\r\n\
             NICK justinfan1234\r\n",
         )
     }
+
+    #[test]
+    fn handshake_commands() {
+        fn encode_all(config: &UserConfig) -> Vec<String> {
+            config
+                .handshake_commands()
+                .map(|cmd| {
+                    let mut out = String::new();
+                    cmd.encode_str(&mut out).unwrap();
+                    out
+                })
+                .collect()
+        }
+
+        let config = UserConfig::builder().anonymous().build().unwrap();
+        assert_eq!(
+            encode_all(&config),
+            vec!["PASS justinfan1234\r\n", "NICK justinfan1234\r\n"]
+        );
+
+        let token = format!("oauth:{}", "a".repeat(30));
+        let config = UserConfig::builder()
+            .name("museun")
+            .token(&token)
+            .enable_all_capabilities()
+            .build()
+            .unwrap();
+        assert_eq!(
+            encode_all(&config),
+            vec![
+                "CAP REQ :twitch.tv/membership twitch.tv/tags twitch.tv/commands\r\n".to_string(),
+                format!("PASS {}\r\n", token),
+                "NICK museun\r\n".to_string(),
+            ]
+        );
+    }
 }
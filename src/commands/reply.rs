@@ -80,6 +80,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reply_newline_injection() {
+        test_encode(
+            reply("#museun\r\nJOIN #evil", TEST_UUID, "hello\r\nJOIN #evil"),
+            format!(
+                "@reply-parent-msg-id={} PRIVMSG #museun join #evil :hello JOIN #evil\r\n",
+                TEST_UUID
+            ),
+        );
+
+        let mut data = vec![];
+        reply("#museun", "id\nQUIT", "hello\nPART #museun\n")
+            .encode(&mut data)
+            .unwrap();
+        assert_eq!(crate::irc::parse(std::str::from_utf8(&data).unwrap()).count(), 1);
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn reply_serde() {
@@ -12,6 +12,28 @@ pub struct Whisper<'a> {
 }
 
 /// Whispers a message to the username.
+///
+/// This is sent as `PRIVMSG jtv :/w username message`
+///
+/// # Rate limits
+/// Whispers have their own, much stricter, limits than normal messages:
+/// * `3` per second
+/// * `100` per minute
+/// * `40` distinct accounts per day
+///
+/// These aren't tracked by the runner, so you'll have to track them yourself. e.g.
+/// ```rust
+/// use twitchchat::{commands, rate_limit::RateLimit};
+/// use std::time::Duration;
+///
+/// let mut per_second = RateLimit::full(3, Duration::from_secs(1));
+/// let mut per_minute = RateLimit::full(100, Duration::from_secs(60));
+///
+/// if per_second.consume(1).is_ok() && per_minute.consume(1).is_ok() {
+///     let whisper = commands::whisper("museun", "hello there");
+///     // encode it
+/// }
+/// ```
 pub const fn whisper<'a>(username: &'a str, message: &'a str) -> Whisper<'a> {
     Whisper { username, message }
 }
@@ -35,7 +57,29 @@ mod tests {
         test_encode(
             whisper("museun", "hello world"),
             "PRIVMSG jtv :/w museun hello world\r\n",
-        )
+        );
+        test_encode(
+            whisper("museun", "hello"),
+            "PRIVMSG jtv :/w museun hello\r\n",
+        );
+        test_encode(
+            whisper("museun", "this is a longer message, with some punctuation!"),
+            "PRIVMSG jtv :/w museun this is a longer message, with some punctuation!\r\n",
+        );
+    }
+
+    #[test]
+    fn whisper_newline_injection() {
+        test_encode(
+            whisper("museun\r\nJOIN #evil", "hello\r\nJOIN #evil"),
+            "PRIVMSG jtv :/w museun JOIN #evil hello JOIN #evil\r\n",
+        );
+
+        let mut data = vec![];
+        whisper("museun\n", "hello\nPART #museun\n")
+            .encode(&mut data)
+            .unwrap();
+        assert_eq!(crate::irc::parse(std::str::from_utf8(&data).unwrap()).count(), 1);
     }
 
     #[test]
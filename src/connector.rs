@@ -10,6 +10,7 @@
 //! | [`smol`](https://docs.rs/smol/latest/smol/)                |`smol`                   |
 //! | [`async_std`](https://docs.rs/async-std/latest/async_std/) |`async-std`              |
 //! | [`tokio`](https://docs.rs/tokio/0.2/tokio/)                |`tokio` and `tokio-util` |
+//! | [`web_sys::WebSocket`](https://docs.rs/web-sys/latest/web_sys/struct.WebSocket.html) |`wasm` |
 //!
 //! ## TLS
 //!
@@ -54,7 +55,7 @@ macro_rules! connector_ctor {
         }
     };
 
-    (tls: $(#[$meta:meta])*) => {
+    (tls: $(#[$meta:meta])* $($field:ident: $default:expr),* $(,)?) => {
         #[doc = "Create a new"]
         $(#[$meta])*
         #[doc = "TLS connector that connects to the ***default Twitch*** address."]
@@ -62,10 +63,13 @@ macro_rules! connector_ctor {
             Self::custom($crate::TWITCH_IRC_ADDRESS_TLS, $crate::TWITCH_TLS_DOMAIN)
         }
 
-
         #[doc = "Create a new"]
         $(#[$meta])*
         #[doc = "TLS connector with a custom address and TLS domain."]
+        #[doc = ""]
+        #[doc = "The socket connects to `addrs`, while `domain` is only used for SNI and"]
+        #[doc = "certificate validation. This lets you connect to a local proxy while still"]
+        #[doc = "presenting (and validating against) another domain."]
         pub fn custom<A, D>(addrs: A, domain: D) -> ::std::io::Result<Self>
         where
             A: ::std::net::ToSocketAddrs,
@@ -75,8 +79,19 @@ macro_rules! connector_ctor {
             addrs.to_socket_addrs().map(|addrs| Self {
                 addrs: addrs.collect(),
                 tls_domain,
+                $($field: $default,)*
             })
         }
+
+        /// The addresses the socket will try to connect to
+        pub fn addrs(&self) -> &[::std::net::SocketAddr] {
+            &self.addrs
+        }
+
+        /// The domain used for SNI and certificate validation
+        pub fn tls_domain(&self) -> &str {
+            &self.tls_domain
+        }
     };
 }
 
@@ -151,6 +166,14 @@ pub use self::tokio::ConnectorNativeTls as TokioConnectorNativeTls;
 #[doc(inline)]
 pub use self::tokio::ConnectorOpenSsl as TokioConnectorOpenSsl;
 
+#[cfg(feature = "wasm")]
+/// Connector for using the browser's [`WebSocket`](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket), through [`web_sys`](https://docs.rs/web-sys/latest/web_sys/)
+pub mod wasm;
+
+#[cfg(feature = "wasm")]
+#[doc(inline)]
+pub use self::wasm::Connector as WasmConnector;
+
 /// The connector trait. This is used to abstract out runtimes.
 ///
 /// You can implement this on your own type to provide a custom connection behavior.
@@ -165,10 +188,145 @@ pub trait Connector: Send + Sync + Clone {
     fn connect(&mut self) -> crate::BoxedFuture<IoResult<Self::Output>>;
 }
 
-// This is used because smol/async_io uses an indv. SocketAddr for their connect
-// instead of the normal ToSocketAddrs trait
+/// An error produced when the TLS handshake failed, after the TCP connection was made
+///
+/// The TLS connectors wrap their handshake errors in this, so they can be told
+/// apart from connection errors. See [is_tls_handshake_error].
+#[derive(Debug)]
+pub struct TlsHandshakeError {
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl std::fmt::Display for TlsHandshakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "tls handshake failed: {}", self.source)
+    }
+}
+
+impl std::error::Error for TlsHandshakeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+impl TlsHandshakeError {
+    /// Wrap this error as a TLS handshake error
+    pub fn wrap(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> std::io::Error {
+        std::io::Error::other(Self { source: err.into() })
+    }
+}
+
+/// Checks whether this error was produced by a failed TLS handshake
+pub fn is_tls_handshake_error(err: &std::io::Error) -> bool {
+    err.get_ref()
+        .is_some_and(|err| err.is::<TlsHandshakeError>())
+}
+
+// the async-tls connectors keep their (optional) custom config in this
 //
-// thus this will be dead if those features aren't enabled.
+// the async_tls::TlsConnector is neither Debug nor PartialEq
+#[cfg(feature = "async-tls")]
+#[derive(Clone, Default)]
+struct AsyncTlsConfig(Option<std::sync::Arc<async_tls::TlsConnector>>);
+
+#[cfg(feature = "async-tls")]
+impl AsyncTlsConfig {
+    fn new(connector: impl Into<async_tls::TlsConnector>) -> Self {
+        Self(Some(std::sync::Arc::new(connector.into())))
+    }
+
+    // this trusts the webpki-roots unless a config was provided
+    fn connector(&self) -> async_tls::TlsConnector {
+        self.0
+            .as_deref()
+            .cloned()
+            .unwrap_or_else(async_tls::TlsConnector::new)
+    }
+}
+
+#[cfg(feature = "async-tls")]
+impl std::fmt::Debug for AsyncTlsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(..) => f.write_str("Custom"),
+            None => f.write_str("WebpkiRoots"),
+        }
+    }
+}
+
+#[cfg(feature = "async-tls")]
+impl PartialEq for AsyncTlsConfig {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(left), Some(right)) => std::sync::Arc::ptr_eq(left, right),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The path taken by [connect_with_fallback]
+#[derive(Debug)]
+pub enum Fallback<T, P> {
+    /// The TLS connection succeeded
+    Tls(T),
+    /// The TLS handshake failed, so a plaintext connection was made instead
+    Plain {
+        /// The plaintext stream
+        stream: P,
+        /// Why the TLS handshake failed
+        tls_error: std::io::Error,
+    },
+}
+
+impl<T, P> Fallback<T, P> {
+    /// Whether the connection was downgraded to plaintext
+    pub fn is_downgraded(&self) -> bool {
+        matches!(self, Self::Plain { .. })
+    }
+}
+
+/// Try to connect with TLS, falling back to plaintext if the TLS handshake fails
+///
+/// **This is for diagnosing TLS issues**, (e.g. an intercepting proxy or old
+/// root certificates). It should not be used in production, as the connection
+/// (and your OAuth token) will be sent in the clear if it falls back.
+///
+/// The fallback only happens if the TCP connection was made but the TLS
+/// handshake failed (see [is_tls_handshake_error]). Any other error is
+/// returned as-is. A warning is logged when it falls back.
+pub async fn connect_with_fallback<T, P>(
+    tls: &mut T,
+    plain: &mut P,
+) -> IoResult<Fallback<T::Output, P::Output>>
+where
+    T: Connector,
+    P: Connector,
+{
+    let tls_error = match tls.connect().await {
+        Ok(stream) => return Ok(Fallback::Tls(stream)),
+        Err(err) if is_tls_handshake_error(&err) => err,
+        Err(err) => return Err(err),
+    };
+
+    log::warn!(
+        "{}. falling back to an insecure plaintext connection",
+        tls_error
+    );
+
+    let stream = plain.connect().await?;
+    Ok(Fallback::Plain { stream, tls_error })
+}
+
+/// How long an address gets to connect before the next one is tried in parallel
+///
+/// This is the 'Connection Attempt Delay' from [RFC 8305](https://tools.ietf.org/html/rfc8305#section-5)
+pub const CONNECTION_ATTEMPT_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+// This is used by all of the connectors so a dead address (e.g. an unreachable
+// IPv6 route) doesn't stall the connection.
+//
+// thus this will be dead if none of the runtime features are enabled.
 #[allow(dead_code)]
 async fn try_connect<F, T, R>(addrs: &[SocketAddr], connect: F) -> IoResult<T>
 where
@@ -176,22 +334,83 @@ where
     R: Future<Output = IoResult<T>> + Send,
     T: Send,
 {
-    let mut last = None;
-    for addr in addrs {
-        let fut = connect(*addr);
-        match fut.await {
-            Ok(socket) => return Ok(socket),
-            Err(err) => last.replace(err),
-        };
-    }
+    happy_eyeballs(addrs, CONNECTION_ATTEMPT_DELAY, connect).await
+}
+
+// A 'happy eyeballs' connect (RFC 8305)
+//
+// The addresses are interleaved by family (preferring IPv6), and each attempt
+// gets `delay` to connect before the next one is started alongside it. If an
+// attempt fails the next one is started right away. The first to connect wins
+// and the rest are dropped.
+async fn happy_eyeballs<F, T, R>(
+    addrs: &[SocketAddr],
+    delay: std::time::Duration,
+    connect: F,
+) -> IoResult<T>
+where
+    F: Fn(SocketAddr) -> R + Send,
+    R: Future<Output = IoResult<T>> + Send,
+    T: Send,
+{
+    use std::{collections::VecDeque, pin::Pin, task::Poll};
+
+    let (mut v6, mut v4): (VecDeque<_>, VecDeque<_>) =
+        addrs.iter().copied().partition(SocketAddr::is_ipv6);
 
-    match last {
-        Some(last) => Err(last),
-        None => Err(std::io::Error::new(
-            std::io::ErrorKind::ConnectionRefused,
-            "cannot connect with any provided address",
-        )),
+    let mut pending = VecDeque::with_capacity(addrs.len());
+    while !v6.is_empty() || !v4.is_empty() {
+        pending.extend(v6.pop_front());
+        pending.extend(v4.pop_front());
     }
+
+    let mut running: Vec<Pin<Box<R>>> = Vec::with_capacity(addrs.len());
+    let mut timer = futures_timer::Delay::new(delay);
+    let mut start_next = true;
+    let mut last = None;
+
+    futures_lite::future::poll_fn(move |ctx| loop {
+        // the current attempts have had their head start (or one of them failed)
+        if start_next || Pin::new(&mut timer).poll(ctx).is_ready() {
+            start_next = false;
+            if let Some(addr) = pending.pop_front() {
+                log::trace!("trying to connect to {}", addr);
+                running.push(Box::pin(connect(addr)));
+                timer.reset(delay);
+                // poll the new attempt (and register the timer)
+                continue;
+            }
+        }
+
+        let mut i = 0;
+        while i < running.len() {
+            match running[i].as_mut().poll(ctx) {
+                Poll::Ready(Ok(socket)) => return Poll::Ready(Ok(socket)),
+                Poll::Ready(Err(err)) => {
+                    drop(running.swap_remove(i));
+                    last.replace(err);
+                    start_next = true;
+                }
+                Poll::Pending => i += 1,
+            }
+        }
+
+        if running.is_empty() && pending.is_empty() {
+            return Poll::Ready(Err(last.take().unwrap_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::ConnectionRefused,
+                    "cannot connect with any provided address",
+                )
+            })));
+        }
+
+        if start_next && !pending.is_empty() {
+            continue;
+        }
+
+        return Poll::Pending;
+    })
+    .await
 }
 
 mod required {
@@ -233,6 +452,156 @@ mod required {
     }
 }
 
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::test::{TestConn, TestConnector};
+
+    #[derive(Clone)]
+    struct Failing(fn() -> std::io::Error);
+
+    impl Connector for Failing {
+        type Output = TestConn;
+
+        fn connect(&mut self) -> crate::BoxedFuture<IoResult<Self::Output>> {
+            let err = (self.0)();
+            Box::pin(async move { Err(err) })
+        }
+    }
+
+    type Attempt = crate::BoxedFuture<IoResult<SocketAddr>>;
+
+    // each address connects after this many milliseconds, or fails if it's 0
+    fn scripted(script: &'static [(&'static str, u64)]) -> impl Fn(SocketAddr) -> Attempt {
+        move |addr| {
+            let (_, ms) = *script
+                .iter()
+                .find(|(s, _)| s.parse::<SocketAddr>().unwrap() == addr)
+                .unwrap();
+            Box::pin(async move {
+                match ms {
+                    0 => Err(std::io::ErrorKind::ConnectionRefused.into()),
+                    u64::MAX => futures_lite::future::pending().await,
+                    ms => {
+                        futures_timer::Delay::new(std::time::Duration::from_millis(ms)).await;
+                        Ok(addr)
+                    }
+                }
+            })
+        }
+    }
+
+    fn addrs(script: &[(&str, u64)]) -> Vec<SocketAddr> {
+        script.iter().map(|(s, _)| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn happy_eyeballs_races_a_slow_address() {
+        // the IPv6 address is a sink, it never connects
+        const SCRIPT: &[(&str, u64)] = &[("[::1]:6667", u64::MAX), ("127.0.0.1:6667", 1)];
+        let delay = std::time::Duration::from_millis(20);
+
+        let start = std::time::Instant::now();
+        let addr = futures_lite::future::block_on(happy_eyeballs(
+            &addrs(SCRIPT),
+            delay,
+            scripted(SCRIPT),
+        ))
+        .unwrap();
+        assert_eq!(addr, "127.0.0.1:6667".parse().unwrap());
+        assert!(start.elapsed() >= delay);
+    }
+
+    #[test]
+    fn happy_eyeballs_prefers_ipv6() {
+        const SCRIPT: &[(&str, u64)] = &[("127.0.0.1:6667", 5), ("[::1]:6667", 5)];
+        let delay = std::time::Duration::from_secs(60);
+
+        let addr = futures_lite::future::block_on(happy_eyeballs(
+            &addrs(SCRIPT),
+            delay,
+            scripted(SCRIPT),
+        ))
+        .unwrap();
+        assert_eq!(addr, "[::1]:6667".parse().unwrap());
+    }
+
+    #[test]
+    fn happy_eyeballs_skips_failures() {
+        // a failed attempt starts the next one without waiting for the delay
+        const SCRIPT: &[(&str, u64)] = &[
+            ("[::1]:6667", 0),
+            ("127.0.0.1:6667", 0),
+            ("[::2]:6667", 1),
+        ];
+        let delay = std::time::Duration::from_secs(60);
+
+        let addr = futures_lite::future::block_on(happy_eyeballs(
+            &addrs(SCRIPT),
+            delay,
+            scripted(SCRIPT),
+        ))
+        .unwrap();
+        assert_eq!(addr, "[::2]:6667".parse().unwrap());
+
+        const FAILING: &[(&str, u64)] = &[("[::1]:6667", 0), ("127.0.0.1:6667", 0)];
+        let err = futures_lite::future::block_on(happy_eyeballs(
+            &addrs(FAILING),
+            delay,
+            scripted(FAILING),
+        ))
+        .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+
+        let err = futures_lite::future::block_on(happy_eyeballs(&[], delay, scripted(FAILING)))
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+    }
+
+    #[test]
+    fn fallback_on_tls_handshake_error() {
+        futures_lite::future::block_on(async move {
+            let mut tls = Failing(|| TlsHandshakeError::wrap("unknown issuer"));
+            let mut plain = TestConnector::default();
+
+            let fallback = connect_with_fallback(&mut tls, &mut plain).await.unwrap();
+            assert!(fallback.is_downgraded());
+            match fallback {
+                Fallback::Plain { tls_error, .. } => {
+                    assert!(is_tls_handshake_error(&tls_error));
+                    assert_eq!(tls_error.to_string(), "tls handshake failed: unknown issuer");
+                }
+                Fallback::Tls(..) => panic!("expected a plaintext fallback"),
+            }
+        });
+    }
+
+    #[test]
+    fn no_fallback_on_connect_error() {
+        futures_lite::future::block_on(async move {
+            let mut tls = Failing(|| std::io::ErrorKind::ConnectionRefused.into());
+            let mut plain = TestConnector::default();
+
+            let err = connect_with_fallback(&mut tls, &mut plain)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+            assert!(!is_tls_handshake_error(&err));
+        });
+    }
+
+    #[test]
+    fn no_fallback_on_tls_success() {
+        futures_lite::future::block_on(async move {
+            let mut tls = TestConnector::default();
+            let mut plain = Failing(|| panic!("plaintext should not be tried"));
+
+            let fallback = connect_with_fallback(&mut tls, &mut plain).await.unwrap();
+            assert!(!fallback.is_downgraded());
+        });
+    }
+}
+
 #[cfg(test)]
 #[allow(dead_code)]
 mod testing {
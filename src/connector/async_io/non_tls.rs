@@ -17,7 +17,7 @@ impl crate::connector::Connector for Connector {
 
     fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
         let addrs = self.addrs.clone();
-        let fut = async move { try_connect(&*addrs, TcpStream::connect).await };
+        let fut = async move { try_connect(&addrs, TcpStream::connect).await };
         Box::pin(fut)
     }
 }
@@ -17,7 +17,7 @@ impl crate::connector::Connector for Connector {
 
     fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
         let addrs = self.addrs.clone();
-        let fut = async move { async_std::net::TcpStream::connect(&*addrs).await };
+        let fut = async move { try_connect(&addrs, async_std::net::TcpStream::connect).await };
         Box::pin(fut)
     }
 }
@@ -4,16 +4,40 @@ use super::*;
 ///
 /// To use this type, ensure you set up the 'TLS Domain' in the
 /// configuration. The crate provides the 'TLS domain' for Twitch in the root of this crate.
+///
+/// By default this trusts the [`webpki-roots`](https://docs.rs/webpki-roots/latest/webpki_roots/),
+/// use [ConnectorTls::with_config] to provide your own `ClientConfig`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConnectorTls {
     addrs: Vec<std::net::SocketAddr>,
     tls_domain: String,
+    config: crate::connector::AsyncTlsConfig,
 }
 
 impl ConnectorTls {
     connector_ctor!(tls:
         /// [`smol`](https://docs.rs/smol/latest/smol/)
+        config: crate::connector::AsyncTlsConfig::default(),
     );
+
+    /// Create a new [`smol`](https://docs.rs/smol/latest/smol/) TLS connector with a custom address, TLS domain
+    /// and `rustls` `ClientConfig`.
+    ///
+    /// `config` can be an `Arc<rustls::ClientConfig>`, a `rustls::ClientConfig`
+    /// or an `async_tls::TlsConnector`.
+    ///
+    /// Use this if you need a custom CA, client certificates or want to pin
+    /// Twitch's certificate.
+    pub fn with_config<A, D, C>(addrs: A, domain: D, config: C) -> std::io::Result<Self>
+    where
+        A: std::net::ToSocketAddrs,
+        D: Into<String>,
+        C: Into<async_tls::TlsConnector>,
+    {
+        let mut this = Self::custom(addrs, domain)?;
+        this.config = crate::connector::AsyncTlsConfig::new(config);
+        Ok(this)
+    }
 }
 
 impl crate::connector::Connector for ConnectorTls {
@@ -22,10 +46,12 @@ impl crate::connector::Connector for ConnectorTls {
     fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
         let this = self.clone();
         let fut = async move {
-            let stream = try_connect(&*this.addrs, TcpStream::connect).await?;
-            async_tls::TlsConnector::new()
+            let stream = try_connect(&this.addrs, TcpStream::connect).await?;
+            this.config
+                .connector()
                 .connect(this.tls_domain, stream)
                 .await
+                .map_err(crate::connector::TlsHandshakeError::wrap)
                 .map(async_dup::Mutex::new)
         };
         Box::pin(fut)
@@ -45,4 +71,22 @@ mod tests {
         assert_type_is_read_write::<<ConnectorTls as C>::Output>();
         assert_obj_is_sane(ConnectorTls::twitch().unwrap());
     }
+
+    #[test]
+    fn with_custom_config() {
+        use crate::connector::testing::*;
+
+        let connector = ConnectorTls::with_config(
+            "127.0.0.1:6697",
+            "localhost",
+            async_tls::TlsConnector::new(),
+        )
+        .unwrap();
+        assert_eq!(connector.tls_domain(), "localhost");
+
+        let default = ConnectorTls::custom("127.0.0.1:6697", "localhost").unwrap();
+        assert_ne!(connector, default);
+        assert_eq!(connector, connector.clone());
+        assert_obj_is_sane(connector);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::connector::try_connect;
 use crate::BoxedFuture;
 
 mod non_tls;
@@ -33,11 +33,11 @@ impl crate::connector::Connector for ConnectorNativeTls {
                 .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
                 .into();
 
-            let stream = tokio::net::TcpStream::connect(&*this.addrs).await?;
+            let stream = try_connect(&this.addrs, tokio::net::TcpStream::connect).await?;
             let stream = connector
                 .connect(&this.tls_domain, stream)
                 .await
-                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                .map_err(crate::connector::TlsHandshakeError::wrap)?;
 
             Ok(async_dup::Mutex::new(stream.compat()))
         };
@@ -19,7 +19,7 @@ impl crate::connector::Connector for Connector {
         let addrs = self.addrs.clone();
         let fut = async move {
             use tokio_util::compat::TokioAsyncReadCompatExt as _;
-            let stream = tokio::net::TcpStream::connect(&*addrs).await?;
+            let stream = try_connect(&addrs, tokio::net::TcpStream::connect).await?;
             Ok(async_dup::Mutex::new(stream.compat()))
         };
         Box::pin(fut)
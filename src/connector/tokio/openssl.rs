@@ -35,14 +35,14 @@ impl crate::connector::Connector for ConnectorOpenSsl {
                 .and_then(|c| c.build().configure())
                 .map_err(|err| Error::new(ErrorKind::Other, err))?;
 
-            let stream = tokio::net::TcpStream::connect(&*this.addrs).await?;
+            let stream = try_connect(&this.addrs, tokio::net::TcpStream::connect).await?;
             let ssl = config.into_ssl(&this.tls_domain).map_err(|err| Error::new(ErrorKind::Other, err))?;
             let mut stream = tokio_openssl::SslStream::new(ssl, stream)
                 .map_err(|err| Error::new(ErrorKind::Other, err))?;
             std::pin::Pin::new(&mut stream)
                 .connect()
                 .await
-                .map_err(|err| Error::new(ErrorKind::Other, err))?;
+                .map_err(crate::connector::TlsHandshakeError::wrap)?;
 
             Ok(async_dup::Mutex::new(stream.compat()))
         };
@@ -5,16 +5,79 @@ use super::*;
 /// To use this type, ensure you set up the 'TLS Domain' in the configuration.
 ///
 /// The crate provides the 'TLS domain' for Twitch in the root of this crate.
+///
+/// By default this trusts the [`webpki-roots`](https://docs.rs/webpki-roots/latest/webpki_roots/),
+/// use [ConnectorRustTls::with_config] to provide your own `ClientConfig`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConnectorRustTls {
     addrs: Vec<std::net::SocketAddr>,
     tls_domain: String,
+    config: TlsConfig,
 }
 
 impl ConnectorRustTls {
     connector_ctor!(tls:
         /// [`tokio`](https://docs.rs/tokio/0.2/tokio/) (using [`tokio-rustls`](https://docs.rs/tokio-rustls/latest/tokio_rustls/))
+        config: TlsConfig(None),
     );
+
+    /// Create a new [`tokio`](https://docs.rs/tokio/0.2/tokio/) (using [`tokio-rustls`](https://docs.rs/tokio-rustls/latest/tokio_rustls/))
+    /// TLS connector with a custom address, TLS domain and `rustls` `ClientConfig`.
+    ///
+    /// Use this if you need a custom CA, client certificates or want to pin
+    /// Twitch's certificate.
+    pub fn with_config<A, D>(
+        addrs: A,
+        domain: D,
+        config: std::sync::Arc<tokio_rustls::rustls::ClientConfig>,
+    ) -> std::io::Result<Self>
+    where
+        A: std::net::ToSocketAddrs,
+        D: Into<String>,
+    {
+        let mut this = Self::custom(addrs, domain)?;
+        this.config = TlsConfig(Some(config));
+        Ok(this)
+    }
+
+    fn server_name(&self) -> std::io::Result<tokio_rustls::webpki::DNSNameRef<'_>> {
+        tokio_rustls::webpki::DNSNameRef::try_from_ascii_str(&self.tls_domain)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
+    }
+}
+
+// the rustls ClientConfig is neither Debug nor PartialEq
+#[derive(Clone)]
+struct TlsConfig(Option<std::sync::Arc<tokio_rustls::rustls::ClientConfig>>);
+
+impl TlsConfig {
+    fn get(&self) -> std::sync::Arc<tokio_rustls::rustls::ClientConfig> {
+        self.0.clone().unwrap_or_else(|| {
+            let mut c = tokio_rustls::rustls::ClientConfig::new();
+            c.root_store
+                .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+            std::sync::Arc::new(c)
+        })
+    }
+}
+
+impl std::fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(..) => f.write_str("Custom"),
+            None => f.write_str("WebpkiRoots"),
+        }
+    }
+}
+
+impl PartialEq for TlsConfig {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(left), Some(right)) => std::sync::Arc::ptr_eq(left, right),
+            (None, None) => true,
+            _ => false,
+        }
+    }
 }
 
 impl crate::connector::Connector for ConnectorRustTls {
@@ -26,19 +89,15 @@ impl crate::connector::Connector for ConnectorRustTls {
         let this = self.clone();
         let fut = async move {
             use tokio_util::compat::TokioAsyncReadCompatExt as _;
-            let domain = tokio_rustls::webpki::DNSNameRef::try_from_ascii_str(&this.tls_domain)
-                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
-
-            let connector: tokio_rustls::TlsConnector = std::sync::Arc::new({
-                let mut c = tokio_rustls::rustls::ClientConfig::new();
-                c.root_store
-                    .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
-                c
-            })
-            .into();
-
-            let stream = tokio::net::TcpStream::connect(&*this.addrs).await?;
-            let stream = connector.connect(domain, stream).await?;
+            let domain = this.server_name()?;
+
+            let connector: tokio_rustls::TlsConnector = this.config.get().into();
+
+            let stream = try_connect(&this.addrs, tokio::net::TcpStream::connect).await?;
+            let stream = connector
+                .connect(domain, stream)
+                .await
+                .map_err(crate::connector::TlsHandshakeError::wrap)?;
             Ok(async_dup::Mutex::new(stream.compat()))
         };
         Box::pin(fut)
@@ -58,4 +117,39 @@ mod tests {
         assert_type_is_read_write::<<ConnectorRustTls as C>::Output>();
         assert_obj_is_sane(ConnectorRustTls::twitch().unwrap());
     }
+
+    #[test]
+    fn with_custom_config() {
+        use crate::connector::testing::*;
+
+        let config = std::sync::Arc::new(tokio_rustls::rustls::ClientConfig::new());
+        let connector =
+            ConnectorRustTls::with_config("127.0.0.1:6697", "localhost", config.clone()).unwrap();
+
+        let default = ConnectorRustTls::custom("127.0.0.1:6697", "localhost").unwrap();
+        assert_ne!(connector, default);
+        assert!(std::sync::Arc::ptr_eq(&connector.config.get(), &config));
+        assert_obj_is_sane(connector);
+    }
+
+    #[test]
+    fn custom_domain_is_only_used_for_sni() {
+        use tokio_rustls::rustls::{ClientSession, Session as _};
+
+        let connector = ConnectorRustTls::custom("127.0.0.1:6697", "irc.example.test").unwrap();
+        assert_eq!(connector.addrs(), &["127.0.0.1:6697".parse().unwrap()]);
+        assert_eq!(connector.tls_domain(), "irc.example.test");
+
+        // the ClientHello should carry the domain, not the address
+        let mut session = ClientSession::new(
+            &connector.config.get(),
+            connector.server_name().unwrap(),
+        );
+        let mut hello = vec![];
+        session.write_tls(&mut hello).unwrap();
+
+        let contains = |needle: &[u8]| hello.windows(needle.len()).any(|w| w == needle);
+        assert!(contains(b"irc.example.test"));
+        assert!(!contains(b"127.0.0.1"));
+    }
 }
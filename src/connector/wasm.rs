@@ -0,0 +1,293 @@
+use crate::BoxedFuture;
+
+use futures_lite::{AsyncRead, AsyncWrite, StreamExt as _};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll},
+};
+use wasm_bindgen::{closure::Closure, JsCast as _, JsValue};
+use web_sys::{BinaryType, Event, MessageEvent, WebSocket};
+
+/// A connector for the browser's [`WebSocket`](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket)
+///
+/// This is only useful on `wasm32-unknown-unknown`, calling `connect` on any other target will panic.
+///
+/// The [AsyncRunner](crate::AsyncRunner) uses `std::time::Instant`, which isn't available in the browser.
+/// Use the [Stream] with an [AsyncDecoder](crate::AsyncDecoder) and [AsyncEncoder](crate::AsyncEncoder) instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connector {
+    url: String,
+}
+
+impl Connector {
+    /// Create a new WebSocket connector that connects to the ***default Twitch*** address.
+    pub fn twitch() -> Self {
+        Self::custom(crate::TWITCH_WS_ADDRESS_TLS)
+    }
+
+    /// Create a new WebSocket connector with a custom url
+    pub fn custom(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    /// The url the WebSocket will connect to
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl crate::connector::Connector for Connector {
+    type Output = Stream;
+
+    fn connect(&mut self) -> BoxedFuture<io::Result<Self::Output>> {
+        let (stream, opened) = match Stream::open(&self.url) {
+            Ok(ok) => ok,
+            Err(err) => return Box::pin(async move { Err(err) }),
+        };
+
+        let fut = async move {
+            match opened.recv().await {
+                Ok(()) => Ok(stream),
+                Err(..) => Err(io::Error::new(
+                    io::ErrorKind::ConnectionRefused,
+                    "websocket closed before it was opened",
+                )),
+            }
+        };
+        Box::pin(fut)
+    }
+}
+
+// the web-sys types aren't Send or Sync, so they stay in this thread local
+// and the Stream just refers to them by id. the browser only has the one thread.
+thread_local! {
+    static SOCKETS: RefCell<HashMap<usize, Socket>> = RefCell::new(HashMap::new());
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+struct Socket {
+    ws: WebSocket,
+    // these have to live as long as the WebSocket can call them
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_event: [Closure<dyn FnMut(Event)>; 3],
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        self.ws.set_onopen(None);
+        self.ws.set_onmessage(None);
+        self.ws.set_onerror(None);
+        self.ws.set_onclose(None);
+        let _ = self.ws.close();
+    }
+}
+
+/// The read/write half of a browser WebSocket, produced by the [Connector]
+///
+/// Each line written is sent as its own text frame.
+pub struct Stream {
+    id: usize,
+    read: Mutex<ReadHalf>,
+    write: Mutex<Vec<u8>>,
+}
+
+struct ReadHalf {
+    incoming: async_channel::Receiver<Vec<u8>>,
+    buf: Vec<u8>,
+}
+
+impl std::fmt::Debug for Stream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Stream").field("id", &self.id).finish()
+    }
+}
+
+impl Stream {
+    fn open(url: &str) -> io::Result<(Self, async_channel::Receiver<()>)> {
+        let ws = WebSocket::new(url).map_err(js_error)?;
+        ws.set_binary_type(BinaryType::Arraybuffer);
+
+        let (opened_tx, opened_rx) = async_channel::bounded(1);
+        let (incoming_tx, incoming_rx) = async_channel::unbounded();
+
+        let on_message = {
+            let tx = incoming_tx.clone();
+            Closure::new(move |ev: MessageEvent| {
+                let data = ev.data();
+                let data = match data.as_string() {
+                    Some(s) => s.into_bytes(),
+                    None => js_sys::Uint8Array::new(&data).to_vec(),
+                };
+                let _ = tx.try_send(data);
+            })
+        };
+
+        let on_open = {
+            let tx = opened_tx.clone();
+            Closure::new(move |_: Event| {
+                let _ = tx.try_send(());
+            })
+        };
+
+        // an error or close before it was opened fails the connect, afterwards it ends the stream
+        let on_close = || {
+            let (opened, incoming) = (opened_tx.clone(), incoming_tx.clone());
+            Closure::new(move |_: Event| {
+                opened.close();
+                incoming.close();
+            })
+        };
+        let (on_error, on_close) = (on_close(), on_close());
+
+        ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        ws.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let socket = Socket {
+            ws,
+            _on_message: on_message,
+            _on_event: [on_open, on_error, on_close],
+        };
+        SOCKETS.with(|sockets| sockets.borrow_mut().insert(id, socket));
+
+        let stream = Self {
+            id,
+            read: Mutex::new(ReadHalf {
+                incoming: incoming_rx,
+                buf: Vec::new(),
+            }),
+            write: Mutex::new(Vec::new()),
+        };
+        Ok((stream, opened_rx))
+    }
+
+    fn send(&self, line: &[u8]) -> io::Result<()> {
+        let line = std::str::from_utf8(line)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        SOCKETS.with(|sockets| match sockets.borrow().get(&self.id) {
+            Some(socket) => socket.ws.send_with_str(line).map_err(js_error),
+            None => Err(io::Error::from(io::ErrorKind::NotConnected)),
+        })
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        // this can be dropped while the thread local is being torn down
+        let _ = SOCKETS.try_with(|sockets| sockets.borrow_mut().remove(&self.id));
+    }
+}
+
+fn js_error(err: JsValue) -> io::Error {
+    io::Error::other(format!("websocket error: {:?}", err))
+}
+
+impl AsyncRead for &Stream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut read = self.read.lock().unwrap();
+        let ReadHalf { incoming, buf: pending } = &mut *read;
+
+        if pending.is_empty() {
+            match futures_lite::ready!(incoming.poll_next(cx)) {
+                Some(data) => *pending = data,
+                None => return Poll::Ready(Ok(0)),
+            }
+        }
+
+        let n = std::cmp::min(buf.len(), pending.len());
+        buf[..n].copy_from_slice(&pending[..n]);
+        pending.drain(..n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for &Stream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut write = self.write.lock().unwrap();
+        write.extend_from_slice(buf);
+
+        // send each complete line as its own frame
+        while let Some(pos) = write.iter().position(|&c| c == b'\n') {
+            let line: Vec<u8> = write.drain(..=pos).collect();
+            self.send(&line)?;
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let _ = SOCKETS.try_with(|sockets| sockets.borrow_mut().remove(&self.id));
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assert_connector_trait_is_fulfilled() {
+        use crate::connector::testing::*;
+        use crate::connector::Connector as C;
+
+        assert_connector::<Connector>();
+        assert_type_is_read_write::<<Connector as C>::Output>();
+        assert_obj_is_sane(Connector::twitch());
+    }
+
+    #[test]
+    fn twitch_url() {
+        assert_eq!(Connector::twitch().url(), "wss://irc-ws.chat.twitch.tv:443");
+        assert_eq!(Connector::custom("ws://localhost").url(), "ws://localhost");
+    }
+}
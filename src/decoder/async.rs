@@ -1,5 +1,9 @@
 cfg_async! {
-use crate::{irc::IrcMessage, IntoOwned,DecodeError};
+use crate::{
+    irc::{IrcMessage, TagIndices},
+    messages::Commands,
+    DecodeError, FromIrcMessage, IntoOwned,
+};
 
 use std::{
     future::Future,
@@ -7,16 +11,26 @@ use std::{
     task::{Context, Poll},
 };
 
-use futures_lite::{io::BufReader as AsyncBufReader, AsyncBufReadExt, AsyncRead, Stream};
+use futures_lite::{
+    io::BufReader as AsyncBufReader, AsyncBufReadExt, AsyncRead, AsyncReadExt, Stream,
+};
 
 /// A decoder over [futures_lite::AsyncRead] that produces [IrcMessage]s
 ///
 /// This will return an [DecodeError::Eof] when its done reading manually.
 ///
-/// When reading it as a stream, `Eof` will signal the end of the stream (e.g. `None`)
+/// When reading it as a stream, `Eof` will signal the end of the stream (e.g. `None`).
+/// The stream is fused, so once it has ended it'll keep returning `None` without touching the reader.
 pub struct AsyncDecoder<R> {
     reader: AsyncBufReader<R>,
     buf: Vec<u8>,
+    max_message_bytes: usize,
+    resync: bool,
+    // set while a line is being read, so a cancelled read can pick up where it left off
+    partial: bool,
+    eof: bool,
+    // reused by read_message_with_tags
+    tags: TagIndices,
 }
 
 impl<R> std::fmt::Debug for AsyncDecoder<R> {
@@ -27,42 +41,199 @@ impl<R> std::fmt::Debug for AsyncDecoder<R> {
 
 impl<R: AsyncRead + Send + Sync + Unpin> AsyncDecoder<R> {
     /// Create a new AsyncDecoder from this [futures_lite::AsyncRead] instance
+    ///
+    /// This uses an `8 KiB` read buffer and an initial `1 KiB` line buffer.
+    /// Use [AsyncDecoder::with_capacity] to change this.
     pub fn new(reader: R) -> Self {
         Self {
             reader: AsyncBufReader::new(reader),
             buf: Vec::with_capacity(1024),
+            max_message_bytes: super::DEFAULT_MAX_MESSAGE_BYTES,
+            resync: false,
+            partial: false,
+            eof: false,
+            tags: TagIndices::default(),
         }
     }
 
+    /// Create a new AsyncDecoder from this [futures_lite::AsyncRead] instance with a specific buffer `capacity`
+    ///
+    /// This is used for both the read buffer and the initial line buffer. The line buffer will grow
+    /// as needed, up to the maximum message length.
+    ///
+    /// A larger capacity means fewer reads for busy connections, a smaller one uses less memory.
+    /// Messages longer than the capacity are still read, they just take multiple reads.
+    pub fn with_capacity(reader: R, capacity: usize) -> Self {
+        Self {
+            reader: AsyncBufReader::with_capacity(capacity, reader),
+            buf: Vec::with_capacity(capacity),
+            max_message_bytes: super::DEFAULT_MAX_MESSAGE_BYTES,
+            resync: false,
+            partial: false,
+            eof: false,
+            tags: TagIndices::default(),
+        }
+    }
+
+    /// Set the maximum length of a message, in bytes.
+    ///
+    /// If a line is longer than this, [DecodeError::LineTooLong] will be
+    /// returned and the rest of the line will be skipped on the next read.
+    ///
+    /// This defaults to [DEFAULT_MAX_MESSAGE_BYTES](super::DEFAULT_MAX_MESSAGE_BYTES)
+    pub fn max_message_bytes(mut self, max: usize) -> Self {
+        self.max_message_bytes = max.max(1);
+        self
+    }
+
     /// Read the next message.
     ///
     /// This returns a borrowed [IrcMessage] which is valid until the next AsyncDecoder call is made.
     ///
     /// If you just want an owned one, use the [AsyncDecoder] as an stream. e.g. dec.next().
+    ///
+    /// This is cancel safe. If the future is dropped part way through a line,
+    /// the next call will continue reading that line.
     pub async fn read_message(&mut self) -> Result<IrcMessage<'_>, DecodeError> {
-        self.buf.clear();
-        let n = self
-            .reader
+        let n = self.read_line().await?;
+        parse_line(&self.buf[..n])
+    }
+
+    /// Read the next message, along with the [TagIndices] for its tags.
+    ///
+    /// The indices are rebuilt in a buffer owned by the decoder, so reading a
+    /// steady stream of messages doesn't allocate for the tags. Both are valid
+    /// until the next AsyncDecoder call is made, clone the indices to keep them.
+    ///
+    /// If the message has no tags, or they are malformed, the indices will be empty.
+    ///
+    /// This is cancel safe, like [AsyncDecoder::read_message].
+    pub async fn read_message_with_tags(
+        &mut self,
+    ) -> Result<(IrcMessage<'_>, &TagIndices), DecodeError> {
+        let n = self.read_line().await?;
+        let msg = parse_line(&self.buf[..n])?;
+        self.tags.rebuild(&msg);
+        Ok((msg, &self.tags))
+    }
+
+    // read the next line into the buffer, returning its length
+    async fn read_line(&mut self) -> Result<usize, DecodeError> {
+        if self.resync {
+            if !self.skip_line().await? {
+                return Err(DecodeError::Eof);
+            }
+            self.resync = false;
+        }
+
+        if !self.partial {
+            self.buf.clear();
+        }
+
+        self.partial = true;
+        let limit = self.max_message_bytes.saturating_sub(self.buf.len());
+        let res = (&mut self.reader)
+            .take(limit as u64)
             .read_until(b'\n', &mut self.buf)
-            .await
-            .map_err(DecodeError::Io)?;
+            .await;
+        self.partial = false;
+
+        res.map_err(DecodeError::Io)?;
+        let n = self.buf.len();
         if n == 0 {
             return Err(DecodeError::Eof);
         }
 
-        let str = std::str::from_utf8(&self.buf[..n]).map_err(DecodeError::InvalidUtf8)?;
-        log::trace!("< {}", str.escape_debug());
+        if n >= self.max_message_bytes && !self.buf.ends_with(b"\n") {
+            self.resync = true;
+            return Err(DecodeError::LineTooLong);
+        }
+
+        Ok(n)
+    }
+
+    // skip the rest of the current line, returning false if EOF was reached
+    async fn skip_line(&mut self) -> Result<bool, DecodeError> {
+        loop {
+            let buf = self.reader.fill_buf().await.map_err(DecodeError::Io)?;
+            if buf.is_empty() {
+                return Ok(false);
+            }
 
-        // this should only ever parse 1 message
-        crate::irc::parse_one(str)
-            .map_err(DecodeError::ParseError)
-            .map(|(_, msg)| msg)
+            match buf.iter().position(|&c| c == b'\n') {
+                Some(pos) => {
+                    self.reader.consume(pos + 1);
+                    return Ok(true);
+                }
+                None => {
+                    let len = buf.len();
+                    self.reader.consume(len)
+                }
+            }
+        }
     }
 
     /// Consume the decoder returning the inner Reader
     pub fn into_inner(self) -> R {
         self.reader.into_inner()
     }
+
+    /// Consume the decoder, returning a stream that produces [Commands] rather than [IrcMessage]s
+    ///
+    /// If a message cannot be parsed into a [Commands], a [DecodeError::CommandError] will be produced.
+    pub fn into_commands(self) -> CommandsStream<R> {
+        CommandsStream { decoder: self }
+    }
+}
+
+/// A stream that produces [Commands] parsed from an [AsyncDecoder]
+///
+/// This is created by [AsyncDecoder::into_commands]
+pub struct CommandsStream<R> {
+    decoder: AsyncDecoder<R>,
+}
+
+impl<R> std::fmt::Debug for CommandsStream<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandsStream").finish()
+    }
+}
+
+impl<R: AsyncRead + Send + Sync + Unpin> CommandsStream<R> {
+    /// Consume the stream returning the inner Reader
+    pub fn into_inner(self) -> R {
+        self.decoder.into_inner()
+    }
+}
+
+fn parse_line(line: &[u8]) -> Result<IrcMessage<'_>, DecodeError> {
+    let str = std::str::from_utf8(line).map_err(DecodeError::InvalidUtf8)?;
+    log::trace!("< {}", str.escape_debug());
+
+    // this should only ever parse 1 message
+    crate::irc::parse_one(str)
+        .map_err(DecodeError::ParseError)
+        .map(|(_, msg)| msg)
+}
+
+/// This will produce `Result<Commands<'static>, DecodeError>` until an `Eof` is received
+impl<R> Stream for CommandsStream<R>
+where
+    R: AsyncRead + Send + Sync + Unpin,
+{
+    type Item = Result<Commands<'static>, DecodeError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let msg = match futures_lite::ready!(Pin::new(&mut self.decoder).poll_next(cx)) {
+            Some(Ok(msg)) => msg,
+            Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+            None => return Poll::Ready(None),
+        };
+
+        Poll::Ready(Some(
+            Commands::from_irc(msg).map_err(DecodeError::CommandError),
+        ))
+    }
 }
 
 /// This will produce `Result<IrcMessage<'static>, DecodeError>` until an `Eof` is received
@@ -73,16 +244,41 @@ where
     type Item = Result<IrcMessage<'static>, DecodeError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut this = self.as_mut();
+        if self.eof {
+            return Poll::Ready(None);
+        }
 
-        let fut = this.read_message();
-        futures_lite::pin!(fut);
+        let res = {
+            let fut = self.as_mut().get_mut().read_message();
+            futures_lite::pin!(fut);
 
-        match futures_lite::ready!(fut.poll(cx)) {
-            Err(DecodeError::Eof) => Poll::Ready(None),
-            Ok(msg) => Poll::Ready(Some(Ok(msg.into_owned()))),
-            Err(err) => Poll::Ready(Some(Err(err))),
-        }
+            match futures_lite::ready!(fut.poll(cx)) {
+                Err(DecodeError::Eof) => None,
+                Ok(msg) => Some(Ok(msg.into_owned())),
+                Err(err) => Some(Err(err)),
+            }
+        };
+
+        self.eof = res.is_none();
+        Poll::Ready(res)
+    }
+}
+
+impl<R> futures_core::stream::FusedStream for AsyncDecoder<R>
+where
+    R: AsyncRead + Send + Sync + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.eof
+    }
+}
+
+impl<R> futures_core::stream::FusedStream for CommandsStream<R>
+where
+    R: AsyncRead + Send + Sync + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.decoder.eof
     }
 }
 
@@ -121,5 +317,202 @@ mod tests {
 
         futures_lite::future::block_on(fut);
     }
+
+    #[test]
+    fn read_message_with_tags() {
+        use crate::validator::Validator as _;
+
+        let fut = async move {
+            let data = "@a=1;b=2 :test!test@test PRIVMSG #museun :hello\r\n\
+                        :test!test@test PRIVMSG #museun :no tags\r\n\
+                        @c=3;=4 PING :malformed\r\n";
+
+            let mut dec = AsyncDecoder::new(data.as_bytes());
+            for len in [2, 0, 0].iter() {
+                let (msg, indices) = dec.read_message_with_tags().await.unwrap();
+                assert_eq!(indices.len(), *len);
+                assert_eq!(indices.clone(), msg.parse_tags());
+            }
+
+            assert!(matches!(
+                dec.read_message_with_tags().await.unwrap_err(),
+                DecodeError::Eof
+            ))
+        };
+
+        futures_lite::future::block_on(fut);
+    }
+
+    #[test]
+    fn read_async_cancelled_mid_line() {
+        use std::{
+            collections::VecDeque,
+            sync::{Arc, Mutex},
+        };
+
+        // yields each chunk in its own read, then is pending until more are pushed
+        #[derive(Default, Clone)]
+        struct Chunks(Arc<Mutex<VecDeque<&'static [u8]>>>);
+
+        impl AsyncRead for Chunks {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<std::io::Result<usize>> {
+                match self.0.lock().unwrap().pop_front() {
+                    Some(chunk) => {
+                        buf[..chunk.len()].copy_from_slice(chunk);
+                        Poll::Ready(Ok(chunk.len()))
+                    }
+                    None => Poll::Pending,
+                }
+            }
+        }
+
+        let fut = async move {
+            let chunks = Chunks::default();
+            let mut dec = AsyncDecoder::new(chunks.clone());
+
+            chunks.0.lock().unwrap().push_back(b"PING :1234");
+            let res = futures_lite::future::poll_once(dec.read_message()).await;
+            assert!(res.is_none());
+
+            chunks.0.lock().unwrap().push_back(b"567890\r\nPING :next\r\n");
+            let msg = dec.read_message().await.unwrap();
+            assert_eq!(msg.get_raw(), "PING :1234567890\r\n");
+
+            let msg = dec.read_message().await.unwrap();
+            assert_eq!(msg.get_raw(), "PING :next\r\n");
+        };
+
+        futures_lite::future::block_on(fut);
+    }
+
+    #[test]
+    fn read_async_line_too_long() {
+        let fut = async move {
+            let data = format!("{}\r\nPING :1234567890\r\n", "a".repeat(20 * 1024));
+            let mut dec = AsyncDecoder::new(data.as_bytes());
+            assert!(matches!(
+                dec.read_message().await.unwrap_err(),
+                DecodeError::LineTooLong
+            ));
+
+            let msg = dec.read_message().await.unwrap();
+            assert_eq!(msg.get_raw(), "PING :1234567890\r\n");
+        };
+
+        futures_lite::future::block_on(fut);
+    }
+
+    #[test]
+    fn read_async_tiny_capacity() {
+        let fut = async move {
+            let data = b"\
+                @badge-info=;badges=;color=#FF69B4;display-name=museun :museun!museun@museun PRIVMSG #museun :hello world\r\n\
+                PING :1234567890\r\n\
+            ";
+
+            let mut dec = AsyncDecoder::with_capacity(&data[..], 4);
+            let msg = dec.read_message().await.unwrap();
+            assert_eq!(msg.get_data().unwrap(), "hello world");
+            assert!(msg.get_tags().unwrap().ends_with("display-name=museun"));
+
+            let msg = dec.read_message().await.unwrap();
+            assert_eq!(msg.get_raw(), "PING :1234567890\r\n");
+
+            assert!(matches!(
+                dec.read_message().await.unwrap_err(),
+                DecodeError::Eof
+            ));
+        };
+
+        futures_lite::future::block_on(fut);
+    }
+
+    #[test]
+    fn read_async_fused() {
+        use futures_core::stream::FusedStream as _;
+        use futures_lite::stream::StreamExt as _;
+
+        // a reader that panics if its read after it reported an EOF
+        #[derive(Default)]
+        struct ReadOnce {
+            reads: usize,
+        }
+
+        impl AsyncRead for ReadOnce {
+            fn poll_read(
+                mut self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<std::io::Result<usize>> {
+                self.reads += 1;
+                match self.reads {
+                    1 => {
+                        let data = b"PING :1234567890\r\n";
+                        buf[..data.len()].copy_from_slice(data);
+                        Poll::Ready(Ok(data.len()))
+                    }
+                    2 => Poll::Ready(Ok(0)),
+                    _ => panic!("read after EOF"),
+                }
+            }
+        }
+
+        let fut = async move {
+            let mut dec = AsyncDecoder::new(ReadOnce::default());
+            assert!(!dec.is_terminated());
+            assert!(dec.next().await.unwrap().is_ok());
+            for _ in 0..5 {
+                assert!(dec.next().await.is_none());
+                assert!(dec.is_terminated());
+            }
+
+            let mut stream = AsyncDecoder::new(ReadOnce::default()).into_commands();
+            assert!(stream.next().await.unwrap().is_ok());
+            for _ in 0..5 {
+                assert!(stream.next().await.is_none());
+                assert!(stream.is_terminated());
+            }
+        };
+
+        futures_lite::future::block_on(fut);
+    }
+
+    #[test]
+    fn read_async_commands() {
+        use futures_lite::stream::StreamExt as _;
+        let fut = async move {
+            let data = b"\
+                :test!test@test PRIVMSG #museun :hello world\r\n\
+                PING :1234567890\r\n\
+                :tmi.twitch.tv 001\r\n\
+            ";
+
+            let mut stream = AsyncDecoder::new(&data[..]).into_commands();
+
+            match stream.next().await.unwrap().unwrap() {
+                Commands::Privmsg(pm) => assert_eq!(pm.data(), "hello world"),
+                cmd => panic!("unexpected: {:?}", cmd),
+            }
+
+            match stream.next().await.unwrap().unwrap() {
+                Commands::Ping(ping) => assert_eq!(ping.token(), "1234567890"),
+                cmd => panic!("unexpected: {:?}", cmd),
+            }
+
+            // this is a valid irc message, but its missing the username
+            assert!(matches!(
+                stream.next().await.unwrap().unwrap_err(),
+                DecodeError::CommandError(..)
+            ));
+
+            assert!(stream.next().await.is_none());
+        };
+
+        futures_lite::future::block_on(fut);
+    }
 }
 }
@@ -45,6 +45,15 @@
 //! }
 //! ```
 
+/// The default maximum length of a message the decoders will read before giving up, in bytes.
+///
+/// Twitch limits messages to 500 characters, so even with a large set of tags
+/// a real line will be well under this. If a peer never sends a `\r\n` the
+/// decoders will return a [DecodeError::LineTooLong] rather than buffering forever.
+///
+/// This can be changed with [Decoder::max_message_bytes] (or [AsyncDecoder::max_message_bytes]).
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 16 * 1024;
+
 cfg_async! {
     mod r#async;
     pub use r#async::*;
@@ -1,5 +1,8 @@
-use crate::{IntoOwned as _, IrcMessage, MessageError};
-use std::io::{BufRead, BufReader, Read};
+use crate::{
+    irc::TagIndices, messages::Commands, FromIrcMessage as _, IntoOwned as _, IrcMessage,
+    MessageError,
+};
+use std::io::{BufRead, BufReader, Read, Write};
 
 /// An error produced by a Decoder.
 #[derive(Debug)]
@@ -13,6 +16,12 @@ pub enum DecodeError {
     ParseError(MessageError),
     /// EOF was reached
     Eof,
+    /// A line longer than the maximum message length was read without a `\r\n`
+    ///
+    /// The decoder can still be used after this, the rest of the line will be skipped.
+    LineTooLong,
+    /// The IRC message was valid, but it could not be parsed into a [Commands]
+    CommandError(MessageError),
 }
 
 impl std::fmt::Display for DecodeError {
@@ -22,6 +31,8 @@ impl std::fmt::Display for DecodeError {
             Self::InvalidUtf8(err) => write!(f, "invalid utf8: {}", err),
             Self::ParseError(err) => write!(f, "parse error: {}", err),
             Self::Eof => f.write_str("end of file reached"),
+            Self::LineTooLong => f.write_str("line exceeded the maximum message length"),
+            Self::CommandError(err) => write!(f, "cannot parse command: {}", err),
         }
     }
 }
@@ -32,6 +43,7 @@ impl std::error::Error for DecodeError {
             Self::Io(err) => Some(err),
             Self::InvalidUtf8(err) => Some(err),
             Self::ParseError(err) => Some(err),
+            Self::CommandError(err) => Some(err),
             _ => None,
         }
     }
@@ -45,11 +57,18 @@ impl std::error::Error for DecodeError {
 pub struct Decoder<R> {
     reader: BufReader<R>,
     buf: Vec<u8>,
+    max_message_bytes: usize,
+    resync: bool,
+    tap: Option<Box<dyn Write + Send + Sync>>,
+    // reused by read_message_with_tags
+    tags: TagIndices,
 }
 
 impl<R> std::fmt::Debug for Decoder<R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Decoder").finish()
+        f.debug_struct("Decoder")
+            .field("tapped", &self.tap.is_some())
+            .finish()
     }
 }
 
@@ -62,30 +81,152 @@ where
         Self {
             reader: BufReader::new(reader),
             buf: Vec::with_capacity(1024),
+            max_message_bytes: super::DEFAULT_MAX_MESSAGE_BYTES,
+            resync: false,
+            tap: None,
+            tags: TagIndices::default(),
         }
     }
 
+    /// Create a new Decoder from this [std::io::Read] instance with a specific buffer `capacity`
+    ///
+    /// This is used for both the read buffer and the initial line buffer. The line buffer will grow
+    /// as needed, up to the maximum message length.
+    pub fn with_capacity(reader: R, capacity: usize) -> Self {
+        Self {
+            reader: BufReader::with_capacity(capacity, reader),
+            buf: Vec::with_capacity(capacity),
+            max_message_bytes: super::DEFAULT_MAX_MESSAGE_BYTES,
+            resync: false,
+            tap: None,
+            tags: TagIndices::default(),
+        }
+    }
+
+    /// Set the maximum length of a message, in bytes.
+    ///
+    /// If a line is longer than this, [DecodeError::LineTooLong] will be
+    /// returned and the rest of the line will be skipped on the next read.
+    ///
+    /// This defaults to [DEFAULT_MAX_MESSAGE_BYTES](super::DEFAULT_MAX_MESSAGE_BYTES)
+    pub fn max_message_bytes(mut self, max: usize) -> Self {
+        self.max_message_bytes = max.max(1);
+        self
+    }
+
+    /// Write every line that's read to `tap`, before it's parsed
+    ///
+    /// This is useful for recording the messages (e.g. to a log file) for debugging.
+    /// The lines are written as they were read, including the `\r\n`.
+    ///
+    /// Lines that are too long aren't written. If writing to the tap fails, the read
+    /// returns a [DecodeError::Io].
+    ///
+    /// ```rust
+    /// # use twitchchat::Decoder;
+    /// let input = ":test!test@test PRIVMSG #museun :hello\r\n";
+    /// let log = std::env::temp_dir().join("twitchchat-tap.log");
+    ///
+    /// let mut decoder = Decoder::new(input.as_bytes())
+    ///     .with_tap(std::fs::File::create(&log).unwrap());
+    /// let msg = decoder.read_message().unwrap();
+    ///
+    /// assert_eq!(std::fs::read_to_string(&log).unwrap(), msg.get_raw());
+    /// # std::fs::remove_file(&log).unwrap();
+    /// ```
+    pub fn with_tap<W>(mut self, tap: W) -> Self
+    where
+        W: Write + Send + Sync + 'static,
+    {
+        self.tap.replace(Box::new(tap));
+        self
+    }
+
     /// Read the next message.
     ///
     /// This returns a borrowed [IrcMessage] which is valid until the next Decoder call is made.
     ///
     /// If you just want an owned one, use the [Decoder] as an iterator. e.g. dec.next().
     pub fn read_message(&mut self) -> Result<IrcMessage<'_>, DecodeError> {
+        let n = self.read_line()?;
+        parse_line(&self.buf[..n])
+    }
+
+    /// Read the next message, along with the [TagIndices] for its tags.
+    ///
+    /// The indices are rebuilt in a buffer owned by the decoder, so reading a
+    /// steady stream of messages doesn't allocate for the tags. Both are valid
+    /// until the next Decoder call is made, clone the indices to keep them.
+    ///
+    /// If the message has no tags, or they are malformed, the indices will be empty.
+    ///
+    /// ```rust
+    /// # use twitchchat::{Decoder, irc::Tags};
+    /// let input = "@color=#FF69B4;display-name=museun :museun!museun@museun PRIVMSG #museun :hello\r\n";
+    ///
+    /// let mut decoder = Decoder::new(input.as_bytes());
+    /// let (msg, indices) = decoder.read_message_with_tags().unwrap();
+    ///
+    /// let tags = Tags::from_data_indices(&msg.raw, indices);
+    /// assert_eq!(tags.get("display-name"), Some("museun"));
+    /// ```
+    pub fn read_message_with_tags(
+        &mut self,
+    ) -> Result<(IrcMessage<'_>, &TagIndices), DecodeError> {
+        let n = self.read_line()?;
+        let msg = parse_line(&self.buf[..n])?;
+        self.tags.rebuild(&msg);
+        Ok((msg, &self.tags))
+    }
+
+    // read the next line into the buffer, returning its length
+    fn read_line(&mut self) -> Result<usize, DecodeError> {
+        if self.resync {
+            if !self.skip_line()? {
+                return Err(DecodeError::Eof);
+            }
+            self.resync = false;
+        }
+
         self.buf.clear();
-        let n = self
-            .reader
+        let n = (&mut self.reader)
+            .take(self.max_message_bytes as u64)
             .read_until(b'\n', &mut self.buf)
             .map_err(DecodeError::Io)?;
         if n == 0 {
             return Err(DecodeError::Eof);
         }
 
-        let str = std::str::from_utf8(&self.buf[..n]).map_err(DecodeError::InvalidUtf8)?;
+        if n == self.max_message_bytes && !self.buf.ends_with(b"\n") {
+            self.resync = true;
+            return Err(DecodeError::LineTooLong);
+        }
 
-        // this should only ever parse 1 message
-        crate::irc::parse_one(str)
-            .map_err(DecodeError::ParseError)
-            .map(|(_, msg)| msg)
+        if let Some(tap) = &mut self.tap {
+            tap.write_all(&self.buf[..n]).map_err(DecodeError::Io)?;
+        }
+        Ok(n)
+    }
+
+    // skip the rest of the current line, returning false if EOF was reached
+    fn skip_line(&mut self) -> Result<bool, DecodeError> {
+        loop {
+            let buf = self.reader.fill_buf().map_err(DecodeError::Io)?;
+            if buf.is_empty() {
+                return Ok(false);
+            }
+
+            match buf.iter().position(|&c| c == b'\n') {
+                Some(pos) => {
+                    self.reader.consume(pos + 1);
+                    return Ok(true);
+                }
+                None => {
+                    let len = buf.len();
+                    self.reader.consume(len)
+                }
+            }
+        }
     }
 
     /// Returns an iterator over messages.
@@ -99,6 +240,61 @@ where
     pub fn into_inner(self) -> R {
         self.reader.into_inner()
     }
+
+    /// Consume the decoder, returning an iterator that produces both the [IrcMessage] and its [Commands]
+    ///
+    /// The [IrcMessage] is a copy of the one stored in the [Commands] (see
+    /// [Commands::as_raw]), so they'll always be consistent. If you don't need
+    /// to own them separately, use [Commands::as_raw] rather than this.
+    pub fn into_commands(self) -> IntoCommands<R> {
+        IntoCommands { decoder: self }
+    }
+}
+
+fn parse_line(line: &[u8]) -> Result<IrcMessage<'_>, DecodeError> {
+    let str = std::str::from_utf8(line).map_err(DecodeError::InvalidUtf8)?;
+
+    // this should only ever parse 1 message
+    crate::irc::parse_one(str)
+        .map_err(DecodeError::ParseError)
+        .map(|(_, msg)| msg)
+}
+
+/// An iterator that produces both the [IrcMessage] and the [Commands] parsed from it
+///
+/// This is created by [Decoder::into_commands]
+pub struct IntoCommands<R> {
+    decoder: Decoder<R>,
+}
+
+impl<R> std::fmt::Debug for IntoCommands<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntoCommands").finish()
+    }
+}
+
+impl<R: Read> IntoCommands<R> {
+    /// Consume the iterator returning the inner Reader
+    pub fn into_inner(self) -> R {
+        self.decoder.into_inner()
+    }
+}
+
+/// This will produce `Result<(IrcMessage<'static>, Commands<'static>), DecodeError>` until an `Eof` is received
+impl<R: Read> Iterator for IntoCommands<R> {
+    type Item = Result<(IrcMessage<'static>, Commands<'static>), DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let msg = match self.decoder.next()? {
+            Ok(msg) => msg,
+            Err(err) => return Some(Err(err)),
+        };
+
+        // the line is only parsed once, but both halves of the pair own it so
+        // the message stored in the Commands is cloned (one allocation)
+        let cmd = Commands::from_irc(msg).map_err(DecodeError::CommandError);
+        Some(cmd.map(|cmd| (cmd.as_raw().clone(), cmd)))
+    }
 }
 
 /// This will produce `Result<IrcMessage<'static>, DecodeError>` until an `Eof` is received
@@ -139,4 +335,142 @@ mod tests {
         }
         assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof))
     }
+
+    #[test]
+    fn read_with_capacity() {
+        let data = format!("PRIVMSG #museun :{}\r\n", "a".repeat(100));
+        let mut dec = Decoder::with_capacity(data.as_bytes(), 4);
+        let msg = dec.read_message().unwrap();
+        assert_eq!(msg.get_raw(), data);
+        assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof))
+    }
+
+    #[test]
+    fn read_line_too_long() {
+        let data = "a".repeat(20 * 1024);
+        let mut dec = Decoder::new(data.as_bytes());
+        assert!(matches!(
+            dec.read_message().unwrap_err(),
+            DecodeError::LineTooLong
+        ));
+        assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof))
+    }
+
+    #[test]
+    fn read_line_too_long_resync() {
+        let data = format!("{}\r\nPING :1234567890\r\n", "a".repeat(20 * 1024));
+        let mut dec = Decoder::new(data.as_bytes());
+        assert!(matches!(
+            dec.read_message().unwrap_err(),
+            DecodeError::LineTooLong
+        ));
+
+        let msg = dec.read_message().unwrap();
+        assert_eq!(msg.get_raw(), "PING :1234567890\r\n");
+        assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof))
+    }
+
+    #[test]
+    fn read_max_message_bytes() {
+        let data = "PING :1234567890\r\nPING :1\r\n";
+        let mut dec = Decoder::new(data.as_bytes()).max_message_bytes(10);
+        assert!(matches!(
+            dec.read_message().unwrap_err(),
+            DecodeError::LineTooLong
+        ));
+        assert_eq!(dec.read_message().unwrap().get_raw(), "PING :1\r\n");
+    }
+
+    #[test]
+    fn read_message_with_tags() {
+        use crate::{irc::Tags, validator::Validator as _};
+
+        let data = "@a=1;b=2 :test!test@test PRIVMSG #museun :hello\r\n\
+                    :test!test@test PRIVMSG #museun :no tags\r\n\
+                    @c=3;=4 PING :malformed\r\n\
+                    @badge-info=;badges=broadcaster/1;color=#FF69B4;display-name=museun :test!test@test PRIVMSG #museun :world\r\n";
+
+        let mut dec = Decoder::new(data.as_bytes());
+        for _ in 0..4 {
+            let (msg, indices) = dec.read_message_with_tags().unwrap();
+            // the owned indices are the same as the ones the messages build
+            assert_eq!(indices.clone(), msg.parse_tags());
+        }
+
+        let mut dec = Decoder::new(data.as_bytes());
+        let (msg, indices) = dec.read_message_with_tags().unwrap();
+        let tags = Tags::from_data_indices(&msg.raw, indices);
+        assert_eq!(tags.get("a"), Some("1"));
+        assert_eq!(tags.get("b"), Some("2"));
+
+        let (_, indices) = dec.read_message_with_tags().unwrap();
+        assert!(indices.is_empty());
+        let (_, indices) = dec.read_message_with_tags().unwrap();
+        assert!(indices.is_empty());
+        let (_, indices) = dec.read_message_with_tags().unwrap();
+        assert_eq!(indices.len(), 4);
+
+        assert!(matches!(
+            dec.read_message_with_tags().unwrap_err(),
+            DecodeError::Eof
+        ));
+    }
+
+    #[test]
+    fn into_commands() {
+        let data = b":test!test@test PRIVMSG #museun :hello world\r\nPING :1234567890\r\n".to_vec();
+        let reader = std::io::Cursor::new(data);
+
+        let v = Decoder::new(reader)
+            .into_commands()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(v.len(), 2);
+
+        let (msg, cmd) = &v[0];
+        assert_eq!(msg.get_raw(), cmd.raw());
+        match cmd {
+            Commands::Privmsg(pm) => assert_eq!(pm.data(), "hello world"),
+            cmd => panic!("unexpected: {:?}", cmd),
+        }
+
+        let (msg, cmd) = &v[1];
+        assert_eq!(msg.get_raw(), cmd.raw());
+        match cmd {
+            Commands::Ping(ping) => assert_eq!(ping.token(), "1234567890"),
+            cmd => panic!("unexpected: {:?}", cmd),
+        }
+    }
+
+    #[test]
+    fn read_with_tap() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default, Clone)]
+        struct Tap(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Tap {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let data = b":test!test@test PRIVMSG #museun :hello world\r\nPING :1234567890\r\n";
+        let tap = Tap::default();
+        let mut decoder = Decoder::new(&data[..]).with_tap(tap.clone());
+
+        let msg = decoder.read_message().unwrap();
+        assert_eq!(msg.get_command(), "PRIVMSG");
+        assert_eq!(
+            &*tap.0.lock().unwrap(),
+            b":test!test@test PRIVMSG #museun :hello world\r\n"
+        );
+
+        let msg = decoder.read_message().unwrap();
+        assert_eq!(msg.get_command(), "PING");
+        assert_eq!(&*tap.0.lock().unwrap(), data);
+    }
 }
@@ -1,5 +1,6 @@
 use std::{
-    io::{Result as IoResult, Write},
+    fmt,
+    io::{Error, ErrorKind, Result as IoResult, Write},
     rc::Rc,
     sync::Arc,
 };
@@ -10,6 +11,31 @@ pub trait Encodable {
     fn encode<W>(&self, buf: &mut W) -> IoResult<()>
     where
         W: Write + ?Sized;
+
+    /// Encode this message to the provided [std::fmt::Write] implementation
+    ///
+    /// This writes directly into the `fmt::Write`, so no intermediate byte buffer is needed.
+    ///
+    /// # Errors
+    /// If the encoded bytes aren't valid UTF-8 (e.g. an arbitrary byte slice) this will return an error.
+    fn encode_str(&self, buf: &mut dyn fmt::Write) -> fmt::Result {
+        self.encode(&mut FmtWriter(buf)).map_err(|_| fmt::Error)
+    }
+}
+
+// adapts a fmt::Write so the io::Write-based encoding can be reused
+struct FmtWriter<'a>(&'a mut dyn fmt::Write);
+
+impl<'a> Write for FmtWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let s = std::str::from_utf8(buf).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        self.0.write_str(s).map_err(Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
 }
 
 impl<T> Encodable for &T
@@ -42,6 +68,24 @@ impl Encodable for String {
     }
 }
 
+impl Encodable for crate::IrcMessage<'_> {
+    fn encode<W>(&self, buf: &mut W) -> IoResult<()>
+    where
+        W: Write + ?Sized,
+    {
+        encode_line(self.get_raw(), buf)
+    }
+}
+
+// write a line that was parsed, making sure it ends with the `\r\n` the parser allows to be missing
+pub(crate) fn encode_line<W>(line: &str, buf: &mut W) -> IoResult<()>
+where
+    W: Write + ?Sized,
+{
+    buf.write_all(line.trim_end_matches(&['\r', '\n'][..]).as_bytes())?;
+    buf.write_all(b"\r\n")
+}
+
 macro_rules! encodable_byte_slice {
     ($($ty:ty)*) => {
         $(impl Encodable for $ty {
@@ -59,3 +103,49 @@ encodable_byte_slice! {
     Arc<[u8]>
     Vec<u8>
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_str() {
+        let mut out = String::new();
+        crate::commands::join("museun")
+            .encode_str(&mut out)
+            .unwrap();
+        crate::commands::privmsg("museun", "hello")
+            .encode_str(&mut out)
+            .unwrap();
+        assert_eq!(out, "JOIN #museun\r\nPRIVMSG #museun :hello\r\n");
+    }
+
+    #[test]
+    fn encode_parsed_messages() {
+        use crate::{messages::*, FromIrcMessage as _};
+
+        let input = "@badge-info=;badges=broadcaster/1;color=#FF69B4;display-name=museun;emotes=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello world\r\n";
+        let msg = crate::irc::parse_one(input).unwrap().1;
+
+        let mut out = vec![];
+        msg.encode(&mut out).unwrap();
+        Privmsg::from_irc(msg.clone())
+            .unwrap()
+            .encode(&mut out)
+            .unwrap();
+        Commands::from_irc(msg).unwrap().encode(&mut out).unwrap();
+        assert_eq!(out, input.repeat(3).as_bytes());
+
+        // the trailing \r\n is optional when parsing, but not when encoding
+        let msg = IrcMessage::parse_lossy(b"PING :1234567890").unwrap();
+        let mut out = String::new();
+        Ping::from_irc(msg).unwrap().encode_str(&mut out).unwrap();
+        assert_eq!(out, "PING :1234567890\r\n");
+    }
+
+    #[test]
+    fn encode_str_invalid_utf8() {
+        let mut out = String::new();
+        assert!([0xF0, 0x28, 0x8C, 0x28][..].encode_str(&mut out).is_err());
+    }
+}
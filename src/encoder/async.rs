@@ -144,11 +144,184 @@ where
     }
 }
 
+// once this much is buffered, the sink writes it out before it accepts more
+#[cfg(feature = "sink")]
+const SINK_BUFFER_LIMIT: usize = 8 * 1024;
+
+#[cfg(feature = "sink")]
+impl<W> AsyncEncoder<W>
+where
+    W: AsyncWrite + Unpin + Send + Sync,
+{
+    // writes out the buffered data, without flushing the writer
+    fn poll_write_buffered(&mut self, ctx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        while self.pos < self.data.len() {
+            let writer = &mut self.writer;
+            futures_lite::pin!(writer);
+            match futures_lite::ready!(writer.poll_write(ctx, &self.data[self.pos..])) {
+                Ok(0) => return Poll::Ready(Err(std::io::ErrorKind::WriteZero.into())),
+                Ok(n) => self.pos += n,
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+
+        self.data.clear();
+        self.pos = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// This buffers encoded messages until the sink is flushed
+///
+/// If more than 8 KiB is buffered, `poll_ready` writes it out (waiting on the writer) first.
+#[cfg(feature = "sink")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sink")))]
+impl<W, M> futures_sink::Sink<M> for AsyncEncoder<W>
+where
+    W: AsyncWrite + Unpin + Send + Sync,
+    M: crate::Encodable,
+{
+    type Error = std::io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+        if this.data.len() - this.pos < SINK_BUFFER_LIMIT {
+            return Poll::Ready(Ok(()));
+        }
+        this.poll_write_buffered(ctx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: M) -> IoResult<()> {
+        item.encode(&mut self.get_mut().data)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+        futures_lite::ready!(this.poll_write_buffered(ctx))?;
+
+        let writer = &mut this.writer;
+        futures_lite::pin!(writer);
+        writer.poll_flush(ctx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        futures_lite::ready!(<Self as futures_sink::Sink<M>>::poll_flush(self.as_mut(), ctx))?;
+        let writer = &mut self.get_mut().writer;
+        futures_lite::pin!(writer);
+        writer.poll_close(ctx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::commands::join;
 
+    #[test]
+    #[cfg(feature = "sink")]
+    fn encoder_sink() {
+        use crate::commands::types::Join;
+        use futures_lite::{future::poll_fn, StreamExt as _};
+        use futures_sink::Sink;
+
+        async fn send<S, M>(sink: &mut S, item: M) -> Result<(), S::Error>
+        where
+            S: Sink<M> + Unpin,
+        {
+            poll_fn(|ctx| Pin::new(&mut *sink).poll_ready(ctx)).await?;
+            Pin::new(&mut *sink).start_send(item)
+        }
+
+        async fn flush<S, M>(sink: &mut S) -> Result<(), S::Error>
+        where
+            S: Sink<M> + Unpin,
+        {
+            poll_fn(|ctx| Pin::new(&mut *sink).poll_flush(ctx)).await
+        }
+
+        let fut = async move {
+            let mut output = vec![];
+            {
+                let mut encoder = AsyncEncoder::new(&mut output);
+
+                let mut stream = futures_lite::stream::iter(vec![
+                    join("#museun"),
+                    join("#shaken_bot"),
+                    join("#twitch"),
+                ]);
+
+                while let Some(cmd) = stream.next().await {
+                    send(&mut encoder, cmd).await.unwrap();
+                }
+
+                // nothing is written until its flushed
+                assert!(encoder.writer.is_empty());
+
+                flush::<_, Join<'_>>(&mut encoder).await.unwrap();
+            }
+
+            let s = std::str::from_utf8(&output).unwrap();
+            assert_eq!(s, "JOIN #museun\r\nJOIN #shaken_bot\r\nJOIN #twitch\r\n");
+        };
+        futures_lite::future::block_on(fut);
+    }
+
+    #[test]
+    #[cfg(feature = "sink")]
+    fn encoder_sink_backpressure() {
+        use futures_lite::future::{block_on, poll_once};
+        use futures_sink::Sink;
+
+        // a writer that never accepts anything
+        struct Stuck;
+
+        impl AsyncWrite for Stuck {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                _ctx: &mut Context<'_>,
+                _buf: &[u8],
+            ) -> Poll<IoResult<usize>> {
+                Poll::Pending
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _ctx: &mut Context<'_>) -> Poll<IoResult<()>> {
+                Poll::Pending
+            }
+
+            fn poll_close(self: Pin<&mut Self>, _ctx: &mut Context<'_>) -> Poll<IoResult<()>> {
+                Poll::Pending
+            }
+        }
+
+        fn ready(encoder: &mut AsyncEncoder<impl AsyncWrite + Unpin + Send + Sync>) -> bool {
+            let fut = futures_lite::future::poll_fn(|ctx| {
+                Sink::<crate::commands::types::Join<'_>>::poll_ready(Pin::new(&mut *encoder), ctx)
+            });
+            block_on(poll_once(fut)).is_some()
+        }
+
+        let mut encoder = AsyncEncoder::new(Stuck);
+        let mut sent = 0;
+        while ready(&mut encoder) {
+            Pin::new(&mut encoder).start_send(join("#museun")).unwrap();
+            sent += 1;
+        }
+        // it stops accepting messages once the buffer is full
+        let len = "JOIN #museun\r\n".len();
+        assert!(sent * len >= SINK_BUFFER_LIMIT);
+        assert!((sent - 1) * len < SINK_BUFFER_LIMIT);
+
+        let mut output = vec![];
+        let mut encoder = AsyncEncoder::new(&mut output);
+        for _ in 0..sent {
+            assert!(ready(&mut encoder));
+            Pin::new(&mut encoder).start_send(join("#museun")).unwrap();
+        }
+        // a writer that accepts it gets the buffer written out before it's flushed
+        assert!(ready(&mut encoder));
+        assert_eq!(output.len(), sent * len);
+    }
+
     #[test]
     fn encoder_async() {
         let fut = async move {
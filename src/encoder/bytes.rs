@@ -0,0 +1,124 @@
+use crate::Encodable;
+use bytes::{BufMut as _, Bytes, BytesMut};
+use std::io::{Result as IoResult, Write};
+
+/// An encoder that accumulates messages into a reusable [BytesMut](bytes::BytesMut)
+///
+/// This is useful for pipelining many commands into a single write. Messages
+/// are appended with [BytesEncoder::encode] and the whole batch is taken with
+/// [BytesEncoder::take], which leaves the encoder empty (but keeps its
+/// allocation around once the batch is dropped).
+///
+/// ```
+/// use twitchchat::{commands, encoder::BytesEncoder};
+///
+/// let mut enc = BytesEncoder::new();
+/// enc.encode(commands::join("museun")).unwrap();
+/// enc.encode(commands::privmsg("museun", "hello")).unwrap();
+///
+/// let batch = enc.take();
+/// assert_eq!(&*batch, b"JOIN #museun\r\nPRIVMSG #museun :hello\r\n");
+/// assert!(enc.is_empty());
+/// ```
+#[derive(Default, Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+pub struct BytesEncoder {
+    buf: BytesMut,
+}
+
+impl std::fmt::Debug for BytesEncoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BytesEncoder")
+            .field("len", &self.buf.len())
+            .finish()
+    }
+}
+
+impl BytesEncoder {
+    /// Create a new, empty BytesEncoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new BytesEncoder with room for `capacity` bytes
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: BytesMut::with_capacity(capacity),
+        }
+    }
+
+    /// Encode this [Encodable] message, appending it to the buffer
+    pub fn encode<M>(&mut self, msg: M) -> IoResult<()>
+    where
+        M: Encodable,
+    {
+        msg.encode(&mut (&mut self.buf).writer())
+    }
+
+    /// Take the encoded messages, leaving the encoder empty
+    pub fn take(&mut self) -> Bytes {
+        self.buf.split().freeze()
+    }
+
+    /// Get the encoded messages that haven't been taken yet
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Get the number of encoded bytes that haven't been taken yet
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Checks whether there are no encoded bytes waiting to be taken
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Get the inner [BytesMut](bytes::BytesMut) out
+    pub fn into_inner(self) -> BytesMut {
+        self.buf
+    }
+}
+
+impl Write for BytesEncoder {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{join, part, privmsg};
+
+    #[test]
+    fn bytes_encoder() {
+        let mut encoder = BytesEncoder::with_capacity(64);
+
+        encoder.encode(join("#museun")).unwrap();
+        encoder.encode(privmsg("#museun", "hello world")).unwrap();
+        encoder.encode(part("#museun")).unwrap();
+        assert_eq!(
+            encoder.as_bytes(),
+            &b"JOIN #museun\r\nPRIVMSG #museun :hello world\r\nPART #museun\r\n"[..]
+        );
+
+        let batch = encoder.take();
+        assert_eq!(
+            &*batch,
+            &b"JOIN #museun\r\nPRIVMSG #museun :hello world\r\nPART #museun\r\n"[..]
+        );
+        assert!(encoder.is_empty());
+
+        // and it can be reused after taking
+        encoder.encode(join("#shaken_bot")).unwrap();
+        assert_eq!(&*encoder.take(), &b"JOIN #shaken_bot\r\n"[..]);
+        assert!(encoder.take().is_empty());
+    }
+}
@@ -37,6 +37,11 @@
 //! assert_eq!(string, "JOIN #museun\r\nits also a writer\r\n");
 //! ```
 
+#[cfg(feature = "bytes")]
+mod bytes;
+#[cfg(feature = "bytes")]
+pub use self::bytes::BytesEncoder;
+
 cfg_async! {
     mod r#async;
     pub use r#async::*;
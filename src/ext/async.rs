@@ -0,0 +1,155 @@
+use super::message_id;
+use crate::{messages::Privmsg, writer::AsyncWriter};
+
+use futures_lite::AsyncWrite;
+use std::{future::Future, io::Result, pin::Pin};
+
+/// A boxed future returned by the [AsyncPrivmsgExt] methods
+pub type ExtFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// Extensions to the `Privmsg` message type, for an [AsyncWriter]
+///
+/// This mirrors [PrivmsgExt](crate::PrivmsgExt).
+///
+/// # NOTE
+/// An `AsyncWriter<MpscWriter>` is also a `std::io::Write`, so if you import both
+/// traits you'll have to disambiguate the calls.
+pub trait AsyncPrivmsgExt {
+    /// Reply to this message with `data`
+    fn reply<'a>(&'a mut self, msg: &'a Privmsg<'_>, data: &'a str) -> ExtFuture<'a>;
+
+    /// Send a message back to the channel this Privmsg came from
+    fn say<'a>(&'a mut self, msg: &'a Privmsg<'_>, data: &'a str) -> ExtFuture<'a>;
+
+    /// Permanently ban the sender of this message from the channel it came from
+    ///
+    /// See [ban](crate::commands::ban()) for details on the `reason`
+    fn ban<'a>(&'a mut self, msg: &'a Privmsg<'_>, reason: Option<&'a str>) -> ExtFuture<'a>;
+
+    /// Timeout the sender of this message in the channel it came from
+    ///
+    /// See [timeout](crate::commands::timeout()) for the format of `duration`
+    fn timeout<'a>(
+        &'a mut self,
+        msg: &'a Privmsg<'_>,
+        duration: Option<&'a str>,
+        reason: Option<&'a str>,
+    ) -> ExtFuture<'a>;
+
+    /// Delete this message from the channel it came from
+    fn delete<'a>(&'a mut self, msg: &'a Privmsg<'_>) -> ExtFuture<'a>;
+}
+
+impl<W> AsyncPrivmsgExt for AsyncWriter<W>
+where
+    W: AsyncWrite + Unpin + Send + Sync,
+{
+    fn reply<'a>(&'a mut self, msg: &'a Privmsg<'_>, data: &'a str) -> ExtFuture<'a> {
+        Box::pin(async move {
+            let cmd = crate::commands::reply(msg.channel(), message_id(msg)?, data);
+            self.encode(cmd).await
+        })
+    }
+
+    fn say<'a>(&'a mut self, msg: &'a Privmsg<'_>, data: &'a str) -> ExtFuture<'a> {
+        Box::pin(async move {
+            let cmd = crate::commands::privmsg(msg.channel(), data);
+            self.encode(cmd).await
+        })
+    }
+
+    fn ban<'a>(&'a mut self, msg: &'a Privmsg<'_>, reason: Option<&'a str>) -> ExtFuture<'a> {
+        Box::pin(async move {
+            let cmd = crate::commands::ban(msg.channel(), msg.name(), reason);
+            self.encode(cmd).await
+        })
+    }
+
+    fn timeout<'a>(
+        &'a mut self,
+        msg: &'a Privmsg<'_>,
+        duration: Option<&'a str>,
+        reason: Option<&'a str>,
+    ) -> ExtFuture<'a> {
+        Box::pin(async move {
+            let cmd = crate::commands::timeout(msg.channel(), msg.name(), duration, reason);
+            self.encode(cmd).await
+        })
+    }
+
+    fn delete<'a>(&'a mut self, msg: &'a Privmsg<'_>) -> ExtFuture<'a> {
+        Box::pin(async move {
+            let cmd = crate::commands::delete(msg.channel(), message_id(msg)?);
+            self.encode(cmd).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{channel::Receiver, irc, writer::MpscWriter, FromIrcMessage as _};
+    use futures_lite::future::block_on;
+
+    const TAGGED: &str = "@badge-info=;badges=;color=;display-name=Someone;emotes=;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=12345;user-type= :someone!someone@someone.tmi.twitch.tv PRIVMSG #museun :spam spam spam\r\n";
+
+    fn privmsg(input: &str) -> Privmsg<'_> {
+        Privmsg::from_irc(irc::parse(input).next().unwrap().unwrap()).unwrap()
+    }
+
+    // an in-memory writer, like the one the AsyncRunner hands out
+    fn writer() -> (AsyncWriter<MpscWriter>, Receiver<Box<[u8]>>, Receiver<()>) {
+        let (tx, rx) = crate::channel::unbounded();
+        let (activity_tx, activity_rx) = crate::channel::unbounded();
+        let writer = AsyncWriter::new(MpscWriter::new(tx), activity_tx);
+        (writer, rx, activity_rx)
+    }
+
+    fn written(rx: &Receiver<Box<[u8]>>) -> String {
+        let data = rx.try_recv().unwrap();
+        String::from_utf8(data.into()).unwrap()
+    }
+
+    #[test]
+    fn async_privmsg_ext() {
+        let msg = privmsg(TAGGED);
+        let (mut writer, rx, activity) = writer();
+
+        block_on(async {
+            writer.reply(&msg, "hello").await.unwrap();
+            assert_eq!(
+                written(&rx),
+                "@reply-parent-msg-id=4e160a53-5482-4764-ba28-f224cd59a51f PRIVMSG #museun :hello\r\n"
+            );
+
+            writer.say(&msg, "hello").await.unwrap();
+            assert_eq!(written(&rx), "PRIVMSG #museun :hello\r\n");
+
+            writer.ban(&msg, Some("spamming")).await.unwrap();
+            assert_eq!(written(&rx), "PRIVMSG #museun :/ban someone spamming\r\n");
+
+            writer.timeout(&msg, Some("1h"), None).await.unwrap();
+            assert_eq!(written(&rx), "PRIVMSG #museun :/timeout someone 1h\r\n");
+
+            writer.delete(&msg).await.unwrap();
+            assert_eq!(
+                written(&rx),
+                "PRIVMSG #museun :/delete 4e160a53-5482-4764-ba28-f224cd59a51f\r\n"
+            );
+        });
+
+        assert!(rx.try_recv().is_none());
+        // every write notified the runner
+        assert_eq!(std::iter::from_fn(|| activity.try_recv()).count(), 5);
+    }
+
+    #[test]
+    fn async_privmsg_ext_without_tags() {
+        let msg = privmsg(":someone!someone@someone.tmi.twitch.tv PRIVMSG #museun :spam\r\n");
+        let (mut writer, rx, _activity) = writer();
+
+        let err = block_on(writer.delete(&msg)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(rx.try_recv().is_none());
+    }
+}
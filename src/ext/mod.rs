@@ -0,0 +1,18 @@
+use crate::messages::Privmsg;
+
+cfg_async! {
+    mod r#async;
+    pub use r#async::*;
+}
+
+mod sync;
+pub use sync::*;
+
+fn message_id<'a>(msg: &'a Privmsg<'_>) -> std::io::Result<&'a str> {
+    msg.tags().get("id").ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "you must have `TAGS` enabled",
+        )
+    })
+}
@@ -0,0 +1,126 @@
+use super::message_id;
+use crate::{messages::Privmsg, Encodable};
+use std::io::Write;
+
+/// Extensions to the `Privmsg` message type
+pub trait PrivmsgExt {
+    /// Reply to this message with `data`
+    fn reply(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()>;
+
+    /// Send a message back to the channel this Privmsg came from
+    fn say(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()>;
+
+    /// Permanently ban the sender of this message from the channel it came from
+    ///
+    /// See [ban](crate::commands::ban()) for details on the `reason`
+    fn ban(&mut self, msg: &Privmsg<'_>, reason: Option<&str>) -> std::io::Result<()>;
+
+    /// Timeout the sender of this message in the channel it came from
+    ///
+    /// See [timeout](crate::commands::timeout()) for the format of `duration`
+    fn timeout(
+        &mut self,
+        msg: &Privmsg<'_>,
+        duration: Option<&str>,
+        reason: Option<&str>,
+    ) -> std::io::Result<()>;
+
+    /// Delete this message from the channel it came from
+    fn delete(&mut self, msg: &Privmsg<'_>) -> std::io::Result<()>;
+}
+
+impl<W: Write + ?Sized> PrivmsgExt for W {
+    fn reply(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()> {
+        let cmd = crate::commands::reply(msg.channel(), message_id(msg)?, data);
+        cmd.encode(self)?;
+        self.flush()
+    }
+
+    fn say(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()> {
+        let cmd = crate::commands::privmsg(msg.channel(), data);
+        cmd.encode(self)?;
+        self.flush()
+    }
+
+    fn ban(&mut self, msg: &Privmsg<'_>, reason: Option<&str>) -> std::io::Result<()> {
+        let cmd = crate::commands::ban(msg.channel(), msg.name(), reason);
+        cmd.encode(self)?;
+        self.flush()
+    }
+
+    fn timeout(
+        &mut self,
+        msg: &Privmsg<'_>,
+        duration: Option<&str>,
+        reason: Option<&str>,
+    ) -> std::io::Result<()> {
+        let cmd = crate::commands::timeout(msg.channel(), msg.name(), duration, reason);
+        cmd.encode(self)?;
+        self.flush()
+    }
+
+    fn delete(&mut self, msg: &Privmsg<'_>) -> std::io::Result<()> {
+        let cmd = crate::commands::delete(msg.channel(), message_id(msg)?);
+        cmd.encode(self)?;
+        self.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{irc, FromIrcMessage as _};
+
+    fn privmsg(input: &str) -> Privmsg<'_> {
+        Privmsg::from_irc(irc::parse(input).next().unwrap().unwrap()).unwrap()
+    }
+
+    fn encoded(func: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> String {
+        let mut out = vec![];
+        func(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    const TAGGED: &str = "@badge-info=;badges=;color=;display-name=Someone;emotes=;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=12345;user-type= :someone!someone@someone.tmi.twitch.tv PRIVMSG #museun :spam spam spam\r\n";
+
+    #[test]
+    fn ban() {
+        let msg = privmsg(TAGGED);
+        assert_eq!(
+            encoded(|w| w.ban(&msg, None)),
+            "PRIVMSG #museun :/ban someone\r\n"
+        );
+        assert_eq!(
+            encoded(|w| w.ban(&msg, Some("spamming"))),
+            "PRIVMSG #museun :/ban someone spamming\r\n"
+        );
+    }
+
+    #[test]
+    fn timeout() {
+        let msg = privmsg(TAGGED);
+        assert_eq!(
+            encoded(|w| w.timeout(&msg, None, None)),
+            "PRIVMSG #museun :/timeout someone\r\n"
+        );
+        assert_eq!(
+            encoded(|w| w.timeout(&msg, Some("1h"), Some("spamming"))),
+            "PRIVMSG #museun :/timeout someone 1h spamming\r\n"
+        );
+    }
+
+    #[test]
+    fn delete() {
+        let msg = privmsg(TAGGED);
+        assert_eq!(
+            encoded(|w| w.delete(&msg)),
+            "PRIVMSG #museun :/delete 4e160a53-5482-4764-ba28-f224cd59a51f\r\n"
+        );
+
+        let msg = privmsg(":someone!someone@someone.tmi.twitch.tv PRIVMSG #museun :spam\r\n");
+        let mut out = vec![];
+        let err = out.delete(&msg).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(out.is_empty());
+    }
+}
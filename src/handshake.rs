@@ -0,0 +1,332 @@
+//! A synchronous handshake, for those driving their own loop
+//!
+//! The [AsyncRunner](crate::AsyncRunner) does this for you when it connects,
+//! but if you're using a [Decoder](crate::Decoder) and an
+//! [Encoder](crate::Encoder) directly you can use [register_and_capture] to
+//! register the connection and get the same identity information.
+//!
+//! ```no_run
+//! use twitchchat::{handshake, twitch::Capability, UserConfig};
+//!
+//! let config = UserConfig::builder()
+//!     .name("museun")
+//!     .token(format!("oauth:{}", "a".repeat(30)))
+//!     .capabilities(&[Capability::Tags, Capability::Commands])
+//!     .build()
+//!     .unwrap();
+//!
+//! // this can be anything that is `std::io::Read + std::io::Write`
+//! let mut stream = std::net::TcpStream::connect(twitchchat::TWITCH_IRC_ADDRESS).unwrap();
+//!
+//! let result = handshake::register_and_capture(&config, &mut stream).unwrap();
+//! println!("connected as {}", result.name);
+//! if let Some(state) = result.global_user_state {
+//!     println!("user-id: {:?}", state.user_id());
+//! }
+//!
+//! // and then continue reading messages with a Decoder
+//! for msg in twitchchat::Decoder::new(&mut stream) {
+//!     println!("{:?}", msg.unwrap());
+//! }
+//! ```
+use crate::{
+    commands,
+    messages::{Commands, GlobalUserState},
+    runner::{Capabilities, Error},
+    twitch::Capability,
+    Decoder, Encodable as _, FromIrcMessage as _, IntoOwned as _, UserConfig,
+};
+
+use std::{
+    collections::HashSet,
+    io::{Read, Write},
+};
+
+/// The information captured during [register_and_capture]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct HandshakeResult {
+    /// The name Twitch will refer to you as
+    pub name: String,
+    /// Your global state, if Twitch sent it
+    ///
+    /// This is only sent if both the `Tags` and `Commands` capabilities were acknowledged
+    pub global_user_state: Option<GlobalUserState<'static>>,
+    /// The capabilities Twitch acknowledged (or refused)
+    pub capabilities: Capabilities,
+    /// Every message read during the handshake, in order
+    pub messages: Vec<Commands<'static>>,
+}
+
+/// Register the connection and capture what Twitch sends back
+///
+/// This writes the [register](crate::commands::register) commands to `io`,
+/// replies to any `PING`s and reads until Twitch has finished the handshake.
+///
+/// # NOTE
+/// This reads one byte at a time so nothing past the end of the handshake is
+/// consumed from `io`, you can wrap it in a [Decoder](crate::Decoder) afterwards.
+///
+/// # Errors
+/// * [Error::LoginFailed] or [Error::InvalidToken] if Twitch rejected the login
+/// * [Error::InvalidCap] if Twitch refused a capability it didn't know about
+/// * [Error::UnexpectedEof] if the connection closed during the handshake
+pub fn register_and_capture<IO>(
+    user_config: &UserConfig,
+    io: &mut IO,
+) -> Result<HandshakeResult, Error>
+where
+    IO: Read + Write + ?Sized,
+{
+    commands::register(user_config).encode(&mut *io)?;
+
+    let mut looking_for: HashSet<_> = user_config.capabilities.iter().copied().collect();
+    let mut capabilities = Capabilities::default();
+    let mut name = None;
+    let mut global_user_state = None;
+    let mut messages = Vec::new();
+
+    // see the note in the AsyncRunner about when Twitch sends this
+    let mut expect_global_user_state = !user_config.is_anonymous()
+        && looking_for.contains(&Capability::Tags)
+        && looking_for.contains(&Capability::Commands);
+
+    loop {
+        let msg = {
+            // a single byte buffer so we don't read past the handshake
+            let mut decoder = Decoder::with_capacity(&mut *io, 1);
+            Commands::from_irc(decoder.read_message()?)?.into_owned()
+        };
+
+        match &msg {
+            Commands::Ready(msg) => {
+                name.replace(msg.username().to_string());
+            }
+
+            Commands::Cap(msg) if msg.is_acknowledged() => {
+                for cap in msg.capabilities() {
+                    let cap = match Capability::maybe_from_str(cap) {
+                        Some(cap) => cap,
+                        None => {
+                            capabilities.unknown.insert(cap.to_string());
+                            continue;
+                        }
+                    };
+
+                    *match cap {
+                        Capability::Tags => &mut capabilities.tags,
+                        Capability::Membership => &mut capabilities.membership,
+                        Capability::Commands => &mut capabilities.commands,
+                    } = true;
+                    looking_for.remove(&cap);
+                }
+            }
+
+            Commands::Cap(msg) => {
+                for cap in msg.capabilities() {
+                    let cap = Capability::maybe_from_str(cap).ok_or_else(|| Error::InvalidCap {
+                        cap: cap.to_string(),
+                    })?;
+
+                    if matches!(cap, Capability::Tags | Capability::Commands) {
+                        expect_global_user_state = false;
+                    }
+                    capabilities.refused.push(cap);
+                    looking_for.remove(&cap);
+                }
+            }
+
+            Commands::GlobalUserState(msg) => {
+                global_user_state.replace(msg.clone());
+            }
+
+            Commands::Notice(msg) => {
+                if let Some(err) = Error::from_login_notice(msg) {
+                    return Err(err);
+                }
+            }
+
+            Commands::Ping(msg) => commands::pong(msg.token()).encode(&mut *io)?,
+
+            _ => {}
+        }
+
+        messages.push(msg);
+
+        if name.is_some()
+            && looking_for.is_empty()
+            && (!expect_global_user_state || global_user_state.is_some())
+        {
+            break;
+        }
+    }
+
+    Ok(HandshakeResult {
+        name: name.unwrap(),
+        global_user_state,
+        capabilities,
+        messages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    // a scripted connection: reads come from the input, writes are captured
+    struct TestStream {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl TestStream {
+        fn new(input: &str) -> Self {
+            Self {
+                input: Cursor::new(input.as_bytes().to_vec()),
+                output: Vec::new(),
+            }
+        }
+
+        fn remaining(&self) -> &[u8] {
+            &self.input.get_ref()[self.input.position() as usize..]
+        }
+    }
+
+    impl Read for TestStream {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for TestStream {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn user_config(caps: &[Capability]) -> UserConfig {
+        UserConfig::builder()
+            .name("museun")
+            .token(format!("oauth:{}", "a".repeat(30)))
+            .capabilities(caps)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn register_and_capture_full() {
+        let mut io = TestStream::new(
+            ":tmi.twitch.tv CAP * ACK :twitch.tv/tags twitch.tv/commands\r\n\
+             :tmi.twitch.tv 001 museun :Welcome, GLHF!\r\n\
+             PING :1234567890\r\n\
+             :tmi.twitch.tv 376 museun :>\r\n\
+             @badge-info=;badges=;color=#FF69B4;display-name=Museun;emote-sets=0;user-id=23196011;user-type= :tmi.twitch.tv GLOBALUSERSTATE\r\n\
+             :museun!museun@museun.tmi.twitch.tv JOIN #museun\r\n",
+        );
+
+        let config = user_config(&[Capability::Tags, Capability::Commands]);
+        let result = register_and_capture(&config, &mut io).unwrap();
+
+        assert_eq!(result.name, "museun");
+        assert!(result.capabilities.tags);
+        assert!(result.capabilities.commands);
+        assert!(!result.capabilities.membership);
+        assert!(result.capabilities.refused().is_empty());
+
+        let state = result.global_user_state.unwrap();
+        assert_eq!(state.user_id(), Some("23196011"));
+        assert_eq!(state.display_name(), Some("Museun"));
+        assert_eq!(result.messages.len(), 5);
+
+        let mut expected = vec![];
+        commands::register(&config).encode(&mut expected).unwrap();
+        commands::pong("1234567890").encode(&mut expected).unwrap();
+        assert_eq!(io.output, expected);
+
+        // nothing past the handshake was read
+        assert_eq!(
+            io.remaining(),
+            b":museun!museun@museun.tmi.twitch.tv JOIN #museun\r\n"
+        );
+    }
+
+    #[test]
+    fn register_and_capture_basic() {
+        let mut io = TestStream::new(
+            ":tmi.twitch.tv CAP * ACK :twitch.tv/membership\r\n\
+             :tmi.twitch.tv 001 museun :Welcome, GLHF!\r\n\
+             :tmi.twitch.tv 376 museun :>\r\n",
+        );
+
+        let config = user_config(&[Capability::Membership]);
+        let result = register_and_capture(&config, &mut io).unwrap();
+        assert_eq!(result.name, "museun");
+        assert!(result.capabilities.membership);
+        assert!(result.global_user_state.is_none());
+    }
+
+    #[test]
+    fn register_and_capture_refused() {
+        let mut io = TestStream::new(
+            ":tmi.twitch.tv CAP * NAK :twitch.tv/tags\r\n\
+             :tmi.twitch.tv CAP * ACK :twitch.tv/commands\r\n\
+             :tmi.twitch.tv 001 museun :Welcome, GLHF!\r\n\
+             :tmi.twitch.tv 376 museun :>\r\n",
+        );
+
+        let config = user_config(&[Capability::Tags, Capability::Commands]);
+        let result = register_and_capture(&config, &mut io).unwrap();
+        assert_eq!(result.capabilities.refused(), &[Capability::Tags]);
+        assert!(result.capabilities.commands);
+        assert!(result.global_user_state.is_none());
+    }
+
+    #[test]
+    fn register_and_capture_login_failed() {
+        let mut io = TestStream::new(":tmi.twitch.tv NOTICE * :Login authentication failed\r\n");
+        let config = user_config(&[]);
+        assert!(matches!(
+            register_and_capture(&config, &mut io).unwrap_err(),
+            Error::LoginFailed
+        ));
+
+        let mut io = TestStream::new(":tmi.twitch.tv NOTICE * :Improperly formatted auth\r\n");
+        assert!(matches!(
+            register_and_capture(&config, &mut io).unwrap_err(),
+            Error::InvalidToken
+        ));
+
+        // it fails as soon as the notice is read, rather than waiting for the GLOBALUSERSTATE
+        let mut io = TestStream::new(
+            ":tmi.twitch.tv CAP * ACK :twitch.tv/tags twitch.tv/commands\r\n\
+             :tmi.twitch.tv NOTICE * :Login authentication failed\r\n\
+             PING :1234567890\r\n",
+        );
+        let config = user_config(&[Capability::Tags, Capability::Commands]);
+        assert!(matches!(
+            register_and_capture(&config, &mut io).unwrap_err(),
+            Error::LoginFailed
+        ));
+        assert_eq!(io.remaining(), b"PING :1234567890\r\n");
+
+        // a notice for a channel isn't about the login
+        let mut io = TestStream::new(
+            ":tmi.twitch.tv NOTICE #museun :Login authentication failed\r\n\
+             :tmi.twitch.tv 001 museun :Welcome, GLHF!\r\n\
+             :tmi.twitch.tv 376 museun :>\r\n",
+        );
+        let config = user_config(&[]);
+        assert_eq!(register_and_capture(&config, &mut io).unwrap().name, "museun");
+
+        let mut io = TestStream::new("");
+        assert!(matches!(
+            register_and_capture(&config, &mut io).unwrap_err(),
+            Error::UnexpectedEof
+        ));
+    }
+}
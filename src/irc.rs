@@ -33,6 +33,14 @@ pub trait FromIrcMessage<'a>: Sized {
     /// This method consumes an `IrcMessage` and tries to produce an instance of `Self`
     fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error>;
 
+    /// This method tries to produce an instance of `Self` that borrows from the `IrcMessage`
+    ///
+    /// Unlike cloning the message, this doesn't copy the raw string. The
+    /// message's tags will still be parsed into an owned form.
+    fn from_irc_ref(msg: &'a IrcMessage<'_>) -> Result<Self, Self::Error> {
+        Self::from_irc(msg.as_borrowed())
+    }
+
     /// Consumes self returning the raw `MaybeOwned<'a>`
     fn into_inner(self) -> MaybeOwned<'a>;
 }
@@ -58,8 +66,11 @@ where
 mod message;
 pub use message::IrcMessage;
 
+mod command;
+pub use command::IrcCommand;
+
 mod prefix;
-pub use prefix::{Prefix, PrefixIndex};
+pub use prefix::{Prefix, PrefixIndex, PrefixKind};
 
 pub(crate) mod tags;
 pub use tags::{Tags, TagsIter};
@@ -86,16 +97,149 @@ pub fn parse(input: &str) -> IrcParserIter<'_> {
 pub fn parse_one(input: &str) -> Result<(usize, IrcMessage<'_>), MessageError> {
     const CRLF: &str = "\r\n";
 
-    let pos = input
-        .find(CRLF)
-        .ok_or(MessageError::IncompleteMessage { pos: 0 })?
-        + CRLF.len();
+    let pos = find_crlf(input).ok_or(MessageError::IncompleteMessage { pos: 0 })? + CRLF.len();
+
+    let next = &input[..pos];
+    let done = next.len() == input.len();
+
+    let msg = IrcMessage::parse(MaybeOwned::Borrowed(next))?;
+    Ok((if done { 0 } else { pos }, msg))
+}
+
+/// Attempts to parse one message, accepting either `\r\n` or a bare `\n` as the line terminator.
+///
+/// This is useful for parsing logs that were saved without the `\r\n` line endings.
+///
+/// Like [parse_one], this returns the index of the /next/ message (e.g, 0 for a single message) and the parsed message
+///
+/// ```
+/// let input = ":tmi.twitch.tv PING :1234\n:tmi.twitch.tv PING :5678\r\n";
+///
+/// let (next, msg) = twitchchat::irc::parse_one_lenient(input).unwrap();
+/// assert_eq!(msg.get_data().unwrap(), "1234");
+///
+/// let (next, msg) = twitchchat::irc::parse_one_lenient(&input[next..]).unwrap();
+/// assert_eq!(msg.get_data().unwrap(), "5678");
+/// assert_eq!(next, 0);
+/// ```
+pub fn parse_one_lenient(input: &str) -> Result<(usize, IrcMessage<'_>), MessageError> {
+    let pos = find_lf(input).ok_or(MessageError::IncompleteMessage { pos: 0 })? + 1;
 
     let next = &input[..pos];
     let done = next.len() == input.len();
 
-    let msg = IrcMessage::parse(crate::MaybeOwned::Borrowed(next))?;
+    let msg = IrcMessage::parse(MaybeOwned::Borrowed(next))?;
     Ok((if done { 0 } else { pos }, msg))
 }
 
-// TODO add a test for parse_one. it was wrong
+// finds the start of the first `\r\n`
+#[cfg(not(feature = "memchr"))]
+fn find_crlf(input: &str) -> Option<usize> {
+    input.find("\r\n")
+}
+
+// finds the start of the first `\r\n`
+#[cfg(feature = "memchr")]
+fn find_crlf(input: &str) -> Option<usize> {
+    let bytes = input.as_bytes();
+    memchr::memchr_iter(b'\r', bytes).find(|&pos| bytes.get(pos + 1) == Some(&b'\n'))
+}
+
+#[cfg(not(feature = "memchr"))]
+fn find_lf(input: &str) -> Option<usize> {
+    input.find('\n')
+}
+
+#[cfg(feature = "memchr")]
+fn find_lf(input: &str) -> Option<usize> {
+    memchr::memchr(b'\n', input.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "memchr")]
+    fn memchr_parity() {
+        let inputs = &[
+            "",
+            "\r",
+            "\n",
+            "\r\n",
+            "\n\r",
+            "\r\r\n",
+            "abc",
+            "abc\r",
+            "abc\rdef\r\nghi",
+            "abc\ndef\r\n",
+            "PING :1234\r\nPING :5678\r\n",
+            "@display-name=月饼 :a!a@a PRIVMSG #a :\r月\r\n",
+        ];
+
+        for input in inputs {
+            assert_eq!(find_crlf(input), input.find("\r\n"), "{:?}", input);
+            assert_eq!(find_lf(input), input.find('\n'), "{:?}", input);
+        }
+
+        let input = "PING :1234\r\nbad\r\n:tmi.twitch.tv PING :5678\r\nPING :incomplete";
+        let got = parse(input)
+            .map(|msg| msg.map(|msg| msg.get_raw().to_string()).map_err(drop))
+            .collect::<Vec<_>>();
+        let expected = vec![
+            Ok("PING :1234\r\n".to_string()),
+            Ok("bad\r\n".to_string()),
+            Ok(":tmi.twitch.tv PING :5678\r\n".to_string()),
+            Err(()),
+        ];
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn parse_one_strict() {
+        let input = "PING :1234\r\nPING :5678\r\n";
+        let (next, msg) = parse_one(input).unwrap();
+        assert_eq!(next, 12);
+        assert_eq!(msg.get_data().unwrap(), "1234");
+
+        let (next, msg) = parse_one(&input[next..]).unwrap();
+        assert_eq!(next, 0);
+        assert_eq!(msg.get_data().unwrap(), "5678");
+
+        assert!(matches!(
+            parse_one("PING :1234\n"),
+            Err(MessageError::IncompleteMessage { pos: 0 })
+        ));
+    }
+
+    #[test]
+    fn parse_one_lenient_crlf() {
+        let input = "PING :1234\r\nPING :5678\r\n";
+        let (next, msg) = parse_one_lenient(input).unwrap();
+        assert_eq!(next, 12);
+        assert_eq!(msg.get_data().unwrap(), "1234");
+
+        let (next, msg) = parse_one_lenient(&input[next..]).unwrap();
+        assert_eq!(next, 0);
+        assert_eq!(msg.get_data().unwrap(), "5678");
+    }
+
+    #[test]
+    fn parse_one_lenient_lf() {
+        let input = ":museun!museun@museun PRIVMSG #museun :hello\n:tmi.twitch.tv PING :1234\n";
+        let (next, msg) = parse_one_lenient(input).unwrap();
+        assert_eq!(next, 45);
+        assert_eq!(msg.get_command(), "PRIVMSG");
+        assert_eq!(msg.get_data().unwrap(), "hello");
+
+        let (next, msg) = parse_one_lenient(&input[next..]).unwrap();
+        assert_eq!(next, 0);
+        assert_eq!(msg.get_command(), "PING");
+        assert_eq!(msg.get_data().unwrap(), "1234");
+
+        assert!(matches!(
+            parse_one_lenient("PING :1234"),
+            Err(MessageError::IncompleteMessage { pos: 0 })
+        ));
+    }
+}
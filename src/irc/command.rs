@@ -0,0 +1,154 @@
+use super::IrcMessage;
+
+/// The command of an [IrcMessage], see [IrcMessage::command_kind]
+///
+/// Numeric replies (e.g. `001` or `366`) are [IrcCommand::Numeric], anything
+/// this doesn't know about is [IrcCommand::Other].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum IrcCommand<'a> {
+    /// A capability response -- `CAP`
+    Cap,
+    /// A user was 'purged' from a channel -- `CLEARCHAT`
+    ClearChat,
+    /// A user's message was removed -- `CLEARMSG`
+    ClearMsg,
+    /// Your user state -- `GLOBALUSERSTATE`
+    GlobalUserState,
+    /// A channel host event -- `HOSTTARGET`
+    HostTarget,
+    /// A user joined a channel -- `JOIN`
+    Join,
+    /// A message from Twitch -- `NOTICE`
+    Notice,
+    /// A user left a channel -- `PART`
+    Part,
+    /// A ping you must reply to -- `PING`
+    Ping,
+    /// A response to your ping -- `PONG`
+    Pong,
+    /// A user sent a message to a channel -- `PRIVMSG`
+    Privmsg,
+    /// Twitch wants you to reconnect -- `RECONNECT`
+    Reconnect,
+    /// The state of a room -- `ROOMSTATE`
+    RoomState,
+    /// A user event, like a subscription -- `USERNOTICE`
+    UserNotice,
+    /// Your state in a channel -- `USERSTATE`
+    UserState,
+    /// A message from a user directly to you -- `WHISPER`
+    Whisper,
+    /// A three digit numeric reply, e.g. `001`
+    Numeric(u16),
+    /// Any other command
+    Other(&'a str),
+}
+
+impl<'a> IrcCommand<'a> {
+    /// Get the command for this string
+    ///
+    /// ```rust
+    /// use twitchchat::irc::IrcCommand;
+    /// assert_eq!(IrcCommand::from_command("PRIVMSG"), IrcCommand::Privmsg);
+    /// assert_eq!(IrcCommand::from_command("366"), IrcCommand::Numeric(366));
+    /// assert_eq!(IrcCommand::from_command("FOO"), IrcCommand::Other("FOO"));
+    /// ```
+    pub fn from_command(command: &'a str) -> Self {
+        use IrcMessage as M;
+        match command {
+            M::CAP => Self::Cap,
+            M::CLEAR_CHAT => Self::ClearChat,
+            M::CLEAR_MSG => Self::ClearMsg,
+            M::GLOBAL_USER_STATE => Self::GlobalUserState,
+            M::HOST_TARGET => Self::HostTarget,
+            M::JOIN => Self::Join,
+            M::NOTICE => Self::Notice,
+            M::PART => Self::Part,
+            M::PING => Self::Ping,
+            M::PONG => Self::Pong,
+            M::PRIVMSG => Self::Privmsg,
+            M::RECONNECT => Self::Reconnect,
+            M::ROOM_STATE => Self::RoomState,
+            M::USER_NOTICE => Self::UserNotice,
+            M::USER_STATE => Self::UserState,
+            M::WHISPER => Self::Whisper,
+            s if s.len() == 3 && s.bytes().all(|c| c.is_ascii_digit()) => {
+                Self::Numeric(s.parse().expect("three digits should fit in a u16"))
+            }
+            s => Self::Other(s),
+        }
+    }
+
+    /// Get the command as it'd appear in a message
+    ///
+    /// Numeric replies aren't borrowed, so this returns `None` for them.
+    pub fn as_str(&self) -> Option<&'a str> {
+        use IrcMessage as M;
+        let s = match self {
+            Self::Cap => M::CAP,
+            Self::ClearChat => M::CLEAR_CHAT,
+            Self::ClearMsg => M::CLEAR_MSG,
+            Self::GlobalUserState => M::GLOBAL_USER_STATE,
+            Self::HostTarget => M::HOST_TARGET,
+            Self::Join => M::JOIN,
+            Self::Notice => M::NOTICE,
+            Self::Part => M::PART,
+            Self::Ping => M::PING,
+            Self::Pong => M::PONG,
+            Self::Privmsg => M::PRIVMSG,
+            Self::Reconnect => M::RECONNECT,
+            Self::RoomState => M::ROOM_STATE,
+            Self::UserNotice => M::USER_NOTICE,
+            Self::UserState => M::USER_STATE,
+            Self::Whisper => M::WHISPER,
+            Self::Numeric(..) => return None,
+            Self::Other(s) => s,
+        };
+        Some(s)
+    }
+}
+
+impl core::fmt::Display for IrcCommand<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Numeric(n) => write!(f, "{:03}", n),
+            cmd => f.write_str(cmd.as_str().expect("only numerics aren't strings")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_kind() {
+        let tests = &[
+            (
+                ":museun!museun@museun PRIVMSG #museun :hello\r\n",
+                IrcCommand::Privmsg,
+            ),
+            (
+                ":museun.tmi.twitch.tv 366 museun #museun :End of /NAMES list\r\n",
+                IrcCommand::Numeric(366),
+            ),
+            (
+                ":tmi.twitch.tv 001 museun :Welcome, GLHF!\r\n",
+                IrcCommand::Numeric(1),
+            ),
+            (
+                ":tmi.twitch.tv FOOBAR #museun\r\n",
+                IrcCommand::Other("FOOBAR"),
+            ),
+            // numerics are exactly three digits
+            (":tmi.twitch.tv 1234 museun\r\n", IrcCommand::Other("1234")),
+        ];
+
+        for (input, expected) in tests {
+            let msg = crate::irc::parse_one(input).unwrap().1;
+            assert_eq!(msg.command_kind(), *expected, "{}", input.escape_debug());
+            assert_eq!(msg.command_kind().to_string(), msg.get_command());
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use alloc::{boxed::Box, string::String};
+
 /// An invalid message was either provided, or could not be parsed
 #[derive(Debug)]
 #[non_exhaustive]
@@ -33,7 +35,7 @@ pub enum MessageError {
         /// The tag name
         name: String,
         /// The parse error
-        error: Box<dyn std::error::Error + Send + Sync>,
+        error: Box<dyn core::error::Error + Send + Sync>,
     },
 
     /// An empty key in the tags was provided
@@ -59,15 +61,27 @@ pub enum MessageError {
     /// An empty message was provided
     EmptyMessage,
 
+    /// The message wasn't valid UTF-8
+    InvalidUtf8 {
+        /// The input was valid up to this index
+        pos: usize,
+    },
+
+    /// The message was too long to be parsed
+    MessageTooLong {
+        /// The length of the message
+        len: usize,
+    },
+
     /// A custom error message
     Custom {
         /// The inner error
-        error: Box<dyn std::error::Error + Send + Sync>,
+        error: Box<dyn core::error::Error + Send + Sync>,
     },
 }
 
-impl std::fmt::Display for MessageError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for MessageError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::InvalidCommand { expected, got } => {
                 write!(f, "invalid command. expected '{}' got '{}'", expected, got)
@@ -81,13 +95,15 @@ impl std::fmt::Display for MessageError {
             Self::MissingTagValue(index) => write!(f, "missing tag value at pair index: {}", index),
             Self::IncompleteMessage { pos } => write!(f, "incomplete message starting at: {}", pos),
             Self::EmptyMessage => write!(f, "no message could be parsed"),
+            Self::InvalidUtf8 { pos } => write!(f, "invalid utf-8 starting at: {}", pos),
+            Self::MessageTooLong { len } => write!(f, "message was too long: {} bytes", len),
             Self::Custom { error } => write!(f, "custom error: {}", error),
         }
     }
 }
 
-impl std::error::Error for MessageError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl core::error::Error for MessageError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self {
             Self::CannotParseTag { error, .. } => Some(&**error),
             Self::Custom { error } => Some(&**error),
@@ -1,6 +1,6 @@
-use super::{parser::Parser, MessageError, Prefix, PrefixIndex};
+use super::{parser::Parser, IrcCommand, MessageError, Prefix, PrefixIndex};
 use crate::{FromIrcMessage, MaybeOwned, MaybeOwnedIndex};
-use std::convert::Infallible;
+use core::convert::Infallible;
 
 /// A raw irc message `@tags :prefix COMMAND args :data\r\n`
 #[derive(Clone, PartialEq)]
@@ -28,15 +28,19 @@ impl<'a> IrcMessage<'a> {
             input.as_ref()
         };
 
-        let data = data.trim();
-        if data.is_empty() {
-            return Err(super::MessageError::EmptyMessage);
+        // the indices are stored as u16, so anything longer than that can't be represented
+        if data.len() > MaybeOwnedIndex::MAX_LEN {
+            return Err(MessageError::MessageTooLong { len: data.len() });
         }
 
-        let mut p = Parser {
-            input: data,
-            pos: 0,
-        };
+        // only the end is trimmed, leading whitespace is skipped so the indices still line up with `raw`
+        let data = data.trim_end();
+        let pos = data.len() - data.trim_start().len();
+        if pos == data.len() {
+            return Err(MessageError::EmptyMessage);
+        }
+
+        let mut p = Parser { input: data, pos };
 
         let this = Self {
             tags: p.tags(),
@@ -49,9 +53,61 @@ impl<'a> IrcMessage<'a> {
         Ok(this)
     }
 
+    /// Parse a single message from arbitrary bytes
+    ///
+    /// Unlike the other parsing functions, this accepts any byte sequence and will never panic.
+    /// Invalid UTF-8, an empty message or a message too long to index will return an error instead.
+    ///
+    /// A trailing `\r\n` is optional.
+    ///
+    /// ```rust
+    /// use twitchchat::IrcMessage;
+    ///
+    /// let msg = IrcMessage::parse_lossy(b":museun!museun@museun PRIVMSG #museun :hello\r\n").unwrap();
+    /// assert_eq!(msg.get_command(), "PRIVMSG");
+    /// assert_eq!(msg.get_data().unwrap(), "hello");
+    ///
+    /// assert!(IrcMessage::parse_lossy(b"PRIVMSG #museun :\xF0\x28\x8C\x28").is_err());
+    /// assert!(IrcMessage::parse_lossy(b"").is_err());
+    /// ```
+    pub fn parse_lossy(input: &'a [u8]) -> Result<Self, MessageError> {
+        let input = core::str::from_utf8(input).map_err(|err| MessageError::InvalidUtf8 {
+            pos: err.valid_up_to(),
+        })?;
+        Self::parse(MaybeOwned::Borrowed(input))
+    }
+
+    /// Get a borrowed view of this message
+    ///
+    /// This doesn't allocate, the indices are reused and the raw string is borrowed from `self`.
+    pub fn as_borrowed(&self) -> IrcMessage<'_> {
+        IrcMessage {
+            raw: MaybeOwned::Borrowed(self.raw.as_ref()),
+            tags: self.tags,
+            prefix: self.prefix,
+            command: self.command,
+            args: self.args,
+            data: self.data,
+        }
+    }
+
+    // reuse the indices with an identical copy of the raw string
+    #[cfg(feature = "async")]
+    pub(crate) fn with_raw<'b>(&self, raw: &'b str) -> IrcMessage<'b> {
+        debug_assert_eq!(raw, &*self.raw);
+        IrcMessage {
+            raw: MaybeOwned::Borrowed(raw),
+            tags: self.tags,
+            prefix: self.prefix,
+            command: self.command,
+            args: self.args,
+            data: self.data,
+        }
+    }
+
     /// Get the raw string
     pub fn get_raw(&self) -> &str {
-        &*self.raw
+        &self.raw
     }
 
     /// Get the raw tags
@@ -64,11 +120,33 @@ impl<'a> IrcMessage<'a> {
         self.prefix.map(|index| &self.raw[index.as_index()])
     }
 
+    /// Get the prefix, if there was one
+    ///
+    /// Use [Prefix::kind] to split it into its parts.
+    pub fn prefix(&self) -> Option<Prefix<'_>> {
+        self.prefix.map(|index| Prefix {
+            data: &self.raw,
+            index,
+        })
+    }
+
     /// Get the raw command
     pub fn get_command(&self) -> &str {
         &self.raw[self.command]
     }
 
+    /// Get the command as an [IrcCommand], so you can match on it
+    ///
+    /// ```rust
+    /// use twitchchat::{irc::IrcCommand, IrcMessage};
+    ///
+    /// let msg = IrcMessage::parse_lossy(b":museun!museun@museun PRIVMSG #museun :hello\r\n").unwrap();
+    /// assert_eq!(msg.command_kind(), IrcCommand::Privmsg);
+    /// ```
+    pub fn command_kind(&self) -> IrcCommand<'_> {
+        IrcCommand::from_command(self.get_command())
+    }
+
     /// Get the raw args
     pub fn get_args(&self) -> Option<&str> {
         self.args.map(|index| &self.raw[index])
@@ -111,7 +189,7 @@ impl<'a> IrcMessage<'a> {
                 seen += 1;
             }
 
-            tail += 1;
+            tail += ch.len_utf8() as u16;
         }
 
         if seen == nth {
@@ -127,6 +205,16 @@ impl<'a> IrcMessage<'a> {
     ///
     /// This is sent when you've connected.
     pub const IRC_READY: &'static str = "001";
+    /// The server's host information -- `002`.
+    pub const YOUR_HOST: &'static str = "002";
+    /// When the server was created -- `003`.
+    pub const CREATED: &'static str = "003";
+    /// The server's information -- `004`.
+    pub const MY_INFO: &'static str = "004";
+    /// The start of the message of the day -- `375`.
+    pub const MOTD_START: &'static str = "375";
+    /// A line of the message of the day -- `372`.
+    pub const MOTD: &'static str = "372";
     /// A Twitch Ready event -- `376`.
     ///
     /// This is sent by Twitch with your user information.
@@ -179,7 +267,10 @@ impl<'a> FromIrcMessage<'a> for IrcMessage<'a> {
         Ok(msg)
     }
 
-    into_inner_raw!();
+    /// Consumes the message, returning the raw [`MaybeOwned<'_>`](./enum.Str.html)
+    fn into_inner(self) -> MaybeOwned<'a> {
+        self.raw
+    }
 }
 
 into_owned! {
@@ -193,18 +284,12 @@ into_owned! {
     }
 }
 
-impl<'a> std::fmt::Debug for IrcMessage<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for IrcMessage<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("IrcMessage")
             .field("raw", &&*self.raw)
             .field("tags", &self.get_tags())
-            .field(
-                "prefix",
-                &self.prefix.map(|index| Prefix {
-                    data: &self.raw,
-                    index,
-                }),
-            )
+            .field("prefix", &self.prefix())
             .field("command", &self.get_command())
             .field("args", &self.get_args())
             .field("data", &self.get_data())
@@ -212,6 +297,13 @@ impl<'a> std::fmt::Debug for IrcMessage<'a> {
     }
 }
 
+/// This renders the message back to its wire form, e.g. `@tags :prefix COMMAND args :data\r\n`
+impl<'a> core::fmt::Display for IrcMessage<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<'a> ::serde::Serialize for IrcMessage<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -253,6 +345,19 @@ mod tests {
         crate::serde::round_trip_rmp::<IrcMessage>(input);
     }
 
+    #[test]
+    fn display() {
+        let inputs = &[
+            ":test!test@test PRIVMSG #museun :this is a test\r\n",
+            "@badges=broadcaster/1;color=#FF69B4 :test!test@test PRIVMSG #museun :hello\r\n",
+            "PING :1234567890\r\n",
+        ];
+        for input in inputs {
+            let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+            assert_eq!(format!("{}", msg), *input);
+        }
+    }
+
     #[test]
     fn parse_empty_spaces() {
         for i in 0..10 {
@@ -261,4 +366,94 @@ mod tests {
             assert!(matches!(err, MessageError::EmptyMessage))
         }
     }
+
+    #[test]
+    fn parse_lossy() {
+        let msg = IrcMessage::parse_lossy(b"  PING :1234\r\n").unwrap();
+        assert_eq!(msg.get_command(), "PING");
+        assert_eq!(msg.get_data().unwrap(), "1234");
+
+        let msg = IrcMessage::parse_lossy("FOO \u{e9}\u{e9} bar".as_bytes()).unwrap();
+        assert_eq!(msg.nth_arg(1).unwrap(), "bar");
+        assert_eq!(&msg.raw[msg.nth_arg_index(1).unwrap()], "bar");
+
+        assert!(matches!(
+            IrcMessage::parse_lossy(b"PING :\xFF"),
+            Err(MessageError::InvalidUtf8 { pos: 6 })
+        ));
+        assert!(matches!(
+            IrcMessage::parse_lossy(b" \r\n"),
+            Err(MessageError::EmptyMessage)
+        ));
+
+        let long = vec![b'a'; MaybeOwnedIndex::MAX_LEN + 1];
+        assert!(matches!(
+            IrcMessage::parse_lossy(&long),
+            Err(MessageError::MessageTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_lossy_random_input() {
+        // a small xorshift so the failing input is reproducible
+        let mut state = 0x2545_F491_4F6C_DD1D_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        // mostly bytes that are meaningful to the parser, with some multi-byte characters
+        const ALPHABET: &[&[u8]] = &[
+            b"@",
+            b":",
+            b"!",
+            b" ",
+            b";",
+            b"=",
+            b"\r",
+            b"\n",
+            b"\t",
+            b"a",
+            b"#",
+            b"/",
+            b"1",
+            "\u{e9}".as_bytes(),
+            "\u{3000}".as_bytes(),
+            "\u{1F600}".as_bytes(),
+        ];
+
+        let mut input = Vec::new();
+        for _ in 0..50_000 {
+            input.clear();
+            for _ in 0..next() % 48 {
+                match next() % 8 {
+                    0 => input.push(next() as u8),
+                    _ => input.extend_from_slice(ALPHABET[next() as usize % ALPHABET.len()]),
+                }
+            }
+
+            let msg = match IrcMessage::parse_lossy(&input) {
+                Ok(msg) => msg,
+                Err(..) => continue,
+            };
+
+            // every index should be usable
+            let _ = format!("{:?}", msg);
+            if let Some(prefix) = msg.prefix {
+                let _ = &msg.raw[prefix.as_index()];
+            }
+            for nth in 0..4 {
+                let _ = msg.nth_arg(nth);
+                if let Some(index) = msg.nth_arg_index(nth) {
+                    let _ = &msg.raw[index];
+                }
+            }
+            if let Some(tags) = msg.get_tags() {
+                let _ = crate::irc::TagIndices::build_indices(tags);
+            }
+            let _ = crate::messages::Commands::from_irc(msg);
+        }
+    }
 }
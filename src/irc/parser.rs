@@ -28,7 +28,8 @@ impl<'a> Parser<'a> {
         if input.starts_with(':') {
             if let Some(pos) = input.find(' ') {
                 self.pos += 1;
-                let prefix = match input.find('!') {
+                // only look for the '!' in the prefix, the rest of the line can contain one
+                let prefix = match input[..pos].find('!') {
                     Some(bang) => PrefixIndex::User {
                         nick: self.mark_index(bang - 1, pos),
                     },
@@ -45,7 +46,7 @@ impl<'a> Parser<'a> {
 
     pub(super) fn command(&mut self) -> MaybeOwnedIndex {
         let input = &self.input[self.pos..];
-        let pos = input.find(' ').unwrap_or_else(|| input.len());
+        let pos = input.find(' ').unwrap_or(input.len());
         self.mark_index(pos, pos + 1)
     }
 
@@ -55,7 +56,7 @@ impl<'a> Parser<'a> {
         }
 
         let input = self.input.get(self.pos..)?;
-        let pos = input.find(" :").unwrap_or_else(|| input.len());
+        let pos = input.find(" :").unwrap_or(input.len());
         Some(self.mark_index(pos, pos))
     }
 
@@ -89,7 +90,7 @@ impl<'a> Iterator for IrcParserIter<'a> {
             return None;
         }
 
-        let index = match self.data.get(self.pos..)?.find(CRLF) {
+        let index = match super::find_crlf(self.data.get(self.pos..)?) {
             Some(index) => index + CRLF.len() + self.pos,
             None => {
                 let err = Err(MessageError::IncompleteMessage { pos: self.pos });
@@ -98,7 +99,7 @@ impl<'a> Iterator for IrcParserIter<'a> {
             }
         };
 
-        let pos = std::mem::replace(&mut self.pos, index);
+        let pos = core::mem::replace(&mut self.pos, index);
         self.data
             .get(pos..index)
             .map(MaybeOwned::from)
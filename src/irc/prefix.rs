@@ -6,8 +6,8 @@ pub struct Prefix<'a> {
     pub(crate) index: PrefixIndex,
 }
 
-impl<'a> std::fmt::Debug for Prefix<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for Prefix<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.data[self.index.as_index()].fmt(f)
     }
 }
@@ -32,6 +32,49 @@ impl<'a> Prefix<'a> {
     pub fn get_nick(&self) -> Option<&'a str> {
         self.index.nick_index().map(|index| &self.data[index])
     }
+
+    /// Split this prefix into its parts
+    ///
+    /// A user prefix is in the form of `nick!user@host`, but the `user` and
+    /// `host` parts are optional.
+    pub fn kind(&self) -> PrefixKind<'a> {
+        match self.index {
+            PrefixIndex::Server { host } => PrefixKind::Server(&self.data[host]),
+            PrefixIndex::User { nick } => {
+                // the index only covers the nick, so find the rest of the prefix
+                let data: &'a str = self.data;
+                let rest = &data[nick.end as usize..];
+                let rest = &rest[..rest.find(' ').unwrap_or(rest.len())];
+                let (user, host) = match rest.find('@') {
+                    Some(at) => (&rest[..at], Some(&rest[at + 1..])),
+                    None => (rest, None),
+                };
+                let non_empty = |s: &'a str| Some(s).filter(|s| !s.is_empty());
+
+                PrefixKind::User {
+                    nick: &self.data[nick],
+                    user: user.strip_prefix('!').and_then(non_empty),
+                    host: host.and_then(non_empty),
+                }
+            }
+        }
+    }
+}
+
+/// The parts of a [Prefix]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PrefixKind<'a> {
+    /// A user prefix, e.g. `nick!user@host`
+    User {
+        /// The nickname
+        nick: &'a str,
+        /// The username, if present
+        user: Option<&'a str>,
+        /// The hostname, if present
+        host: Option<&'a str>,
+    },
+    /// A server prefix, e.g. `tmi.twitch.tv`
+    Server(&'a str),
 }
 
 /// Prefix is the sender of a message
@@ -91,3 +134,64 @@ impl IntoOwned<'static> for PrefixIndex {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::IrcMessage;
+
+    use super::*;
+
+    #[test]
+    fn prefix_kind() {
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(
+            ":tmi.twitch.tv 001 museun :Welcome!\r\n",
+        ))
+        .unwrap();
+        let prefix = msg.prefix().unwrap();
+        assert!(prefix.is_server());
+        assert_eq!(prefix.kind(), PrefixKind::Server("tmi.twitch.tv"));
+
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(
+            ":museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n",
+        ))
+        .unwrap();
+        let prefix = msg.prefix().unwrap();
+        assert!(prefix.is_user());
+        assert_eq!(prefix.get_nick(), Some("museun"));
+        assert_eq!(
+            prefix.kind(),
+            PrefixKind::User {
+                nick: "museun",
+                user: Some("museun"),
+                host: Some("museun.tmi.twitch.tv"),
+            }
+        );
+
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(":museun! JOIN #museun\r\n")).unwrap();
+        assert_eq!(
+            msg.prefix().unwrap().kind(),
+            PrefixKind::User {
+                nick: "museun",
+                user: None,
+                host: None,
+            }
+        );
+
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed("PING :1234567890\r\n")).unwrap();
+        assert!(msg.prefix().is_none());
+    }
+
+    #[test]
+    fn prefix_bang_in_data() {
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(
+            ":tmi.twitch.tv NOTICE #museun :hello!world\r\n",
+        ))
+        .unwrap();
+        assert_eq!(msg.get_prefix(), Some("tmi.twitch.tv"));
+        assert_eq!(
+            msg.prefix().unwrap().kind(),
+            PrefixKind::Server("tmi.twitch.tv")
+        );
+        assert_eq!(msg.get_command(), "NOTICE");
+    }
+}
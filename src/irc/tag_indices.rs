@@ -1,20 +1,26 @@
-use std::borrow::Cow;
-
-use crate::{maybe_owned::MaybeOwned, IntoOwned, MessageError};
+use crate::{
+    maybe_owned::{MaybeOwned, MaybeOwnedIndex},
+    IntoOwned, MessageError,
+};
+use alloc::vec::Vec;
 
 /// Pre-computed tag indices
 ///
 /// This type is only exposed for those wanting to extend/make custom types.
+///
+/// These are the spans of the keys and values in the tags fragment, so they
+/// need the same data they were built from to be useful.
 #[derive(Default, Clone, PartialEq)]
 pub struct TagIndices {
-    // NOTE this is a hack to keep the semver stable, in v0.15 this'll go back to being borrowed.
-    pub(super) map: Box<[(Cow<'static, str>, Cow<'static, str>)]>,
+    // a Vec rather than a boxed slice so a decoder can rebuild one in place.
+    // cloning it still only allocates the exact length
+    pub(super) map: Vec<(MaybeOwnedIndex, MaybeOwnedIndex)>,
 }
 
-impl std::fmt::Debug for TagIndices {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for TagIndices {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_map()
-            .entries(self.map.iter().map(|(k, v)| (k, v)))
+            .entries(self.map.iter().map(|(k, v)| (k.as_range(), v.as_range())))
             .finish()
     }
 }
@@ -28,24 +34,80 @@ impl TagIndices {
             return Ok(Self::default());
         }
 
-        input[1..]
-            .split_terminator(';')
-            .enumerate()
-            .map(|(pos, input)| {
-                use MessageError::{MissingTagKey, MissingTagValue};
-                let expect = |s: Option<&str>, err: fn(usize) -> MessageError| {
-                    s.map(ToString::to_string)
-                        .map(Cow::from)
-                        .ok_or_else(|| err(pos))
-                };
-
-                let mut iter = input.split('=');
-                let key = expect(iter.next().filter(|s| !s.is_empty()), MissingTagKey)?;
-                let value = expect(iter.next(), MissingTagValue)?;
-                Ok((key, value))
-            })
-            .collect::<Result<_, _>>()
-            .map(|map| Self { map })
+        // the indices are stored as u16, so anything longer than that can't be represented
+        if input.len() > MaybeOwnedIndex::MAX_LEN {
+            return Err(MessageError::MessageTooLong { len: input.len() });
+        }
+
+        // the only allocation is the map itself, which is sized up front
+        let pairs = input.bytes().filter(|&c| c == b';').count() + 1;
+        let mut map = Vec::with_capacity(pairs);
+        Self::build_into(input, &mut map)?;
+        Ok(Self { map })
+    }
+
+    /// Rebuild these indices from the tags of `msg`, reusing the allocation
+    ///
+    /// This produces the same indices as `Validator::parse_tags`: if the tags
+    /// are missing or malformed, this will be empty.
+    #[cfg(any(not(feature = "no_std"), feature = "async"))]
+    pub(crate) fn rebuild(&mut self, msg: &crate::IrcMessage<'_>) {
+        self.map.clear();
+        let index = match msg.tags {
+            Some(index) => index,
+            None => return,
+        };
+
+        let input = &msg.raw[index];
+        if !input.starts_with('@') || input.len() > MaybeOwnedIndex::MAX_LEN {
+            return;
+        }
+
+        if Self::build_into(input, &mut self.map).is_err() {
+            self.map.clear();
+            return;
+        }
+
+        for (k, v) in self.map.iter_mut() {
+            *k = k.offset_by(index.start as usize);
+            *v = v.offset_by(index.start as usize);
+        }
+    }
+
+    // a single scan over the bytes, recording the spans of the keys and values
+    fn build_into(
+        input: &str,
+        map: &mut Vec<(MaybeOwnedIndex, MaybeOwnedIndex)>,
+    ) -> Result<(), MessageError> {
+        let bytes = input.as_bytes();
+
+        // ';' and '=' are ascii, so these are always on char boundaries
+        let (mut start, mut eq) = (1, None);
+        for pos in 1..=bytes.len() {
+            match bytes.get(pos) {
+                Some(b'=') if eq.is_none() => eq = Some(pos),
+                Some(b';') | None => {
+                    // a trailing ';' doesn't start a new pair
+                    if pos == start && pos == bytes.len() {
+                        break;
+                    }
+
+                    let n = map.len();
+                    if eq.unwrap_or(pos) == start {
+                        return Err(MessageError::MissingTagKey(n));
+                    }
+                    let eq = eq.take().ok_or(MessageError::MissingTagValue(n))?;
+
+                    map.push((
+                        MaybeOwnedIndex::raw(start, eq),
+                        MaybeOwnedIndex::raw(eq + 1, pos),
+                    ));
+                    start = pos + 1;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
     }
 
     /// Get the number of parsed tags
@@ -58,17 +120,29 @@ impl TagIndices {
         self.len() == 0
     }
 
+    // the tags fragment doesn't always start at the beginning of the message
+    pub(crate) fn offset_by(mut self, pos: usize) -> Self {
+        for (k, v) in self.map.iter_mut() {
+            *k = k.offset_by(pos);
+            *v = v.offset_by(pos);
+        }
+        self
+    }
+
     // NOTE: this isn't public because they don't verify 'data' is the same as the built-indices data
-    pub(crate) fn get_unescaped<'a>(&'a self, key: &str) -> Option<MaybeOwned<'a>> {
-        self.get(key).map(crate::test::unescape_str)
+    pub(crate) fn get_unescaped<'a>(&self, data: &'a str, key: &str) -> Option<MaybeOwned<'a>> {
+        self.get(data, key).map(crate::test::unescape_str)
     }
 
     // NOTE: this isn't public because they don't verify 'data' is the same as the built-indices data
-    pub(crate) fn get<'a>(&'a self, key: &str) -> Option<&'a str> {
+    pub(crate) fn get<'a>(&self, data: &'a str, key: &str) -> Option<&'a str> {
         let key = crate::test::escape_str(key);
         self.map
             .iter()
-            .find_map(|(k, v)| if &key == k { Some(&**v) } else { None })
+            .find_map(|(k, v)| match data.get(k.as_range()) {
+                Some(k) if key == k => data.get(v.as_range()),
+                _ => None,
+            })
     }
 }
 
@@ -87,4 +161,38 @@ mod tests {
         let input = "@id=86293428;login=yuebing233;display_name=月饼;foo=bar";
         TagIndices::build_indices(input).unwrap();
     }
+
+    #[test]
+    fn build_indices() {
+        let input = "@a=1;b=;c=3";
+        let indices = TagIndices::build_indices(input).unwrap();
+        assert_eq!(indices.len(), 3);
+        assert_eq!(indices.get(input, "a"), Some("1"));
+        assert_eq!(indices.get(input, "b"), Some(""));
+        assert_eq!(indices.get(input, "c"), Some("3"));
+
+        // trailing separators are fine, and values can contain '='
+        let input = MaybeOwned::Borrowed("@a=1;b=c=d;");
+        let indices = TagIndices::build_indices(&input).unwrap();
+        let tags = crate::irc::Tags::from_data_indices(&input, &indices);
+        assert_eq!(
+            tags.iter().collect::<Vec<_>>(),
+            vec![("a", "1"), ("b", "c=d")]
+        );
+
+        assert!(TagIndices::build_indices("a=1").unwrap().is_empty());
+        assert!(matches!(
+            TagIndices::build_indices("@a=1;=2").unwrap_err(),
+            MessageError::MissingTagKey(1)
+        ));
+        assert!(matches!(
+            TagIndices::build_indices("@a=1;b").unwrap_err(),
+            MessageError::MissingTagValue(1)
+        ));
+        assert!(matches!(
+            TagIndices::build_indices("@a=1;;b=2").unwrap_err(),
+            MessageError::MissingTagKey(1)
+        ));
+        assert!(TagIndices::build_indices("@").unwrap().is_empty());
+    }
 }
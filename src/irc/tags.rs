@@ -1,5 +1,6 @@
 use crate::{irc::TagIndices, MaybeOwned};
-use std::{borrow::Borrow, str::FromStr};
+use alloc::string::String;
+use core::{borrow::Borrow, str::FromStr};
 
 /// Tags are IRCv3 message tags. Twitch uses them extensively.
 ///
@@ -12,8 +13,8 @@ pub struct Tags<'a> {
     pub(crate) indices: &'a TagIndices,
 }
 
-impl<'a> std::fmt::Debug for Tags<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for Tags<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_map().entries(self.iter()).finish()
     }
 }
@@ -26,7 +27,7 @@ impl<'a> Tags<'a> {
 
     /// Gets the raw string that represents the tags
     pub fn raw_tags(&self) -> &'a str {
-        &*self.data
+        self.data
     }
 
     /// Returns how many tags were parsed
@@ -47,7 +48,7 @@ impl<'a> Tags<'a> {
     where
         K: ?Sized + Borrow<str>,
     {
-        self.indices.get_unescaped(key.borrow())
+        self.indices.get_unescaped(self.data, key.borrow())
     }
 
     /// Tries to get this `key`
@@ -58,10 +59,10 @@ impl<'a> Tags<'a> {
     where
         K: ?Sized + Borrow<str>,
     {
-        self.indices.get(key.borrow())
+        self.indices.get(self.data, key.borrow())
     }
 
-    /** Tries to get the tag as a parsable [std::str::FromStr] type.
+    /** Tries to get the tag as a parsable [core::str::FromStr] type.
 
     This returns None if it cannot parse, or cannot find the tag
 
@@ -145,6 +146,10 @@ impl<'a> Tags<'a> {
     }
 
     /// Get an iterator over all of the `key, value` pairs of tags
+    ///
+    /// These are in the order they appeared in the message, and the values
+    /// are not unescaped. So this can be used to reconstruct the original
+    /// `@key=val;key=val` prefix byte-for-byte.
     pub fn iter(&self) -> TagsIter<'_> {
         TagsIter {
             inner: self,
@@ -172,8 +177,8 @@ pub struct TagsIter<'a> {
     pos: usize,
 }
 
-impl<'a> std::fmt::Debug for TagsIter<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for TagsIter<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("TagsIter").finish()
     }
 }
@@ -188,8 +193,9 @@ impl<'a> Iterator for TagsIter<'a> {
         let pos = self.pos;
         self.pos += 1;
 
-        let (k, v) = &self.inner.indices.map.get(pos)?;
-        Some((&**k, &**v))
+        let (k, v) = self.inner.indices.map.get(pos)?;
+        let data: &'a str = self.inner.data;
+        Some((data.get(k.as_range())?, data.get(v.as_range())?))
     }
 }
 
@@ -275,7 +281,7 @@ pub fn unescape_str(s: &str) -> MaybeOwned<'_> {
 /// | --          | the character itself |
 ///
 /// [ref]: https://ircv3.net/specs/extensions/message-tags.html#escaping-values
-pub fn escape_str(s: &str) -> std::borrow::Cow<'_, str> {
+pub fn escape_str(s: &str) -> alloc::borrow::Cow<'_, str> {
     const NEEDS_ESCAPE: [char; 5] = [';', ' ', '\\', '\n', '\r'];
     let n = s.chars().filter(|c| NEEDS_ESCAPE.contains(c)).count();
     if n == 0 {
@@ -300,19 +306,54 @@ pub fn escape_str(s: &str) -> std::borrow::Cow<'_, str> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::str::FromStr;
+    use core::str::FromStr;
+
+    #[test]
+    fn iter_round_trip() {
+        let input = "@zebra=1;apple=two\\sparts;mango=;badges=broadcaster/1 :test!test@test PRIVMSG #museun :hi\r\n";
+        let msg = crate::IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        let indices = TagIndices::build_indices(msg.get_tags().unwrap()).unwrap();
+        let data = MaybeOwned::Borrowed(msg.get_tags().unwrap());
+        let tags = Tags::from_data_indices(&data, &indices);
+
+        let rendered = tags
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let (head, tail) = input.split_at(input.find(' ').unwrap());
+        assert_eq!(format!("@{}", rendered), head);
+        assert_eq!(format!("@{}{}", rendered, tail), input);
+    }
+
+    #[test]
+    fn tags_after_leading_whitespace() {
+        use crate::{messages::Privmsg, FromIrcMessage as _};
+
+        let input =
+            "  @color=#FF69B4;display-name=museun :museun!museun@museun PRIVMSG #museun :hi\r\n";
+        let msg = crate::IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        let msg = Privmsg::from_irc(msg).unwrap();
+
+        assert_eq!(msg.tags().get("display-name"), Some("museun"));
+        assert_eq!(
+            msg.tags().iter().collect::<Vec<_>>(),
+            vec![("color", "#FF69B4"), ("display-name", "museun")]
+        );
+    }
 
     #[test]
     fn round_trip_escape() {
         let s = r"foo;bar and\foo\rwith\n";
-        assert_eq!(unescape_str(&*escape_str(s)), s);
+        assert_eq!(unescape_str(&escape_str(s)), s);
     }
 
     #[test]
     fn escaped_tag() {
         let s = escape_str(r"@hello;world=abc\ndef");
-        let data = MaybeOwned::Borrowed(&*s);
-        let indices = TagIndices::build_indices(&*data).unwrap();
+        let data = MaybeOwned::Borrowed(&s);
+        let indices = TagIndices::build_indices(&data).unwrap();
 
         let tags = Tags::from_data_indices(&data, &indices);
         assert_eq!(tags.get_unescaped("hello;world").unwrap(), r"abc\ndef");
@@ -322,7 +363,7 @@ mod tests {
     #[test]
     fn invalid_input_missing_leading_at() {
         let data = MaybeOwned::Borrowed("foo=bar;baz=quux");
-        let indices = TagIndices::build_indices(&*data).unwrap();
+        let indices = TagIndices::build_indices(&data).unwrap();
 
         let tags = Tags::from_data_indices(&data, &indices);
         assert!(tags.is_empty());
@@ -333,8 +374,8 @@ mod tests {
         let inputs = &["@", ""];
 
         for input in inputs {
-            let data = MaybeOwned::Borrowed(*input);
-            let indices = TagIndices::build_indices(&*data).unwrap();
+            let data = MaybeOwned::Borrowed(input);
+            let indices = TagIndices::build_indices(&data).unwrap();
 
             let tags = Tags::from_data_indices(&data, &indices);
             assert!(tags.is_empty());
@@ -344,7 +385,7 @@ mod tests {
     #[test]
     fn get_parsed() {
         let input = MaybeOwned::Borrowed("@foo=42;badges=broadcaster/1,subscriber/6");
-        let indices = TagIndices::build_indices(&*input).unwrap();
+        let indices = TagIndices::build_indices(&input).unwrap();
 
         let tags = Tags::from_data_indices(&input, &indices);
         assert_eq!(tags.get_parsed::<_, usize>("foo").unwrap(), 42);
@@ -358,7 +399,7 @@ mod tests {
         struct Badges(std::collections::HashMap<String, usize>);
 
         impl FromStr for Badges {
-            type Err = std::convert::Infallible;
+            type Err = core::convert::Infallible;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
                 let iter = s.split_terminator(',').filter_map(|s| {
                     let mut iter = s.split('/');
@@ -379,7 +420,7 @@ mod tests {
     #[test]
     fn get_bool() {
         let input = MaybeOwned::Borrowed("@foo=42;ok=true;nope=false");
-        let indices = TagIndices::build_indices(&*input).unwrap();
+        let indices = TagIndices::build_indices(&input).unwrap();
 
         let tags = Tags::from_data_indices(&input, &indices);
         assert!(!tags.get_as_bool("foo"));
@@ -397,8 +438,8 @@ mod tests {
         ];
 
         for input in inputs {
-            let data = MaybeOwned::Borrowed(*input);
-            let indices = TagIndices::build_indices(&*data).unwrap();
+            let data = MaybeOwned::Borrowed(input);
+            let indices = TagIndices::build_indices(&data).unwrap();
             let tags = Tags::from_data_indices(&data, &indices);
 
             assert_eq!(tags.get("foo").unwrap(), "bar");
@@ -417,8 +458,8 @@ mod tests {
         ];
 
         for input in inputs {
-            let data = MaybeOwned::Borrowed(*input);
-            let indices = TagIndices::build_indices(&*data).unwrap();
+            let data = MaybeOwned::Borrowed(input);
+            let indices = TagIndices::build_indices(&data).unwrap();
             let tags = Tags::from_data_indices(&data, &indices);
 
             let len = tags.into_iter().count();
@@ -477,7 +518,7 @@ mod tests {
         ];
 
         let input = MaybeOwned::Borrowed(input);
-        let indices = TagIndices::build_indices(&*input).unwrap();
+        let indices = TagIndices::build_indices(&input).unwrap();
 
         let tags = Tags::from_data_indices(&input, &indices);
 
@@ -11,7 +11,6 @@
     missing_crate_level_docs,
     missing_debug_implementations,
     missing_docs,
-    private_in_public,
     rust_2018_compatibility,
     // rust_2018_idioms, // this complains about elided lifetimes.
     trivial_casts,
@@ -21,6 +20,7 @@
     unused_import_braces,
     unused_qualifications
 )]
+#![cfg_attr(all(feature = "no_std", not(any(test, feature = "async"))), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![cfg_attr(docsrs, feature(doc_alias))]
 #![cfg_attr(docsrs, feature(broken_intra_doc_links))]
@@ -35,6 +35,15 @@ By default, this crate depends on zero external crates -- but it makes it rather
 
 This allows parsing, and decoding/encoding to standard trait types (`std::io::{Read, Write}`).
 
+With the `no_std` feature, this crate is `#![no_std]` (it only needs `alloc`)
+and just the parsing is available: [irc], [messages] and [twitch]. Everything doing I/O
+(the [decoder], [encoder], [commands] and the [runner]) is removed. This is ignored
+if the `async` feature is enabled.
+
+```toml
+twitchchat = { version = "0.14", features = ["no_std"] }
+```
+
 To use the [AsyncRunner] (an async-event loop) and related helpers, you must able the `async` feature.
 
 ***NOTE*** This is a breaking change from `0.12` which had the async stuff enabled by default.
@@ -63,6 +72,18 @@ For just encoding messages:
 
 */
 
+extern crate alloc;
+
+macro_rules! cfg_std {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(not(feature = "no_std"), feature = "async"))]
+            #[cfg_attr(docsrs, doc(cfg(not(feature = "no_std"))))]
+            $item
+        )*
+    };
+}
+
 macro_rules! cfg_async {
     ($($item:item)*) => {
         $(
@@ -91,38 +112,43 @@ pub const TWITCH_TLS_DOMAIN: &str = "irc.chat.twitch.tv";
 /// An anonymous login.
 pub const ANONYMOUS_LOGIN: (&str, &str) = (JUSTINFAN1234, JUSTINFAN1234);
 pub(crate) const JUSTINFAN1234: &str = "justinfan1234";
+pub(crate) const JUSTINFAN: &str = "justinfan";
 
 #[macro_use]
 #[allow(unused_macros)]
 mod macros;
 
-pub mod decoder;
-pub use decoder::{DecodeError, Decoder};
+cfg_std! { pub mod decoder; }
+cfg_std! { pub use decoder::{DecodeError, Decoder}; }
 cfg_async! { pub use decoder::AsyncDecoder; }
 
-pub mod encoder;
-pub use encoder::Encoder;
+cfg_std! { pub mod encoder; }
+cfg_std! { pub use encoder::Encoder; }
 cfg_async! { pub use encoder::AsyncEncoder; }
 
-/// A boxed `Future` that is `Send + Sync`
-pub type BoxedFuture<T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + Sync>>;
+cfg_std! {
+    /// A boxed `Future` that is `Send + Sync`
+    pub type BoxedFuture<T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + Sync>>;
+}
 
 cfg_async! {
     /// An AsyncWriter over an MpscWriter
-    pub type Writer = crate::writer::AsyncWriter<crate::writer::MpscWriter>;
+    pub type Writer = writer::AsyncWriter<writer::MpscWriter>;
 }
 
 cfg_async! { pub mod connector; }
 cfg_async! { pub mod writer; }
 cfg_async! { pub mod channel; }
 
-pub mod runner;
-pub use runner::{Error as RunnerError, Status};
+cfg_std! { pub mod runner; }
+cfg_std! { pub use runner::{Error as RunnerError, Status}; }
+
+cfg_std! { pub mod handshake; }
 cfg_async! { pub use runner::AsyncRunner; }
 
-pub mod rate_limit;
+cfg_std! { pub mod rate_limit; }
 
-pub mod commands;
+cfg_std! { pub mod commands; }
 pub mod messages;
 
 pub mod irc;
@@ -137,8 +163,8 @@ pub use irc::{FromIrcMessage, IntoIrcMessage};
 pub mod twitch;
 pub use twitch::UserConfig;
 
-mod encodable;
-pub use encodable::Encodable;
+cfg_std! { mod encodable; }
+cfg_std! { pub use encodable::Encodable; }
 
 pub mod maybe_owned;
 pub use maybe_owned::IntoOwned;
@@ -147,9 +173,10 @@ use maybe_owned::{MaybeOwned, MaybeOwnedIndex};
 mod validator;
 pub use validator::Validator;
 
-mod ext;
+cfg_std! { mod ext; }
 #[cfg(feature = "serde")]
 mod serde;
-mod util;
+cfg_std! { mod util; }
 
-pub use ext::PrivmsgExt;
+cfg_std! { pub use ext::PrivmsgExt; }
+cfg_async! { pub use ext::AsyncPrivmsgExt; }
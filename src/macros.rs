@@ -46,8 +46,8 @@ macro_rules! serde_struct {
 
 macro_rules! impl_custom_debug {
     ($ty:ident { $($field:ident),* $(,)? }) => {
-        impl<'a> std::fmt::Debug for $ty<'a> {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        impl<'a> core::fmt::Debug for $ty<'a> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                 f.debug_struct(stringify!($ty))
                     $( .field(stringify!($field), &self.$field()) )*
                 .finish()
@@ -61,7 +61,24 @@ macro_rules! raw {
     () => {
         /// Get the raw message
         pub fn raw(&self) -> &str {
-            &*self.raw
+            &*self.msg.raw
+        }
+
+        /// Get the [IrcMessage](crate::IrcMessage) this was parsed from
+        pub fn as_raw(&self) -> &$crate::IrcMessage<'a> {
+            &self.msg
+        }
+    };
+}
+
+macro_rules! is_in_channel {
+    () => {
+        /// Whether this message is in the channel `name`
+        ///
+        /// Both sides are normalized first, so `Museun`, `#Museun` and `#museun` are all the same channel
+        pub fn is_in_channel(&self, name: &str) -> bool {
+            $crate::twitch::normalize_channel(self.channel())
+                == $crate::twitch::normalize_channel(name)
         }
     };
 }
@@ -70,7 +87,7 @@ macro_rules! into_inner_raw {
     () => {
         /// Consumes the message, returning the raw [`MaybeOwned<'_>`](./enum.Str.html)
         fn into_inner(self) -> MaybeOwned<'a> {
-            self.raw
+            self.msg.raw
         }
     };
 }
@@ -80,7 +97,7 @@ macro_rules! tags {
         /// Get a view of parsable tags
         pub fn tags(&self) -> $crate::irc::Tags<'_> {
             Tags {
-                data: &self.raw,
+                data: &self.msg.raw,
                 indices: &self.tags,
             }
         }
@@ -91,12 +108,12 @@ macro_rules! str_field {
     ($(#[$meta:meta])* $name:ident) => {
         $(#[$meta])*
         pub fn $name(&self) -> &str {
-            &self.raw[self.$name]
+            &self.msg.raw[self.$name]
         }
     };
     ($name:ident) => {
         pub fn $name(&self) -> &str {
-            &self.raw[self.$name]
+            &self.msg.raw[self.$name]
         }
     };
 }
@@ -105,13 +122,13 @@ macro_rules! opt_str_field {
     ($(#[$meta:meta])* $name:ident) => {
         $(#[$meta])*
         pub fn $name(&self) -> Option<&str> {
-            self.$name.map(|index| &self.raw[index])
+            self.$name.map(|index| &self.msg.raw[index])
         }
     };
 
     ($name:ident) => {
         pub fn $name(&self) -> Option<&str> {
-            self.$name.map(|index| &self.raw[index])
+            self.$name.map(|index| &self.msg.raw[index])
         }
     };
 }
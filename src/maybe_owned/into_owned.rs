@@ -1,5 +1,6 @@
 use super::{MaybeOwned, MaybeOwnedIndex};
 use crate::twitch::{Color, UserConfig};
+use alloc::{string::ToString, vec::Vec};
 
 /// Converts a **borrowed** type into an owned type. e.g. `'a` to `'static`
 pub trait IntoOwned<'a> {
@@ -54,6 +55,21 @@ impl<'a, T: IntoOwned<'a> + 'a> IntoOwned<'a> for Option<T> {
     }
 }
 
+impl<'a, T: IntoOwned<'a> + 'a> IntoOwned<'a> for Vec<T> {
+    type Output = Vec<T::Output>;
+    fn into_owned(self) -> Self::Output {
+        self.into_iter().map(IntoOwned::into_owned).collect()
+    }
+}
+
+// the error is passed through as-is, so any 'static error type can be used
+impl<'a, T: IntoOwned<'a> + 'a, E: 'static> IntoOwned<'a> for Result<T, E> {
+    type Output = Result<T::Output, E>;
+    fn into_owned(self) -> Self::Output {
+        self.map(IntoOwned::into_owned)
+    }
+}
+
 macro_rules! into_owned_primitives {
     ($($ty:ty)*) => {
         $(impl IntoOwned<'static> for $ty {
@@ -70,3 +86,46 @@ into_owned_primitives! {
     i8 i16 i32 i64 i128 isize
     bool f32 f64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{irc::MessageError, messages::Privmsg, FromIrcMessage as _, IrcMessage};
+
+    fn privmsgs(input: &str) -> Vec<Privmsg<'_>> {
+        crate::irc::parse(input)
+            .map(|msg| Privmsg::from_irc(msg.unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn vec() {
+        let input = ":a!a@a PRIVMSG #museun :hello\r\n:b!b@b PRIVMSG #museun :world\r\n";
+        let owned: Vec<Privmsg<'static>> = privmsgs(input).into_owned();
+        assert_eq!(owned.len(), 2);
+        assert_eq!(owned[0].data(), "hello");
+        assert_eq!(owned[1].data(), "world");
+    }
+
+    #[test]
+    fn option() {
+        let input = ":a!a@a PRIVMSG #museun :hello\r\n";
+        let owned: Option<Privmsg<'static>> = privmsgs(input).pop().into_owned();
+        assert_eq!(owned.unwrap().data(), "hello");
+
+        let none: Option<Privmsg<'_>> = None;
+        assert!(none.into_owned().is_none());
+    }
+
+    #[test]
+    fn result() {
+        let input = ":a!a@a PRIVMSG #museun :hello\r\n";
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(input));
+        let owned: Result<IrcMessage<'static>, MessageError> = msg.into_owned();
+        assert_eq!(owned.unwrap().get_raw(), input);
+
+        let err: Result<IrcMessage<'_>, _> = Err(std::io::Error::other("nope"));
+        let owned: Result<IrcMessage<'static>, std::io::Error> = err.into_owned();
+        assert_eq!(owned.unwrap_err().to_string(), "nope");
+    }
+}
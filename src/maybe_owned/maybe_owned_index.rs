@@ -1,5 +1,5 @@
 use super::MaybeOwned;
-use std::ops::{Index, Range};
+use core::ops::{Index, Range};
 
 type IndexWidth = u16;
 
@@ -13,6 +13,9 @@ pub struct MaybeOwnedIndex {
 }
 
 impl MaybeOwnedIndex {
+    /// The maximum length of a string that can be indexed
+    pub const MAX_LEN: usize = IndexWidth::MAX as usize;
+
     /// Create a new index from this start and end point
     pub const fn raw(start: usize, end: usize) -> Self {
         Self {
@@ -52,7 +55,7 @@ impl MaybeOwnedIndex {
 
     /// Replace this index with a new one start/ending at `pos`, returning the old index
     pub fn replace(&mut self, pos: usize) -> Self {
-        std::mem::replace(self, Self::new(pos))
+        core::mem::replace(self, Self::new(pos))
     }
 
     /// Checks whether this index is empty (e.g. start points to the dn)
@@ -87,14 +90,14 @@ impl<'a> Index<MaybeOwnedIndex> for MaybeOwned<'a> {
     }
 }
 
-impl<'a> Index<&MaybeOwnedIndex> for str {
+impl Index<&MaybeOwnedIndex> for str {
     type Output = Self;
     fn index(&self, index: &MaybeOwnedIndex) -> &Self::Output {
         &self[index.as_range()]
     }
 }
 
-impl<'a> Index<MaybeOwnedIndex> for str {
+impl Index<MaybeOwnedIndex> for str {
     type Output = Self;
     fn index(&self, index: MaybeOwnedIndex) -> &Self::Output {
         &self[index.as_range()]
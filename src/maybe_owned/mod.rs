@@ -3,7 +3,11 @@
 //! It is read-only unlike the std implementation.
 //!
 //! Its also specialized for just `str`
-use std::{fmt::Debug, ops::Deref};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+use core::{fmt::Debug, ops::Deref};
 
 mod into_owned;
 pub use into_owned::IntoOwned;
@@ -48,7 +52,7 @@ impl<'a> Clone for MaybeOwned<'a> {
 }
 
 impl<'a> Debug for MaybeOwned<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.as_ref().fmt(f)
     }
 }
@@ -74,7 +78,7 @@ impl<'a> PartialEq<&str> for MaybeOwned<'a> {
 impl<'a> AsRef<str> for MaybeOwned<'a> {
     fn as_ref(&self) -> &str {
         match self {
-            MaybeOwned::Owned(s) => &*s,
+            MaybeOwned::Owned(s) => s,
             MaybeOwned::Borrowed(s) => s,
         }
     }
@@ -29,9 +29,28 @@
 //! assert!(matches!(all, Commands::Privmsg{..}));
 //! ```
 //!
+//! # Encoding a message
+//!
+//! All of the messages are [Encodable](crate::Encodable), which writes the
+//! line they were parsed from back out. This is useful for proxies and for replaying messages.
+//!
+//! ```
+//! # #[cfg(any(not(feature = "no_std"), feature = "async"))] {
+//! use twitchchat::{Encodable, FromIrcMessage, messages::Commands};
+//!
+//! let input = ":user!user@user PRIVMSG #test_channel :this is some data\r\n";
+//! let irc_msg = twitchchat::irc::parse(input).next().unwrap().unwrap();
+//! let all = Commands::from_irc(irc_msg).unwrap();
+//!
+//! let mut out = vec![];
+//! all.encode(&mut out).unwrap();
+//! assert_eq!(out, input.as_bytes());
+//! # }
+//! ```
+//!
 
 mod commands;
-pub use commands::Commands;
+pub use commands::{by_channel, CommandKind, Commands};
 
 mod irc_ready;
 pub use irc_ready::IrcReady;
@@ -39,6 +58,9 @@ pub use irc_ready::IrcReady;
 mod ready;
 pub use ready::Ready;
 
+mod numeric;
+pub use numeric::{Created, Motd, MotdStart, MyInfo, YourHost};
+
 mod cap;
 pub use cap::{Cap, Capability};
 
@@ -58,7 +80,7 @@ mod join;
 pub use join::Join;
 
 mod notice;
-pub use notice::{MessageId, Notice};
+pub use notice::{CommandError, MessageId, Notice};
 
 mod part;
 pub use part::Part;
@@ -88,3 +110,45 @@ mod whisper;
 pub use whisper::Whisper;
 
 pub use crate::irc::IrcMessage;
+
+// messages keep the line they were parsed from, so they're encoded as that line
+macro_rules! encodable_raw {
+    ($($ty:ident)*) => {
+        $(#[cfg(any(not(feature = "no_std"), feature = "async"))]
+        impl<'a> crate::Encodable for $ty<'a> {
+            fn encode<W>(&self, buf: &mut W) -> std::io::Result<()>
+            where
+                W: std::io::Write + ?Sized,
+            {
+                crate::encodable::encode_line(self.raw(), buf)
+            }
+        })*
+    };
+}
+
+encodable_raw! {
+    Commands
+    IrcReady
+    Ready
+    YourHost
+    Created
+    MyInfo
+    MotdStart
+    Motd
+    Cap
+    ClearChat
+    ClearMsg
+    GlobalUserState
+    HostTarget
+    Join
+    Notice
+    Part
+    Ping
+    Pong
+    Privmsg
+    Reconnect
+    RoomState
+    UserNotice
+    UserState
+    Whisper
+}
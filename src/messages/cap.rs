@@ -19,7 +19,7 @@ pub enum Capability<'a> {
 /// Acknowledgement (or not) on a **CAPS** request
 #[derive(Clone, PartialEq)]
 pub struct Cap<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     capability: MaybeOwnedIndex,
     acknowledged: bool,
 }
@@ -29,13 +29,27 @@ impl<'a> Cap<'a> {
 
     /// The parsed capability
     pub fn capability(&self) -> Capability<'_> {
-        let cap = &self.raw[self.capability];
+        let cap = &self.msg.raw[self.capability];
         if self.acknowledged {
             Capability::Acknowledged(cap)
         } else {
             Capability::NotAcknowledged(cap)
         }
     }
+
+    /// Whether the capabilities were acknowledged (e.g. `ACK`)
+    ///
+    /// This is `false` for both `NAK` and `LS`
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged
+    }
+
+    /// An iterator over the capability names in this message
+    ///
+    /// e.g. `CAP * ACK :twitch.tv/tags twitch.tv/commands` will produce `twitch.tv/tags` and `twitch.tv/commands`
+    pub fn capabilities(&self) -> impl Iterator<Item = &str> + '_ {
+        self.msg.raw[self.capability].split_whitespace()
+    }
 }
 
 impl<'a> FromIrcMessage<'a> for Cap<'a> {
@@ -49,7 +63,7 @@ impl<'a> FromIrcMessage<'a> for Cap<'a> {
         let this = Self {
             capability: msg.expect_data_index()?,
             acknowledged: msg.expect_arg(1)? == ACK,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -59,7 +73,7 @@ impl<'a> FromIrcMessage<'a> for Cap<'a> {
 }
 
 into_owned!(Cap {
-    raw,
+    msg,
     capability,
     acknowledged
 });
@@ -92,7 +106,7 @@ mod tests {
         ];
         for (msg, expected) in parse(input).map(|s| s.unwrap()).zip(expected) {
             let msg = Cap::from_irc(msg).unwrap();
-            assert_eq!(msg.capability(), Capability::Acknowledged(*expected));
+            assert_eq!(msg.capability(), Capability::Acknowledged(expected));
         }
     }
 
@@ -104,4 +118,36 @@ mod tests {
             assert_eq!(cap.capability(), Capability::NotAcknowledged("foobar"));
         }
     }
+
+    #[test]
+    fn cap_capabilities() {
+        let input = ":tmi.twitch.tv CAP * ACK :twitch.tv/tags twitch.tv/commands\r\n";
+        let cap = Cap::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+        assert!(cap.is_acknowledged());
+        assert_eq!(
+            cap.capabilities().collect::<Vec<_>>(),
+            vec!["twitch.tv/tags", "twitch.tv/commands"]
+        );
+
+        let input = ":tmi.twitch.tv CAP * NAK :twitch.tv/tags\r\n";
+        let cap = Cap::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+        assert!(!cap.is_acknowledged());
+        assert_eq!(
+            cap.capabilities().collect::<Vec<_>>(),
+            vec!["twitch.tv/tags"]
+        );
+
+        let input =
+            ":tmi.twitch.tv CAP * LS :twitch.tv/tags twitch.tv/commands twitch.tv/membership\r\n";
+        let cap = Cap::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+        assert!(!cap.is_acknowledged());
+        assert_eq!(
+            cap.capabilities().collect::<Vec<_>>(),
+            vec![
+                "twitch.tv/tags",
+                "twitch.tv/commands",
+                "twitch.tv/membership"
+            ]
+        );
+    }
 }
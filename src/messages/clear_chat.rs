@@ -5,7 +5,7 @@ use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 /// Typically after a user is banned from chat or timed out
 #[derive(Clone, PartialEq)]
 pub struct ClearChat<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     tags: TagIndices,
     channel: MaybeOwnedIndex,
     name: Option<MaybeOwnedIndex>,
@@ -19,6 +19,7 @@ impl<'a> ClearChat<'a> {
         /// The channel this event happened on
         channel
     );
+    is_in_channel!();
     opt_str_field!(
         /// The user, if any, that was being purged
         name
@@ -33,6 +34,13 @@ impl<'a> ClearChat<'a> {
     pub fn room_id(&self) -> Option<&str> {
         self.tags().get("room-id")
     }
+
+    /// The id of the user, if any, that was being purged
+    ///
+    /// Unlike their [name](Self::name), this doesn't change if the user renames themselves.
+    pub fn target_user_id(&self) -> Option<u64> {
+        self.tags().get_parsed("target-user-id")
+    }
 }
 
 impl<'a> FromIrcMessage<'a> for ClearChat<'a> {
@@ -45,7 +53,7 @@ impl<'a> FromIrcMessage<'a> for ClearChat<'a> {
             tags: msg.parse_tags(),
             channel: msg.expect_arg_index(0)?,
             name: msg.data,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -55,7 +63,7 @@ impl<'a> FromIrcMessage<'a> for ClearChat<'a> {
 }
 
 into_owned!(ClearChat {
-    raw,
+    msg,
     tags,
     channel,
     name
@@ -68,6 +76,7 @@ impl_custom_debug!(ClearChat {
     name,
     ban_duration,
     room_id,
+    target_user_id,
 });
 
 serde_struct!(ClearChat {
@@ -96,6 +105,18 @@ mod tests {
             let cc = ClearChat::from_irc(msg).unwrap();
             assert_eq!(cc.channel(), "#museun");
             assert_eq!(cc.name().unwrap(), "shaken_bot");
+            assert_eq!(cc.target_user_id(), None);
+        }
+    }
+
+    #[test]
+    fn clear_chat_target_user_id() {
+        let input = "@ban-duration=600;room-id=23196011;target-user-id=27121543;tmi-sent-ts=1601079032426 :tmi.twitch.tv CLEARCHAT #museun :shaken_bot\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let cc = ClearChat::from_irc(msg).unwrap();
+            assert_eq!(cc.name().unwrap(), "shaken_bot");
+            assert_eq!(cc.target_user_id(), Some(27121543));
+            assert_eq!(cc.ban_duration(), Some(600));
         }
     }
 
@@ -5,7 +5,7 @@ use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 /// This is triggered via `/delete` on IRC.
 #[derive(Clone, PartialEq)]
 pub struct ClearMsg<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     tags: TagIndices,
     channel: MaybeOwnedIndex,
     message: Option<MaybeOwnedIndex>,
@@ -18,6 +18,7 @@ impl<'a> ClearMsg<'a> {
         /// The channel this event happened on
         channel
     );
+    is_in_channel!();
     opt_str_field!(
         /// The message that was deleted
         message
@@ -44,7 +45,7 @@ impl<'a> FromIrcMessage<'a> for ClearMsg<'a> {
             tags: msg.parse_tags(),
             channel: msg.expect_arg_index(0)?,
             message: msg.data,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -54,7 +55,7 @@ impl<'a> FromIrcMessage<'a> for ClearMsg<'a> {
 }
 
 into_owned!(ClearMsg {
-    raw,
+    msg,
     tags,
     channel,
     message,
@@ -13,6 +13,16 @@ pub enum Commands<'a> {
     IrcReady(IrcReady<'a>),
     /// A ClearChat event occured
     Ready(Ready<'a>),
+    /// A YourHost event occured
+    YourHost(YourHost<'a>),
+    /// A Created event occured
+    Created(Created<'a>),
+    /// A MyInfo event occured
+    MyInfo(MyInfo<'a>),
+    /// A MotdStart event occured
+    MotdStart(MotdStart<'a>),
+    /// A Motd event occured
+    Motd(Motd<'a>),
     /// A ClearMsg event occured
     Cap(Cap<'a>),
     /// A GlobalUserState event occured
@@ -47,13 +57,75 @@ pub enum Commands<'a> {
     Whisper(Whisper<'a>),
 }
 
+/// The kind of a [Commands], without any of its data.
+///
+/// This is useful for cheaply keying maps (e.g. for metrics) by the message type.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum CommandKind {
+    /// A Raw event
+    Raw,
+    /// A IrcReady event
+    IrcReady,
+    /// A Ready event
+    Ready,
+    /// A YourHost event
+    YourHost,
+    /// A Created event
+    Created,
+    /// A MyInfo event
+    MyInfo,
+    /// A MotdStart event
+    MotdStart,
+    /// A Motd event
+    Motd,
+    /// A Cap event
+    Cap,
+    /// A ClearChat event
+    ClearChat,
+    /// A ClearMsg event
+    ClearMsg,
+    /// A GlobalUserState event
+    GlobalUserState,
+    /// A HostTarget event
+    HostTarget,
+    /// A Join event
+    Join,
+    /// A Notice event
+    Notice,
+    /// A Part event
+    Part,
+    /// A Ping event
+    Ping,
+    /// A Pong event
+    Pong,
+    /// A Privmsg event
+    Privmsg,
+    /// A Reconnect event
+    Reconnect,
+    /// A RoomState event
+    RoomState,
+    /// A UserNotice event
+    UserNotice,
+    /// A UserState event
+    UserState,
+    /// A Whisper event
+    Whisper,
+}
+
 impl<'a> Commands<'a> {
     /// Get the raw string out of this
-    pub fn raw(&'a self) -> &'a str {
+    pub fn raw(&self) -> &str {
         match self {
             Self::Raw(msg) => msg.get_raw(),
             Self::IrcReady(msg) => msg.raw(),
             Self::Ready(msg) => msg.raw(),
+            Self::YourHost(msg) => msg.raw(),
+            Self::Created(msg) => msg.raw(),
+            Self::MyInfo(msg) => msg.raw(),
+            Self::MotdStart(msg) => msg.raw(),
+            Self::Motd(msg) => msg.raw(),
             Self::Cap(msg) => msg.raw(),
             Self::ClearChat(msg) => msg.raw(),
             Self::ClearMsg(msg) => msg.raw(),
@@ -72,6 +144,101 @@ impl<'a> Commands<'a> {
             Self::Whisper(msg) => msg.raw(),
         }
     }
+
+    /// Get the [IrcMessage] this was parsed from
+    ///
+    /// ```rust
+    /// use twitchchat::{messages::Commands, FromIrcMessage as _};
+    ///
+    /// let input = ":museun!museun@museun PRIVMSG #museun :hello\r\n";
+    /// let all = Commands::from_irc(twitchchat::irc::parse(input).next().unwrap().unwrap()).unwrap();
+    ///
+    /// let msg = all.as_raw();
+    /// assert_eq!(msg.get_raw(), input);
+    /// assert_eq!(msg.get_command(), "PRIVMSG");
+    /// ```
+    pub fn as_raw(&self) -> &IrcMessage<'a> {
+        match self {
+            Self::Raw(msg) => msg,
+            Self::IrcReady(msg) => msg.as_raw(),
+            Self::Ready(msg) => msg.as_raw(),
+            Self::YourHost(msg) => msg.as_raw(),
+            Self::Created(msg) => msg.as_raw(),
+            Self::MyInfo(msg) => msg.as_raw(),
+            Self::MotdStart(msg) => msg.as_raw(),
+            Self::Motd(msg) => msg.as_raw(),
+            Self::Cap(msg) => msg.as_raw(),
+            Self::ClearChat(msg) => msg.as_raw(),
+            Self::ClearMsg(msg) => msg.as_raw(),
+            Self::GlobalUserState(msg) => msg.as_raw(),
+            Self::HostTarget(msg) => msg.as_raw(),
+            Self::Join(msg) => msg.as_raw(),
+            Self::Notice(msg) => msg.as_raw(),
+            Self::Part(msg) => msg.as_raw(),
+            Self::Ping(msg) => msg.as_raw(),
+            Self::Pong(msg) => msg.as_raw(),
+            Self::Privmsg(msg) => msg.as_raw(),
+            Self::Reconnect(msg) => msg.as_raw(),
+            Self::RoomState(msg) => msg.as_raw(),
+            Self::UserNotice(msg) => msg.as_raw(),
+            Self::UserState(msg) => msg.as_raw(),
+            Self::Whisper(msg) => msg.as_raw(),
+        }
+    }
+
+    /// Get the id of the room (the channel's user id) this message was sent to
+    ///
+    /// This is the `room-id` tag, which is what the Helix API uses to identify
+    /// a channel. This returns `None` for messages that don't have one.
+    ///
+    /// ```rust
+    /// use twitchchat::{messages::Commands, FromIrcMessage as _};
+    ///
+    /// let input = "@room-id=23196011 :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+    /// let msg = Commands::from_irc(twitchchat::irc::parse(input).next().unwrap().unwrap()).unwrap();
+    /// assert_eq!(msg.room_id(), Some(23196011));
+    /// ```
+    pub fn room_id(&self) -> Option<u64> {
+        match self {
+            Self::ClearChat(msg) => msg.room_id()?.parse().ok(),
+            Self::Notice(msg) => msg.room_id(),
+            Self::Privmsg(msg) => msg.room_id(),
+            Self::RoomState(msg) => msg.room_id(),
+            Self::UserNotice(msg) => msg.room_id(),
+            Self::UserState(msg) => msg.room_id(),
+            _ => None,
+        }
+    }
+
+    /// Get the [CommandKind] of this message
+    pub fn kind(&self) -> CommandKind {
+        match self {
+            Self::Raw(..) => CommandKind::Raw,
+            Self::IrcReady(..) => CommandKind::IrcReady,
+            Self::Ready(..) => CommandKind::Ready,
+            Self::YourHost(..) => CommandKind::YourHost,
+            Self::Created(..) => CommandKind::Created,
+            Self::MyInfo(..) => CommandKind::MyInfo,
+            Self::MotdStart(..) => CommandKind::MotdStart,
+            Self::Motd(..) => CommandKind::Motd,
+            Self::Cap(..) => CommandKind::Cap,
+            Self::ClearChat(..) => CommandKind::ClearChat,
+            Self::ClearMsg(..) => CommandKind::ClearMsg,
+            Self::GlobalUserState(..) => CommandKind::GlobalUserState,
+            Self::HostTarget(..) => CommandKind::HostTarget,
+            Self::Join(..) => CommandKind::Join,
+            Self::Notice(..) => CommandKind::Notice,
+            Self::Part(..) => CommandKind::Part,
+            Self::Ping(..) => CommandKind::Ping,
+            Self::Pong(..) => CommandKind::Pong,
+            Self::Privmsg(..) => CommandKind::Privmsg,
+            Self::Reconnect(..) => CommandKind::Reconnect,
+            Self::RoomState(..) => CommandKind::RoomState,
+            Self::UserNotice(..) => CommandKind::UserNotice,
+            Self::UserState(..) => CommandKind::UserState,
+            Self::Whisper(..) => CommandKind::Whisper,
+        }
+    }
 }
 
 impl<'a> IntoOwned<'a> for Commands<'a> {
@@ -82,6 +249,11 @@ impl<'a> IntoOwned<'a> for Commands<'a> {
             Self::Raw(s) => Commands::Raw(s.into_owned()),
             Self::IrcReady(s) => Commands::IrcReady(s.into_owned()),
             Self::Ready(s) => Commands::Ready(s.into_owned()),
+            Self::YourHost(s) => Commands::YourHost(s.into_owned()),
+            Self::Created(s) => Commands::Created(s.into_owned()),
+            Self::MyInfo(s) => Commands::MyInfo(s.into_owned()),
+            Self::MotdStart(s) => Commands::MotdStart(s.into_owned()),
+            Self::Motd(s) => Commands::Motd(s.into_owned()),
             Self::Cap(s) => Commands::Cap(s.into_owned()),
             Self::ClearChat(s) => Commands::ClearChat(s.into_owned()),
             Self::ClearMsg(s) => Commands::ClearMsg(s.into_owned()),
@@ -116,6 +288,11 @@ impl<'a> FromIrcMessage<'a> for Commands<'a> {
         let this = match msg.get_command() {
             M::IRC_READY => map!(IrcReady),
             M::READY => map!(Ready),
+            M::YOUR_HOST => map!(YourHost),
+            M::CREATED => map!(Created),
+            M::MY_INFO => map!(MyInfo),
+            M::MOTD_START => map!(MotdStart),
+            M::MOTD => map!(Motd),
             M::CAP => map!(Cap),
             M::CLEAR_CHAT => map!(ClearChat),
             M::CLEAR_MSG => map!(ClearMsg),
@@ -144,6 +321,11 @@ impl<'a> FromIrcMessage<'a> for Commands<'a> {
             Self::Raw(msg) => msg.into_inner(),
             Self::IrcReady(msg) => msg.into_inner(),
             Self::Ready(msg) => msg.into_inner(),
+            Self::YourHost(msg) => msg.into_inner(),
+            Self::Created(msg) => msg.into_inner(),
+            Self::MyInfo(msg) => msg.into_inner(),
+            Self::MotdStart(msg) => msg.into_inner(),
+            Self::Motd(msg) => msg.into_inner(),
             Self::Cap(msg) => msg.into_inner(),
             Self::ClearChat(msg) => msg.into_inner(),
             Self::ClearMsg(msg) => msg.into_inner(),
@@ -164,6 +346,42 @@ impl<'a> FromIrcMessage<'a> for Commands<'a> {
     }
 }
 
+/// Get the channel a message is scoped to, if it has one
+///
+/// This returns `None` for global messages, e.g. `GLOBALUSERSTATE`, `PING` or a `WHISPER`.
+///
+/// It's useful for routing messages to per-channel workers without matching on every variant.
+///
+/// ```rust
+/// use twitchchat::{messages::{by_channel, Commands}, FromIrcMessage as _};
+///
+/// let input = ":museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\nPING :1234\r\n";
+/// let channels = twitchchat::irc::parse(input)
+///     .map(|msg| Commands::from_irc(msg.unwrap()).unwrap())
+///     .map(|msg| by_channel(&msg).map(ToString::to_string))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(channels, vec![Some("#museun".to_string()), None]);
+/// ```
+pub fn by_channel<'a>(msg: &'a Commands<'_>) -> Option<&'a str> {
+    let channel = match msg {
+        Commands::ClearChat(msg) => msg.channel(),
+        Commands::ClearMsg(msg) => msg.channel(),
+        Commands::HostTarget(msg) => msg.source(),
+        Commands::Join(msg) => msg.channel(),
+        Commands::Notice(msg) => msg.channel(),
+        Commands::Part(msg) => msg.channel(),
+        Commands::Privmsg(msg) => msg.channel(),
+        Commands::RoomState(msg) => msg.channel(),
+        Commands::UserNotice(msg) => msg.channel(),
+        Commands::UserState(msg) => msg.channel(),
+        _ => return None,
+    };
+
+    // global notices are sent to '*'
+    Some(channel).filter(|&channel| channel != "*")
+}
+
 macro_rules! from_other {
     ($($ident:tt)*) => {
         $(impl<'a> From<$ident<'a>> for Commands<'a> {
@@ -180,6 +398,11 @@ from_other! {
     Raw
     IrcReady
     Ready
+    YourHost
+    Created
+    MyInfo
+    MotdStart
+    Motd
     Cap
     ClearChat
     ClearMsg
@@ -206,8 +429,8 @@ mod tests {
     #[cfg(feature = "serde")]
     fn commands_serde() {
         let input = ":test!test@test PRIVMSG #museun :this is a test\r\n";
-        crate::serde::round_trip_json::<Commands>(input);
-        crate::serde::round_trip_rmp::<Commands>(input);
+        serde::round_trip_json::<Commands>(input);
+        serde::round_trip_rmp::<Commands>(input);
     }
 
     #[test]
@@ -217,4 +440,206 @@ mod tests {
         let all = Commands::from_irc(msg).unwrap();
         assert!(matches!(all, Commands::Privmsg { .. }));
     }
+
+    #[test]
+    fn as_raw() {
+        let input = "@badges=broadcaster/1;display-name=museun :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello world\r\n";
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        let all = Commands::from_irc(msg.clone()).unwrap();
+        assert!(matches!(all, Commands::Privmsg { .. }));
+
+        let raw = all.as_raw();
+        assert_eq!(raw, &msg);
+        assert_eq!(raw.get_raw(), input);
+        assert_eq!(raw.get_command(), "PRIVMSG");
+
+        // it's the message stored in the variant
+        let all = all.into_owned();
+        assert!(std::ptr::eq(all.as_raw().get_raw(), all.raw()));
+    }
+
+    #[test]
+    fn numerics() {
+        let input = ":tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n";
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        match Commands::from_irc(msg).unwrap() {
+            Commands::IrcReady(msg) => assert_eq!(msg.data(), Some("Welcome, GLHF!")),
+            cmd => panic!("unexpected: {:?}", cmd),
+        }
+
+        let input =
+            ":tmi.twitch.tv 372 shaken_bot :You are in a maze of twisty passages, all alike.\r\n";
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        assert!(matches!(
+            Commands::from_irc(msg).unwrap(),
+            Commands::Motd { .. }
+        ));
+    }
+
+    #[test]
+    fn kind() {
+        let input = ":test!test@test PRIVMSG #museun :this is a test\r\n\
+                     PING :1234567890\r\n\
+                     :test!test@test JOIN #museun\r\n\
+                     :tmi.twitch.tv NOTICE #museun :hello\r\n\
+                     :tmi.twitch.tv 421 museun FOO :Unknown command\r\n";
+
+        let kinds = irc::parse(input)
+            .map(|msg| Commands::from_irc(msg.unwrap()).unwrap().kind())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                CommandKind::Privmsg,
+                CommandKind::Ping,
+                CommandKind::Join,
+                CommandKind::Notice,
+                CommandKind::Raw,
+            ]
+        );
+    }
+
+    #[test]
+    fn into_owned_all_variants() {
+        fn assert_static<T: 'static>(_: &T) {}
+
+        let input = String::from(
+            ":tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n\
+             :tmi.twitch.tv 002 shaken_bot :Your host is tmi.twitch.tv\r\n\
+             :tmi.twitch.tv 003 shaken_bot :This server is rather new\r\n\
+             :tmi.twitch.tv 004 shaken_bot :-\r\n\
+             :tmi.twitch.tv 375 shaken_bot :-\r\n\
+             :tmi.twitch.tv 372 shaken_bot :You are in a maze of twisty passages, all alike.\r\n\
+             :tmi.twitch.tv 376 shaken_bot :>\r\n\
+             :tmi.twitch.tv CAP * ACK :twitch.tv/tags\r\n\
+             :tmi.twitch.tv CLEARCHAT #museun :shaken_bot\r\n\
+             @login=shaken_bot;target-msg-id=abc :tmi.twitch.tv CLEARMSG #museun :hello\r\n\
+             @badge-info=;badges=;color=;display-name=shaken_bot;emote-sets=0;user-id=241015868;user-type= :tmi.twitch.tv GLOBALUSERSTATE\r\n\
+             :tmi.twitch.tv HOSTTARGET #museun :shaken_bot 10\r\n\
+             :museun!museun@museun.tmi.twitch.tv JOIN #museun\r\n\
+             @msg-id=host_on :tmi.twitch.tv NOTICE #museun :Now hosting shaken_bot.\r\n\
+             :museun!museun@museun.tmi.twitch.tv PART #museun\r\n\
+             PING :1234567890\r\n\
+             PONG :1234567890\r\n\
+             :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n\
+             :tmi.twitch.tv RECONNECT\r\n\
+             @emote-only=0;room-id=23196011 :tmi.twitch.tv ROOMSTATE #museun\r\n\
+             @msg-id=raid;login=shaken_bot;msg-param-viewerCount=10 :tmi.twitch.tv USERNOTICE #museun\r\n\
+             @badges=;color=;display-name=shaken_bot;emote-sets=0;mod=0;subscriber=0;user-type= :tmi.twitch.tv USERSTATE #museun\r\n\
+             :museun!museun@museun.tmi.twitch.tv WHISPER shaken_bot :hello\r\n\
+             :tmi.twitch.tv 421 museun FOO :Unknown command\r\n",
+        );
+
+        let expected = vec![
+            CommandKind::IrcReady,
+            CommandKind::YourHost,
+            CommandKind::Created,
+            CommandKind::MyInfo,
+            CommandKind::MotdStart,
+            CommandKind::Motd,
+            CommandKind::Ready,
+            CommandKind::Cap,
+            CommandKind::ClearChat,
+            CommandKind::ClearMsg,
+            CommandKind::GlobalUserState,
+            CommandKind::HostTarget,
+            CommandKind::Join,
+            CommandKind::Notice,
+            CommandKind::Part,
+            CommandKind::Ping,
+            CommandKind::Pong,
+            CommandKind::Privmsg,
+            CommandKind::Reconnect,
+            CommandKind::RoomState,
+            CommandKind::UserNotice,
+            CommandKind::UserState,
+            CommandKind::Whisper,
+            CommandKind::Raw,
+        ];
+
+        let borrowed = irc::parse(&input)
+            .map(|msg| Commands::from_irc(msg.unwrap()).unwrap())
+            .collect::<Vec<_>>();
+
+        let owned: Vec<Commands<'static>> = borrowed
+            .iter()
+            .cloned()
+            .map(IntoOwned::into_owned)
+            .collect();
+
+        assert_eq!(borrowed, owned);
+        drop(borrowed);
+        drop(input);
+
+        assert_static(&owned);
+        assert_eq!(
+            owned.iter().map(Commands::kind).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn room_id() {
+        let input = "@room-id=23196011 :test!test@test PRIVMSG #museun :this is a test\r\n\
+                     @login=test;room-id=23196011 :tmi.twitch.tv CLEARCHAT #museun :test\r\n\
+                     @msg-id=slow_on;room-id=23196011 :tmi.twitch.tv NOTICE #museun :This room is now in slow mode.\r\n\
+                     @emote-only=0;room-id=23196011 :tmi.twitch.tv ROOMSTATE #museun\r\n\
+                     @login=test;msg-id=raid;room-id=23196011;system-msg=test :tmi.twitch.tv USERNOTICE #museun\r\n\
+                     @mod=0;room-id=23196011 :tmi.twitch.tv USERSTATE #museun\r\n\
+                     :test!test@test PRIVMSG #museun :no tags\r\n\
+                     :tmi.twitch.tv CLEARCHAT #museun\r\n\
+                     @room-id=23196011 :test!test@test JOIN #museun\r\n\
+                     PING :1234567890\r\n";
+
+        let ids = irc::parse(input)
+            .map(|msg| Commands::from_irc(msg.unwrap()).unwrap())
+            .map(|msg| msg.room_id())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            ids,
+            vec![
+                Some(23196011),
+                Some(23196011),
+                Some(23196011),
+                Some(23196011),
+                Some(23196011),
+                Some(23196011),
+                None,
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn by_channel() {
+        let input = ":test!test@test PRIVMSG #museun :this is a test\r\n\
+                     :test!test@test JOIN #shaken_bot\r\n\
+                     @emote-only=0;room-id=23196011 :tmi.twitch.tv ROOMSTATE #museun\r\n\
+                     :tmi.twitch.tv NOTICE * :Login authentication failed\r\n\
+                     @badge-info=;badges=;color=;display-name=shaken_bot;emote-sets=0;user-id=241015868;user-type= :tmi.twitch.tv GLOBALUSERSTATE\r\n\
+                     PING :1234567890\r\n\
+                     :museun!museun@museun.tmi.twitch.tv WHISPER shaken_bot :hello\r\n";
+
+        let channels = irc::parse(input)
+            .map(|msg| Commands::from_irc(msg.unwrap()).unwrap())
+            .map(|msg| super::by_channel(&msg).map(ToString::to_string))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            channels,
+            vec![
+                Some("#museun".to_string()),
+                Some("#shaken_bot".to_string()),
+                Some("#museun".to_string()),
+                None,
+                None,
+                None,
+                None,
+            ]
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use crate::{irc::*, twitch::*, IntoOwned, MaybeOwned, Validator};
+use alloc::{boxed::Box, vec, vec::Vec};
 
 /// Sent on successful login, if both **TAGS** and **COMMANDS** capabilities have been sent beforehand.
 ///
@@ -11,7 +12,7 @@ use crate::{irc::*, twitch::*, IntoOwned, MaybeOwned, Validator};
 /// verify that you actually got the real message
 #[derive(Clone, PartialEq)]
 pub struct GlobalUserState<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     tags: TagIndices,
     /// Your user-id, if you have Tags enabled
     pub user_id: Option<MaybeOwned<'a>>,
@@ -87,7 +88,7 @@ impl<'a> FromIrcMessage<'a> for GlobalUserState<'a> {
         let color = tags
             .get("color")
             .filter(|s| !s.is_empty())
-            .map(std::str::FromStr::from_str)
+            .map(core::str::FromStr::from_str)
             .transpose()
             .map_err(|err| MessageError::CannotParseTag {
                 name: "color".into(),
@@ -100,7 +101,7 @@ impl<'a> FromIrcMessage<'a> for GlobalUserState<'a> {
             display_name,
             color,
             tags: tag_index,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -110,7 +111,7 @@ impl<'a> FromIrcMessage<'a> for GlobalUserState<'a> {
 }
 
 into_owned!(GlobalUserState {
-    raw,
+    msg,
     tags,
     user_id,
     display_name,
@@ -171,7 +172,7 @@ mod tests {
             let msg = GlobalUserState::from_irc(msg).unwrap();
             assert!(msg.user_id().is_none());
             assert!(msg.display_name().is_none());
-            assert_eq!(msg.color(), crate::twitch::Color::default());
+            assert_eq!(msg.color(), Color::default());
             assert_eq!(msg.emote_sets(), vec!["0"]);
         }
     }
@@ -183,7 +184,7 @@ mod tests {
             let msg = GlobalUserState::from_irc(msg).unwrap();
             assert_eq!(msg.user_id().unwrap(), "241015868");
             assert_eq!(msg.display_name().unwrap(), "shaken_bot");
-            assert_eq!(msg.color(), crate::twitch::Color::default());
+            assert_eq!(msg.color(), Color::default());
             assert_eq!(msg.emote_sets(), vec!["0"]);
         }
     }
@@ -16,7 +16,7 @@ pub enum HostTargetKind<'a> {
 /// When a channel starts to host another channel
 #[derive(Clone, PartialEq)]
 pub struct HostTarget<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     source: MaybeOwnedIndex,
     viewers: Option<usize>,
     target: Option<MaybeOwnedIndex>,
@@ -38,7 +38,7 @@ impl<'a> HostTarget<'a> {
     pub fn host_target_kind(&self) -> HostTargetKind<'_> {
         match self.target {
             Some(index) => HostTargetKind::Start {
-                target: &self.raw[index],
+                target: &self.msg.raw[index],
             },
             None => HostTargetKind::End,
         }
@@ -71,7 +71,7 @@ impl<'a> FromIrcMessage<'a> for HostTarget<'a> {
             source: msg.expect_arg_index(0)?,
             viewers,
             target,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -81,7 +81,7 @@ impl<'a> FromIrcMessage<'a> for HostTarget<'a> {
 }
 
 into_owned!(HostTarget {
-    raw,
+    msg,
     source,
     viewers,
     target,
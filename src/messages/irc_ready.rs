@@ -3,8 +3,9 @@ use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 /// Happens when the IRC connection has been succesfully established
 #[derive(Clone, PartialEq)]
 pub struct IrcReady<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     username: MaybeOwnedIndex,
+    data: Option<MaybeOwnedIndex>,
 }
 
 impl<'a> IrcReady<'a> {
@@ -13,6 +14,10 @@ impl<'a> IrcReady<'a> {
         /// The name the server will refer to you as
         username
     );
+    opt_str_field!(
+        /// The text the server sent, if any
+        data
+    );
 }
 
 impl<'a> FromIrcMessage<'a> for IrcReady<'a> {
@@ -23,7 +28,8 @@ impl<'a> FromIrcMessage<'a> for IrcReady<'a> {
 
         let this = Self {
             username: msg.expect_arg_index(0)?,
-            raw: msg.raw,
+            data: msg.data,
+            msg,
         };
 
         Ok(this)
@@ -32,9 +38,21 @@ impl<'a> FromIrcMessage<'a> for IrcReady<'a> {
     into_inner_raw!();
 }
 
-into_owned!(IrcReady { raw, username });
-impl_custom_debug!(IrcReady { raw, username });
-serde_struct!(IrcReady { raw, username });
+into_owned!(IrcReady {
+    msg,
+    username,
+    data
+});
+impl_custom_debug!(IrcReady {
+    raw,
+    username,
+    data
+});
+serde_struct!(IrcReady {
+    raw,
+    username,
+    data
+});
 
 #[cfg(test)]
 mod tests {
@@ -53,7 +71,18 @@ mod tests {
         let input = ":tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n";
         for irc in parse(input).map(|s| s.unwrap()) {
             let msg = IrcReady::from_irc(irc).unwrap();
-            assert_eq!(msg.username(), "shaken_bot")
+            assert_eq!(msg.username(), "shaken_bot");
+            assert_eq!(msg.data(), Some("Welcome, GLHF!"));
+        }
+    }
+
+    #[test]
+    fn irc_ready_without_text() {
+        let input = ":tmi.twitch.tv 001 shaken_bot\r\n";
+        for irc in parse(input).map(|s| s.unwrap()) {
+            let msg = IrcReady::from_irc(irc).unwrap();
+            assert_eq!(msg.username(), "shaken_bot");
+            assert_eq!(msg.data(), None);
         }
     }
 }
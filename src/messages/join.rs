@@ -5,7 +5,7 @@ use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 /// The happens when a user (yourself included) joins a channel
 #[derive(Clone, PartialEq)]
 pub struct Join<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     name: MaybeOwnedIndex,
     channel: MaybeOwnedIndex,
 }
@@ -20,6 +20,7 @@ impl<'a> Join<'a> {
         /// Channel which they joined
         channel
     );
+    is_in_channel!();
 }
 
 impl<'a> FromIrcMessage<'a> for Join<'a> {
@@ -31,7 +32,7 @@ impl<'a> FromIrcMessage<'a> for Join<'a> {
         let this = Self {
             channel: msg.expect_arg_index(0)?,
             name: msg.expect_nick()?,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -40,7 +41,7 @@ impl<'a> FromIrcMessage<'a> for Join<'a> {
     into_inner_raw!();
 }
 
-into_owned!(Join { raw, name, channel });
+into_owned!(Join { msg, name, channel });
 impl_custom_debug!(Join { raw, name, channel });
 serde_struct!(Join { raw, name, channel });
 
@@ -56,6 +57,16 @@ mod tests {
         crate::serde::round_trip_rmp::<Join>(input);
     }
 
+    #[test]
+    fn join_is_in_channel() {
+        let input = ":test!test@test JOIN #foo\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Join::from_irc(msg).unwrap();
+            assert!(msg.is_in_channel("Foo"));
+            assert!(!msg.is_in_channel("#bar"));
+        }
+    }
+
     #[test]
     fn join_bad_command() {
         let input = ":tmi.twitch.tv NOT_JOIN #foo\r\n";
@@ -3,7 +3,7 @@ use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 /// General notices from the server.
 #[derive(Clone, PartialEq)]
 pub struct Notice<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     tags: TagIndices,
     channel: MaybeOwnedIndex,
     message: MaybeOwnedIndex,
@@ -16,6 +16,7 @@ impl<'a> Notice<'a> {
         /// The channel this event happened on
         channel
     );
+    is_in_channel!();
     str_field!(
         /// The message from the server
         message
@@ -29,8 +30,71 @@ impl<'a> Notice<'a> {
     pub fn msg_id(&self) -> Option<MessageId<'_>> {
         self.tags().get("msg-id").map(MessageId::parse)
     }
+
+    /// If this notice is Twitch rejecting a command you sent, get why
+    ///
+    /// Returns None for informational notices (e.g. `ban_success`) or if there isn't a `msg-id`
+    pub fn as_command_error(&self) -> Option<CommandError> {
+        self.msg_id()?.as_command_error()
+    }
+
+    /// The id of the room this notice was sent to
+    ///
+    /// Twitch doesn't usually send this on notices, so this is often `None`.
+    pub fn room_id(&self) -> Option<u64> {
+        self.tags().get_parsed("room-id")
+    }
 }
 
+/// Why Twitch rejected a command, mapped from the `msg-id` of a [Notice]
+///
+/// See [Notice::as_command_error] and [MessageId::as_command_error]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum CommandError {
+    /// You don't have permission to do this (e.g. you're not a moderator)
+    NoPermission,
+    /// You're banned or suspended
+    Banned,
+    /// You're timed out
+    TimedOut,
+    /// You're sending too many messages or commands
+    RateLimited,
+    /// The channel's chat settings prevented it (e.g. followers-only, emote-only)
+    Restricted,
+    /// The target of the command (e.g. the user or channel) is invalid for it
+    InvalidTarget,
+    /// The command wouldn't change anything (e.g. the user is already banned)
+    AlreadyDone,
+    /// The command was used incorrectly
+    InvalidUsage,
+    /// The command isn't recognized or supported
+    UnknownCommand,
+    /// Twitch failed to do it, try again later
+    Failed,
+}
+
+impl core::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let msg = match self {
+            Self::NoPermission => "you don't have permission",
+            Self::Banned => "you are banned",
+            Self::TimedOut => "you are timed out",
+            Self::RateLimited => "you are being rate limited",
+            Self::Restricted => "the channel's settings prevented it",
+            Self::InvalidTarget => "the target is invalid",
+            Self::AlreadyDone => "it has already been done",
+            Self::InvalidUsage => "the command was used incorrectly",
+            Self::UnknownCommand => "the command is unknown",
+            Self::Failed => "twitch failed to do it",
+        };
+        write!(f, "command rejected: {}", msg)
+    }
+}
+
+impl core::error::Error for CommandError {}
+
 impl<'a> FromIrcMessage<'a> for Notice<'a> {
     type Error = MessageError;
 
@@ -41,7 +105,7 @@ impl<'a> FromIrcMessage<'a> for Notice<'a> {
             tags: msg.parse_tags(),
             channel: msg.expect_arg_index(0)?,
             message: msg.expect_data_index()?,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -51,7 +115,7 @@ impl<'a> FromIrcMessage<'a> for Notice<'a> {
 }
 
 into_owned!(Notice {
-    raw,
+    msg,
     tags,
     channel,
     message,
@@ -432,6 +496,107 @@ pub enum MessageId<'a> {
 }
 
 impl<'a> MessageId<'a> {
+    /// If this is Twitch rejecting a command, get why
+    ///
+    /// Returns None for informational ids
+    pub fn as_command_error(&self) -> Option<CommandError> {
+        use CommandError as E;
+        use MessageId::*;
+
+        let err = match self {
+            NoPermission
+            | BadBanAdmin
+            | BadBanBroadcaster
+            | BadBanGlobalMod
+            | BadBanMod
+            | BadBanStaff
+            | BadDeleteMessageBroadcaster
+            | BadDeleteMessageMod
+            | BadTimeoutAdmin
+            | BadTimeoutBroadcaster
+            | BadTimeoutGlobalMod
+            | BadTimeoutMod
+            | BadTimeoutStaff
+            | RaidErrorForbidden => E::NoPermission,
+
+            MsgBanned | MsgSuspended | MsgChannelSuspended | TosBan | WhisperBanned => E::Banned,
+
+            MsgTimedout => E::TimedOut,
+
+            MsgRatelimit | MsgDuplicate | WhisperLimitPerMin | WhisperLimitPerSec
+            | BadHostRateExceeded => E::RateLimited,
+
+            MsgBadCharacters
+            | MsgChannelBlocked
+            | MsgEmoteonly
+            | MsgFacebook
+            | MsgFollowersonly
+            | MsgFollowersonlyFollowed
+            | MsgFollowersonlyZero
+            | MsgR9k
+            | MsgRejected
+            | MsgRejectedMandatory
+            | MsgSlowmode
+            | MsgSubsonly
+            | MsgVerifiedEmail
+            | TurboOnlyColor
+            | WhisperRestricted
+            | WhisperRestrictedRecipient => E::Restricted,
+
+            InvalidUser
+            | MsgRoomNotFound
+            | BadBanAnon
+            | BadBanSelf
+            | BadHostRejected
+            | BadHostSelf
+            | BadModBanned
+            | BadTimeoutAnon
+            | BadTimeoutSelf
+            | BadUnbanNoBan
+            | NotHosting
+            | RaidErrorSelf
+            | RaidErrorTooManyViewers
+            | TimeoutNoTimeout
+            | UnraidErrorNoActiveRaid
+            | UntimeoutBanned
+            | WhisperBannedRecipient
+            | WhisperInvalidLogin
+            | WhisperInvalidSelf => E::InvalidTarget,
+
+            AlreadyBanned
+            | AlreadyEmoteOnlyOff
+            | AlreadyEmoteOnlyOn
+            | AlreadyR9kOff
+            | AlreadyR9kOn
+            | AlreadySubsOff
+            | AlreadySubsOn
+            | BadHostHosting
+            | BadModMod
+            | BadUnmodMod
+            | RaidErrorAlreadyRaiding => E::AlreadyDone,
+
+            BadSlowDuration | BadTimeoutDuration | WhisperInvalidArgs | UsageBan | UsageClear
+            | UsageColor | UsageCommercial | UsageDisconnect | UsageEmoteOnlyOff
+            | UsageEmoteOnlyOn | UsageFollowersOff | UsageFollowersOn | UsageHelp | UsageHost
+            | UsageMarker | UsageMe | UsageMod | UsageMods | UsageR9kOff | UsageR9kOn
+            | UsageRaid | UsageSlowOff | UsageSlowOn | UsageSubsOff | UsageSubsOn
+            | UsageTimeout | UsageUnban | UsageUnhost | UsageUnmod | UsageUnraid
+            | UsageUntimeout => E::InvalidUsage,
+
+            UnrecognizedCmd | UnsupportedChatroomsCmd | BadMarkerClient => E::UnknownCommand,
+
+            BadCommercialError
+            | BadHostError
+            | BadUnhostError
+            | RaidErrorUnexpected
+            | UnraidErrorUnexpected => E::Failed,
+
+            _ => return None,
+        };
+
+        Some(err)
+    }
+
     pub(super) fn parse(input: &'a str) -> MessageId<'a> {
         use MessageId::*;
         match input {
@@ -596,6 +761,36 @@ mod tests {
         crate::serde::round_trip_rmp::<Notice>(input);
     }
 
+    #[test]
+    fn notice_command_error() {
+        let input = "\
+            @msg-id=no_permission :tmi.twitch.tv NOTICE #museun :You don't have permission to perform that action.\r\n\
+            @msg-id=msg_banned :tmi.twitch.tv NOTICE #museun :You are permanently banned from talking in museun.\r\n\
+            @msg-id=already_banned :tmi.twitch.tv NOTICE #museun :shaken_bot is already banned in this channel.\r\n\
+            @msg-id=usage_timeout :tmi.twitch.tv NOTICE #museun :Usage: \"/timeout <username> [duration][time unit] [reason]\"\r\n\
+            @msg-id=msg_ratelimit :tmi.twitch.tv NOTICE #museun :Your message was not sent because you are sending messages too quickly.\r\n\
+            @msg-id=ban_success :tmi.twitch.tv NOTICE #museun :shaken_bot is now banned from this channel.\r\n\
+            @msg-id=some_new_thing :tmi.twitch.tv NOTICE #museun :Something new.\r\n\
+            :tmi.twitch.tv NOTICE #museun :This room is no longer in slow mode.\r\n\
+        ";
+
+        let expected = &[
+            Some(CommandError::NoPermission),
+            Some(CommandError::Banned),
+            Some(CommandError::AlreadyDone),
+            Some(CommandError::InvalidUsage),
+            Some(CommandError::RateLimited),
+            None,
+            None,
+            None,
+        ];
+
+        for (msg, expected) in parse(input).map(|s| s.unwrap()).zip(expected) {
+            let msg = Notice::from_irc(msg).unwrap();
+            assert_eq!(msg.as_command_error(), *expected, "{}", msg.raw());
+        }
+    }
+
     #[test]
     fn notice() {
         let input = ":tmi.twitch.tv NOTICE #museun :This room is no longer in slow mode.\r\n";
@@ -603,6 +798,13 @@ mod tests {
             let msg = Notice::from_irc(msg).unwrap();
             assert_eq!(msg.channel(), "#museun");
             assert_eq!(msg.message(), "This room is no longer in slow mode.");
+            assert_eq!(msg.room_id(), None);
+        }
+
+        let input = "@msg-id=slow_off;room-id=23196011 :tmi.twitch.tv NOTICE #museun :This room is no longer in slow mode.\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Notice::from_irc(msg).unwrap();
+            assert_eq!(msg.room_id(), Some(23196011));
         }
     }
 }
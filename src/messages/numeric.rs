@@ -0,0 +1,127 @@
+use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+
+// these are all the same shape: `:tmi.twitch.tv <numeric> <username> :<text>`
+macro_rules! numeric {
+    ($(#[$meta:meta])* $ty:ident => $cmd:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, PartialEq)]
+        pub struct $ty<'a> {
+            msg: IrcMessage<'a>,
+            username: MaybeOwnedIndex,
+            data: MaybeOwnedIndex,
+        }
+
+        impl<'a> $ty<'a> {
+            raw!();
+            str_field!(
+                /// The name the server will refer to you as
+                username
+            );
+            str_field!(
+                /// The text the server sent
+                data
+            );
+        }
+
+        impl<'a> FromIrcMessage<'a> for $ty<'a> {
+            type Error = MessageError;
+
+            fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+                msg.expect_command(IrcMessage::$cmd)?;
+
+                let this = Self {
+                    username: msg.expect_arg_index(0)?,
+                    data: msg.expect_data_index()?,
+                    msg,
+                };
+
+                Ok(this)
+            }
+
+            into_inner_raw!();
+        }
+
+        into_owned!($ty { msg, username, data });
+        impl_custom_debug!($ty { raw, username, data });
+        serde_struct!($ty { raw, username, data });
+    };
+}
+
+numeric! {
+    /// The server's host information -- `002`
+    YourHost => YOUR_HOST
+}
+
+numeric! {
+    /// When the server was created -- `003`
+    Created => CREATED
+}
+
+numeric! {
+    /// The server's information -- `004`
+    MyInfo => MY_INFO
+}
+
+numeric! {
+    /// The start of the message of the day -- `375`
+    MotdStart => MOTD_START
+}
+
+numeric! {
+    /// A line of the message of the day -- `372`
+    Motd => MOTD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "\
+        :tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n\
+        :tmi.twitch.tv 002 shaken_bot :Your host is tmi.twitch.tv\r\n\
+        :tmi.twitch.tv 003 shaken_bot :This server is rather new\r\n\
+        :tmi.twitch.tv 004 shaken_bot :-\r\n\
+        :tmi.twitch.tv 375 shaken_bot :-\r\n\
+        :tmi.twitch.tv 372 shaken_bot :You are in a maze of twisty passages, all alike.\r\n\
+        :tmi.twitch.tv 376 shaken_bot :>\r\n\
+    ";
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn numeric_serde() {
+        let input = ":tmi.twitch.tv 002 shaken_bot :Your host is tmi.twitch.tv\r\n";
+        crate::serde::round_trip_json::<YourHost>(input);
+        crate::serde::round_trip_rmp::<YourHost>(input);
+
+        let input =
+            ":tmi.twitch.tv 372 shaken_bot :You are in a maze of twisty passages, all alike.\r\n";
+        crate::serde::round_trip_json::<Motd>(input);
+        crate::serde::round_trip_rmp::<Motd>(input);
+    }
+
+    #[test]
+    fn numeric() {
+        let mut iter = parse(INPUT).map(|s| s.unwrap()).skip(1);
+
+        let msg = YourHost::from_irc(iter.next().unwrap()).unwrap();
+        assert_eq!(msg.username(), "shaken_bot");
+        assert_eq!(msg.data(), "Your host is tmi.twitch.tv");
+
+        let msg = Created::from_irc(iter.next().unwrap()).unwrap();
+        assert_eq!(msg.data(), "This server is rather new");
+
+        let msg = MyInfo::from_irc(iter.next().unwrap()).unwrap();
+        assert_eq!(msg.data(), "-");
+
+        let msg = MotdStart::from_irc(iter.next().unwrap()).unwrap();
+        assert_eq!(msg.data(), "-");
+
+        let msg = Motd::from_irc(iter.next().unwrap()).unwrap();
+        assert_eq!(
+            msg.data(),
+            "You are in a maze of twisty passages, all alike."
+        );
+
+        assert!(Motd::from_irc(iter.next().unwrap()).is_err());
+    }
+}
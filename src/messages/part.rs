@@ -5,7 +5,7 @@ use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 /// The happens when a user (yourself included) leaves a channel
 #[derive(Clone, PartialEq)]
 pub struct Part<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     name: MaybeOwnedIndex,
     channel: MaybeOwnedIndex,
 }
@@ -20,6 +20,7 @@ impl<'a> Part<'a> {
         /// Channel which they left
         channel
     );
+    is_in_channel!();
 }
 
 impl<'a> FromIrcMessage<'a> for Part<'a> {
@@ -31,7 +32,7 @@ impl<'a> FromIrcMessage<'a> for Part<'a> {
         let this = Self {
             channel: msg.expect_arg_index(0)?,
             name: msg.expect_nick()?,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -40,7 +41,7 @@ impl<'a> FromIrcMessage<'a> for Part<'a> {
     into_inner_raw!();
 }
 
-into_owned!(Part { raw, name, channel });
+into_owned!(Part { msg, name, channel });
 impl_custom_debug!(Part { raw, name, channel });
 serde_struct!(Part { raw, name, channel });
 
@@ -3,7 +3,7 @@ use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 /// A ping request from the server
 #[derive(Clone, PartialEq)]
 pub struct Ping<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     token: MaybeOwnedIndex,
 }
 
@@ -23,7 +23,7 @@ impl<'a> FromIrcMessage<'a> for Ping<'a> {
 
         let this = Self {
             token: msg.expect_data_index()?,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -32,7 +32,7 @@ impl<'a> FromIrcMessage<'a> for Ping<'a> {
     into_inner_raw!();
 }
 
-into_owned!(Ping { raw, token });
+into_owned!(Ping { msg, token });
 impl_custom_debug!(Ping { raw, token });
 serde_struct!(Ping { raw, token });
 
@@ -5,7 +5,7 @@ use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 /// This should be a response to sending a PING to the server
 #[derive(Clone, PartialEq)]
 pub struct Pong<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     token: MaybeOwnedIndex,
 }
 
@@ -25,7 +25,7 @@ impl<'a> FromIrcMessage<'a> for Pong<'a> {
 
         let this = Self {
             token: msg.expect_data_index()?,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -34,7 +34,7 @@ impl<'a> FromIrcMessage<'a> for Pong<'a> {
     into_inner_raw!();
 }
 
-into_owned!(Pong { raw, token });
+into_owned!(Pong { msg, token });
 impl_custom_debug!(Pong { raw, token });
 serde_struct!(Pong { raw, token });
 
@@ -1,7 +1,8 @@
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+use alloc::vec::Vec;
 
 use crate::twitch::{
-    parse_badges, parse_badges_iter, parse_emotes, Badge, BadgeInfo, BadgeKind, Color, Emotes,
+    parse_badges, parse_emotes, Badge, BadgeInfo, Color, Emotes, Flag, Role, Roles,
 };
 
 /// Some PRIVMSGs are considered 'CTCP' (client-to-client protocol)
@@ -22,7 +23,7 @@ pub enum Ctcp<'a> {
 /// Message sent by a user
 #[derive(Clone, PartialEq)]
 pub struct Privmsg<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     tags: TagIndices,
     name: MaybeOwnedIndex,
     channel: MaybeOwnedIndex,
@@ -33,7 +34,7 @@ pub struct Privmsg<'a> {
 /// An iterator over badges
 #[derive(Debug)]
 pub struct BadgesIter<'a> {
-    items: Option<std::str::Split<'a, char>>,
+    items: Option<core::str::Split<'a, char>>,
 }
 
 impl<'a> Iterator for BadgesIter<'a> {
@@ -51,7 +52,7 @@ impl<'a> Iterator for BadgesIter<'a> {
 /// An iterator over emotes
 #[derive(Debug)]
 pub struct EmotesIter<'a> {
-    items: Option<std::str::SplitTerminator<'a, char>>,
+    items: Option<core::str::SplitTerminator<'a, char>>,
 }
 
 impl<'a> Iterator for EmotesIter<'a> {
@@ -66,6 +67,21 @@ impl<'a> Iterator for EmotesIter<'a> {
     }
 }
 
+/// An iterator over AutoMod flags
+#[derive(Debug)]
+pub struct FlagsIter<'a> {
+    items: Option<core::str::SplitTerminator<'a, char>>,
+}
+
+impl<'a> Iterator for FlagsIter<'a> {
+    type Item = Flag;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // skip over any malformed items
+        self.items.as_mut()?.find_map(Flag::parse_item)
+    }
+}
+
 impl<'a> Privmsg<'a> {
     raw!();
     tags!();
@@ -77,29 +93,39 @@ impl<'a> Privmsg<'a> {
         /// Channel this message was sent on
         channel
     );
+    is_in_channel!();
     str_field!(
         /// Data that the user provided
         data
     );
 
     /// Iterator alternative to `Privmsg::badges()`
-    pub fn iter_badges(&self) -> BadgesIter {
+    pub fn iter_badges(&self) -> BadgesIter<'_> {
         BadgesIter {
             items: self.tags().get("badges").map(|s| s.split(',')),
         }
     }
 
     /// Iterator alternative to `Privmsg::emotes()`
-    pub fn iter_emotes(&self) -> EmotesIter {
+    pub fn iter_emotes(&self) -> EmotesIter<'_> {
         EmotesIter {
             items: self.tags().get("emotes").map(|s| s.split_terminator('/')),
         }
     }
 
+    /// Iterator over the AutoMod flags on this message
+    ///
+    /// This'll be empty if the message wasn't flagged
+    pub fn flags(&self) -> FlagsIter<'_> {
+        FlagsIter {
+            items: self.tags().get("flags").map(|s| s.split_terminator(',')),
+        }
+    }
+
     /// Gets the 'CTCP' kind associated with this message, if any
     pub fn ctcp(&self) -> Option<Ctcp<'_>> {
         const ACTION: &str = "ACTION";
-        let command = &self.raw[self.ctcp?];
+        let command = &self.msg.raw[self.ctcp?];
         if command == ACTION {
             Some(Ctcp::Action)
         } else {
@@ -150,7 +176,7 @@ impl<'a> Privmsg<'a> {
     /// their display name to **FOO** then this'll return that **FOO**.
     ///
     /// Otherwise it'll return `None`.
-    pub fn display_name(&'a self) -> Option<&str> {
+    pub fn display_name(&'a self) -> Option<&'a str> {
         self.tags().get("display-name")
     }
 
@@ -162,39 +188,71 @@ impl<'a> Privmsg<'a> {
             .unwrap_or_default()
     }
 
+    /// The roles of the user sending this message, from their badges
+    ///
+    /// This only looks at the badges once, so it's cheaper than calling the `is_*` methods separately.
+    pub fn roles(&self) -> Roles {
+        Roles::from_badges(self.iter_badges())
+    }
+
+    /// Whether the user sending this message had any of these [roles](Role)
+    ///
+    /// This is useful for cheaply filtering messages, e.g. for a moderator only command:
+    /// ```rust
+    /// # use twitchchat::{messages::Privmsg, twitch::Role, FromIrcMessage as _, irc};
+    /// let input = "@badges=moderator/1 :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :!kick someone\r\n";
+    /// let msg = Privmsg::from_irc(irc::parse(input).next().unwrap().unwrap()).unwrap();
+    /// assert!(msg.has_any_role(&[Role::Broadcaster, Role::Moderator]));
+    /// ```
+    pub fn has_any_role(&self, roles: &[Role]) -> bool {
+        self.roles().has_any(roles)
+    }
+
     /// Whether the user sending this message was a broadcaster
     pub fn is_broadcaster(&self) -> bool {
-        self.contains_badge(BadgeKind::Broadcaster)
+        self.roles().broadcaster
     }
 
     /// Whether the user sending this message was a moderator
     pub fn is_moderator(&self) -> bool {
-        self.contains_badge(BadgeKind::Moderator)
+        self.roles().moderator
     }
 
     /// Whether the user sending this message was a vip
     pub fn is_vip(&self) -> bool {
-        self.contains_badge(BadgeKind::VIP)
+        self.roles().vip
     }
 
     /// Whether the user sending this message was a susbcriber
+    ///
+    /// Twitch treats founders as subscribers, so this is also true for them.
     pub fn is_subscriber(&self) -> bool {
-        self.contains_badge(BadgeKind::Subscriber)
+        self.roles().subscriber
+    }
+
+    /// Whether the user sending this message was a founder, one of the channel's earliest subscribers
+    pub fn is_founder(&self) -> bool {
+        self.roles().founder
     }
 
     /// Whether the user sending this message was a staff member
     pub fn is_staff(&self) -> bool {
-        self.contains_badge(BadgeKind::Staff)
+        self.roles().staff
     }
 
     /// Whether the user sending this message had turbo
     pub fn is_turbo(&self) -> bool {
-        self.contains_badge(BadgeKind::Turbo)
+        self.roles().turbo
     }
 
     /// Whether the user sending this message was a global moderator
     pub fn is_global_moderator(&self) -> bool {
-        self.contains_badge(BadgeKind::GlobalMod)
+        self.roles().global_moderator
+    }
+
+    /// Whether the user sending this message was a partner
+    pub fn is_partner(&self) -> bool {
+        self.roles().partner
     }
 
     /// The id of the room this message was sent to
@@ -229,14 +287,6 @@ impl<'a> Privmsg<'a> {
     pub fn msg_id(&self) -> Option<&str> {
         self.tags().get("msg-id")
     }
-
-    fn contains_badge(&self, badge: BadgeKind<'_>) -> bool {
-        self.tags()
-            .get("badges")
-            .into_iter()
-            .flat_map(parse_badges_iter)
-            .any(|x| x.kind == badge)
-    }
 }
 
 impl<'a> FromIrcMessage<'a> for Privmsg<'a> {
@@ -275,7 +325,7 @@ impl<'a> FromIrcMessage<'a> for Privmsg<'a> {
             channel: msg.expect_arg_index(0)?,
             data: index,
             ctcp,
-            raw: msg.raw,
+            msg,
         };
         Ok(this)
     }
@@ -284,7 +334,7 @@ impl<'a> FromIrcMessage<'a> for Privmsg<'a> {
 }
 
 into_owned!(Privmsg {
-    raw,
+    msg,
     tags,
     name,
     channel,
@@ -321,6 +371,19 @@ impl_custom_debug!(Privmsg {
     // msg_id,
 });
 
+/// This renders the message in a human readable form, e.g. `[#channel] name: data`
+///
+/// Actions (e.g. `/me`) are rendered as `[#channel] * name data`
+impl<'a> core::fmt::Display for Privmsg<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_action() {
+            write!(f, "[{}] * {} {}", self.channel(), self.name(), self.data())
+        } else {
+            write!(f, "[{}] {}: {}", self.channel(), self.name(), self.data())
+        }
+    }
+}
+
 serde_struct!(Privmsg {
     raw,
     tags,
@@ -423,6 +486,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn privmsg_user_id() {
+        let input = "@badge-info=;badges=;color=;display-name=museun;emotes=;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.user_id(), Some(23196011));
+        }
+
+        let input = ":museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.user_id(), None);
+        }
+    }
+
+    #[test]
+    fn privmsg_unknown_badge() {
+        let input = "@badge-info=subscriber/8;badges=vip/1,subscriber/6,my_custom_badge/7;color=#FF69B4;display-name=museun;emotes=;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=1;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            let badges = msg.badges();
+            assert_eq!(badges.len(), 3);
+
+            assert_eq!(badges[0].kind(), &crate::twitch::BadgeKind::VIP);
+            assert_eq!(badges[1].kind(), &crate::twitch::BadgeKind::Subscriber);
+            assert_eq!(badges[1].version(), "6");
+
+            assert_eq!(
+                badges[2].kind(),
+                &crate::twitch::BadgeKind::Unknown("my_custom_badge")
+            );
+            assert_eq!(badges[2].version(), "7");
+        }
+    }
+
     #[test]
     fn privmsg_emotes_iter() {
         let input = "@badge-info=;badges=broadcaster/1;color=#FF69B4;display-name=museun;emote-only=1;emotes=25:0-4,6-10/81274:12-17;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :Kappa Kappa VoHiYo\r\n";
@@ -431,4 +529,169 @@ mod tests {
             assert_eq!(msg.iter_emotes().count(), 2);
         }
     }
+
+    #[test]
+    fn privmsg_is_in_channel() {
+        let input = ":museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.is_in_channel("Museun"));
+            assert!(msg.is_in_channel("#MUSEUN"));
+            assert!(msg.is_in_channel("museun"));
+            assert!(!msg.is_in_channel("shaken_bot"));
+            assert!(!msg.is_in_channel("#museun2"));
+        }
+    }
+
+    #[test]
+    fn privmsg_has_any_role() {
+        let input = "@badges=moderator/1 :a!a@a.tmi.twitch.tv PRIVMSG #museun :!mod\r\n\
+                     @badges=subscriber/6 :b!b@b.tmi.twitch.tv PRIVMSG #museun :!sub\r\n\
+                     @badges=broadcaster/1,subscriber/0 :c!c@c.tmi.twitch.tv PRIVMSG #museun :!both\r\n\
+                     @badges= :d!d@d.tmi.twitch.tv PRIVMSG #museun :!none\r\n\
+                     :e!e@e.tmi.twitch.tv PRIVMSG #museun :!untagged\r\n";
+
+        let msgs = parse(input)
+            .map(|s| Privmsg::from_irc(s.unwrap()).unwrap())
+            .collect::<Vec<_>>();
+
+        let filter = |roles: &[Role]| {
+            msgs.iter()
+                .filter(|msg| msg.has_any_role(roles))
+                .map(|msg| msg.data())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            filter(&[Role::Broadcaster, Role::Moderator]),
+            vec!["!mod", "!both"]
+        );
+        assert_eq!(filter(&[Role::Subscriber]), vec!["!sub", "!both"]);
+        assert!(filter(&[]).is_empty());
+    }
+
+    #[test]
+    fn privmsg_roles() {
+        let input = "@badge-info=subscriber/8;badges=moderator/1,subscriber/6,partner/1,turbo/1;color=#FF69B4;display-name=museun;emotes=;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=1;room-id=23196011;subscriber=1;tmi-sent-ts=1601079032426;turbo=1;user-id=23196011;user-type=mod :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            let roles = msg.roles();
+            assert_eq!(
+                roles,
+                Roles {
+                    moderator: true,
+                    subscriber: true,
+                    partner: true,
+                    turbo: true,
+                    ..Roles::default()
+                }
+            );
+
+            assert!(msg.is_moderator());
+            assert!(msg.is_subscriber());
+            assert!(msg.is_partner());
+            assert!(msg.is_turbo());
+            assert!(!msg.is_broadcaster());
+            assert!(!msg.is_vip());
+            assert!(!msg.is_staff());
+            assert!(!msg.is_global_moderator());
+        }
+
+        let input = ":museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.roles(), Roles::default());
+        }
+    }
+
+    #[test]
+    fn privmsg_founder_is_subscriber() {
+        let input = "@badge-info=founder/14;badges=founder/0;color=#FF69B4;display-name=museun;emotes=;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.is_founder());
+            assert!(msg.is_subscriber());
+            assert!(msg.has_any_role(&[Role::Subscriber]));
+        }
+    }
+
+    #[test]
+    fn privmsg_flags() {
+        use crate::twitch::{FlagCategory, FlagScore};
+
+        let input = "@badge-info=;badges=;color=;display-name=museun;emotes=;flags=0-4:P.5,10-15:A.7/I.6;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :dang it, you jerk!\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            let flags = msg.flags().collect::<Vec<_>>();
+            assert_eq!(flags.len(), 2);
+
+            assert_eq!(flags[0].range, 0..4);
+            assert_eq!(
+                flags[0].scores,
+                vec![FlagScore {
+                    category: FlagCategory::Profanity,
+                    level: 5
+                }]
+            );
+
+            assert_eq!(flags[1].range, 10..15);
+            assert_eq!(
+                flags[1].scores,
+                vec![
+                    FlagScore {
+                        category: FlagCategory::Aggressive,
+                        level: 7
+                    },
+                    FlagScore {
+                        category: FlagCategory::Identity,
+                        level: 6
+                    }
+                ]
+            );
+        }
+
+        let input = "@badge-info=;badges=;color=;display-name=museun;emotes=;flags=5-9:S.3;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :well heck\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            let flags = msg.flags().collect::<Vec<_>>();
+            assert_eq!(
+                flags,
+                vec![Flag {
+                    range: 5..9,
+                    scores: vec![FlagScore {
+                        category: FlagCategory::Sexual,
+                        level: 3
+                    }]
+                }]
+            );
+        }
+
+        let input = "@badge-info=;badges=broadcaster/1;color=#FF69B4;display-name=museun;emotes=;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n\
+                     :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.flags().count(), 0);
+        }
+    }
+
+    #[test]
+    fn privmsg_flags_skips_malformed() {
+        let input = "@flags=0-4:P.5,bad,10-15:A.7 :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :dang it, you jerk!\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            let ranges = msg.flags().map(|flag| flag.range).collect::<Vec<_>>();
+            assert_eq!(ranges, vec![0..4, 10..15]);
+        }
+    }
+
+    #[test]
+    fn privmsg_display() {
+        let input = ":test!user@host PRIVMSG #museun :this is a test\r\n\
+                     :test!user@host PRIVMSG #museun :\x01ACTION waves\x01\r\n";
+        let expected = &["[#museun] test: this is a test", "[#museun] * test waves"];
+        for (msg, expected) in parse(input).map(|s| s.unwrap()).zip(expected) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.to_string(), *expected);
+        }
+    }
 }
@@ -4,8 +4,9 @@ use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 /// established
 #[derive(Clone, PartialEq)]
 pub struct Ready<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     username: MaybeOwnedIndex,
+    data: Option<MaybeOwnedIndex>,
 }
 
 impl<'a> Ready<'a> {
@@ -14,6 +15,10 @@ impl<'a> Ready<'a> {
         /// The name Twitch will refer to you as
         username
     );
+    opt_str_field!(
+        /// The text the server sent, if any
+        data
+    );
 }
 
 impl<'a> FromIrcMessage<'a> for Ready<'a> {
@@ -24,7 +29,8 @@ impl<'a> FromIrcMessage<'a> for Ready<'a> {
 
         let this = Self {
             username: msg.expect_arg_index(0)?,
-            raw: msg.raw,
+            data: msg.data,
+            msg,
         };
 
         Ok(this)
@@ -33,9 +39,21 @@ impl<'a> FromIrcMessage<'a> for Ready<'a> {
     into_inner_raw!();
 }
 
-into_owned!(Ready { raw, username });
-impl_custom_debug!(Ready { raw, username });
-serde_struct!(Ready { raw, username });
+into_owned!(Ready {
+    msg,
+    username,
+    data
+});
+impl_custom_debug!(Ready {
+    raw,
+    username,
+    data
+});
+serde_struct!(Ready {
+    raw,
+    username,
+    data
+});
 
 #[cfg(test)]
 mod tests {
@@ -54,7 +72,18 @@ mod tests {
         let input = ":tmi.twitch.tv 376 shaken_bot :>\r\n";
         for irc in parse(input).map(|s| s.unwrap()) {
             let msg = Ready::from_irc(irc).unwrap();
-            assert_eq!(msg.username(), "shaken_bot")
+            assert_eq!(msg.username(), "shaken_bot");
+            assert_eq!(msg.data(), Some(">"));
+        }
+    }
+
+    #[test]
+    fn ready_without_text() {
+        let input = ":tmi.twitch.tv 376 shaken_bot\r\n";
+        for irc in parse(input).map(|s| s.unwrap()) {
+            let msg = Ready::from_irc(irc).unwrap();
+            assert_eq!(msg.username(), "shaken_bot");
+            assert_eq!(msg.data(), None);
         }
     }
 }
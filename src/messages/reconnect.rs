@@ -9,7 +9,7 @@ use crate::{irc::*, MaybeOwned, Validator};
 /// would normally.
 #[derive(Clone, PartialEq)]
 pub struct Reconnect<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
 }
 
 impl<'a> Reconnect<'a> {
@@ -21,13 +21,13 @@ impl<'a> FromIrcMessage<'a> for Reconnect<'a> {
 
     fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
         msg.expect_command(IrcMessage::RECONNECT)?;
-        Ok(Self { raw: msg.raw })
+        Ok(Self { msg })
     }
 
     into_inner_raw!();
 }
 
-into_owned!(Reconnect { raw });
+into_owned!(Reconnect { msg });
 impl_custom_debug!(Reconnect { raw });
 serde_struct!(Reconnect { raw });
 
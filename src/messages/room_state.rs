@@ -15,7 +15,7 @@ pub enum FollowersOnly {
 /// Identifies the channel's chat settings (e.g., slow mode duration).
 #[derive(Clone, PartialEq)]
 pub struct RoomState<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     tags: TagIndices,
     channel: MaybeOwnedIndex,
 }
@@ -28,7 +28,7 @@ impl<'a> FromIrcMessage<'a> for RoomState<'a> {
         let this = Self {
             tags: msg.parse_tags(),
             channel: msg.expect_arg_index(0)?,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -44,6 +44,7 @@ impl<'a> RoomState<'a> {
         /// The channel that this event is happening on
         channel
     );
+    is_in_channel!();
 
     /// Whether this room is in emote only mode
     pub fn is_emote_only(&self) -> bool {
@@ -84,7 +85,7 @@ impl<'a> RoomState<'a> {
     }
 }
 
-into_owned!(RoomState { raw, tags, channel });
+into_owned!(RoomState { msg, tags, channel });
 impl_custom_debug!(RoomState { raw, tags, channel });
 serde_struct!(RoomState { raw, tags, channel });
 
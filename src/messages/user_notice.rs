@@ -1,5 +1,6 @@
 use crate::twitch::{parse_badges, parse_emotes, Badge, BadgeInfo, Color, Emotes};
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+use alloc::{borrow::Cow, string::String, vec::Vec};
 
 /// A paid subscription ot the channel
 #[non_exhaustive]
@@ -54,7 +55,7 @@ pub enum NoticeType<'a> {
 /// Announces Twitch-specific events to the channel (e.g., a user's subscription notification).
 #[derive(Clone, PartialEq)]
 pub struct UserNotice<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     tags: TagIndices,
     channel: MaybeOwnedIndex,
     message: Option<MaybeOwnedIndex>,
@@ -67,6 +68,7 @@ impl<'a> UserNotice<'a> {
         /// The channel that this event is happening on
         channel
     );
+    is_in_channel!();
     opt_str_field!(
         /// Optional message attached to the event
         message
@@ -162,15 +164,23 @@ impl<'a> UserNotice<'a> {
     }
 
     /// The message printed in chat along with this notice
+    ///
+    /// This is the same as [UserNotice::system_message], but always allocates.
     pub fn system_msg(&self) -> Option<String> {
-        self.tags()
-            .get("system-msg")?
-            .replace("\\s", " ")
-            .replace("\\r", "\r")
-            .replace("\\n", "\n")
-            .replace("\\\\", "\\")
-            .replace("\\:", ":")
-            .into()
+        self.system_message().map(Cow::into_owned)
+    }
+
+    /// The message printed in chat along with this notice, unescaped
+    ///
+    /// e.g. `GlitchButSmol subscribed at Tier 1. They've subscribed for 5 months!`
+    ///
+    /// This only allocates if the tag had any escaped characters.
+    /// Not every type of notice has a system message.
+    pub fn system_message(&self) -> Option<Cow<'_, str>> {
+        match self.tags().get_unescaped("system-msg")? {
+            MaybeOwned::Borrowed(s) => Some(Cow::Borrowed(s)),
+            MaybeOwned::Owned(s) => Some(Cow::Owned(s.into())),
+        }
     }
 
     /// (Sent only on sub, resub) The total number of months the user has
@@ -189,7 +199,6 @@ impl<'a> UserNotice<'a> {
     }
 
     /// (Sent on only raid) The name of the source user raiding this channel.
-
     pub fn msg_param_login(&self) -> Option<&str> {
         self.tags().get("msg-param-login")
     }
@@ -316,7 +325,7 @@ impl<'a> FromIrcMessage<'a> for UserNotice<'a> {
             channel: msg.expect_arg_index(0)?,
             message: msg.data,
             tags: msg.parse_tags(),
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -326,7 +335,7 @@ impl<'a> FromIrcMessage<'a> for UserNotice<'a> {
 }
 
 into_owned!(UserNotice {
-    raw,
+    msg,
     tags,
     channel,
     message,
@@ -389,13 +398,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn user_notice_system_message() {
+        let input = "@badge-info=;badges=;color=;display-name=GlitchButSmol;emotes=;flags=;id=3198b02c-eaf4-4904-9b07-eb1b2b12ba50;login=glitchbutsmol;mod=0;msg-id=resub;room-id=22552479;subscriber=1;system-msg=GlitchButSmol\\ssubscribed\\sat\\sTier\\s1.\\sThey've\\ssubscribed\\sfor\\s5\\smonths\\:\\s\\\\o/;tmi-sent-ts=1580932171144;user-id=44979519;user-type= :tmi.twitch.tv USERNOTICE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserNotice::from_irc(msg).unwrap();
+            let expected =
+                r"GlitchButSmol subscribed at Tier 1. They've subscribed for 5 months; \o/";
+            assert_eq!(msg.system_message().unwrap(), expected);
+            assert_eq!(msg.system_msg().unwrap(), expected);
+            assert_eq!(msg.user_id(), Some(44979519));
+        }
+
+        let input = "@msg-id=raid;system-msg=nothing_escaped :tmi.twitch.tv USERNOTICE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserNotice::from_irc(msg).unwrap();
+            assert!(matches!(
+                msg.system_message(),
+                Some(Cow::Borrowed("nothing_escaped"))
+            ));
+        }
+
+        let input = "@msg-id=raid :tmi.twitch.tv USERNOTICE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserNotice::from_irc(msg).unwrap();
+            assert_eq!(msg.system_message(), None);
+            assert_eq!(msg.system_msg(), None);
+            assert_eq!(msg.user_id(), None);
+        }
+    }
+
     #[test]
     fn user_notice_unknown() {
         let input = "@badge-info=subscriber/8;badges=subscriber/6,bits/100;color=#59517B;display-name=lllAirJordanlll;emotes=;flags=;id=3198b02c-eaf4-4904-9b07-eb1b2b12ba50;login=lllairjordanlll;mod=0;msg-id=resub;msg-param-cumulative-months=8;msg-param-months=0;msg-param-should-share-streak=0;msg-param-sub-plan-name=Channel\\sSubscription\\s(giantwaffle);msg-param-sub-plan=1000;room-id=22552479;subscriber=1;system-msg=lllAirJordanlll\\ssubscribed\\sat\\sTier\\s1.\\sThey\'ve\\ssubscribed\\sfor\\s8\\smonths!;tmi-sent-ts=1580932171144;user-id=44979519;user-type= :tmi.twitch.tv USERNOTICE #giantwaffle\r\n";
         for msg in parse(input).map(|s| s.unwrap()) {
             let msg = UserNotice::from_irc(msg).unwrap();
             assert_eq!(msg.channel(), "#giantwaffle");
-            assert_eq!(msg.tags().is_empty(), false);
+            assert!(!msg.tags().is_empty());
         }
     }
 }
@@ -1,10 +1,11 @@
 use crate::twitch::{parse_badges, parse_emotes, Badge, BadgeInfo, Color, Emotes};
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+use alloc::vec::Vec;
 
 /// Identifies a user's chat settings or properties (e.g., chat color)..
 #[derive(Clone, PartialEq)]
 pub struct UserState<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     tags: TagIndices,
     channel: MaybeOwnedIndex,
 }
@@ -16,6 +17,7 @@ impl<'a> UserState<'a> {
         /// Channel this event happened on
         channel
     );
+    is_in_channel!();
 
     /// Metadata related to the chat badges
     ///
@@ -58,6 +60,13 @@ impl<'a> UserState<'a> {
     pub fn is_moderator(&self) -> bool {
         self.tags().get_as_bool("mod")
     }
+
+    /// The id of the room this user state is for
+    ///
+    /// Twitch doesn't usually send this on `USERSTATE`, so this is often `None`.
+    pub fn room_id(&self) -> Option<u64> {
+        self.tags().get_parsed("room-id")
+    }
 }
 
 impl<'a> FromIrcMessage<'a> for UserState<'a> {
@@ -69,7 +78,7 @@ impl<'a> FromIrcMessage<'a> for UserState<'a> {
         let this = Self {
             tags: msg.parse_tags(),
             channel: msg.expect_arg_index(0)?,
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -78,7 +87,7 @@ impl<'a> FromIrcMessage<'a> for UserState<'a> {
     into_inner_raw!();
 }
 
-into_owned!(UserState { raw, tags, channel });
+into_owned!(UserState { msg, tags, channel });
 impl_custom_debug!(UserState { raw, tags, channel });
 serde_struct!(UserState { raw, tags, channel });
 
@@ -100,6 +109,13 @@ mod tests {
         for msg in parse(input).map(|s| s.unwrap()) {
             let msg = UserState::from_irc(msg).unwrap();
             assert_eq!(msg.channel(), "#museun");
+            assert_eq!(msg.room_id(), None);
+        }
+
+        let input = "@mod=0;room-id=23196011 :tmi.twitch.tv USERSTATE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserState::from_irc(msg).unwrap();
+            assert_eq!(msg.room_id(), Some(23196011));
         }
     }
 }
@@ -2,11 +2,12 @@ use crate::twitch::{
     parse_badges, parse_badges_iter, parse_emotes, Badge, BadgeKind, Color, Emotes,
 };
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+use alloc::vec::Vec;
 
 /// Message sent by another user to your user (a 'DM')
 #[derive(Clone, PartialEq)]
 pub struct Whisper<'a> {
-    raw: MaybeOwned<'a>,
+    msg: IrcMessage<'a>,
     tags: TagIndices,
     name: MaybeOwnedIndex,
     data: MaybeOwnedIndex,
@@ -105,7 +106,7 @@ impl<'a> FromIrcMessage<'a> for Whisper<'a> {
             name: msg.expect_nick()?,
             data: msg.expect_data_index()?,
             tags: msg.parse_tags(),
-            raw: msg.raw,
+            msg,
         };
 
         Ok(this)
@@ -115,7 +116,7 @@ impl<'a> FromIrcMessage<'a> for Whisper<'a> {
 }
 
 into_owned!(Whisper {
-    raw,
+    msg,
     tags,
     name,
     data,
@@ -153,6 +154,13 @@ mod tests {
 
             assert_eq!(msg.name(), "test");
             assert_eq!(msg.data(), "this is a test");
+            assert_eq!(msg.user_id(), None);
+        }
+
+        let input = "@badges=;color=;display-name=test;emotes=;message-id=1;thread-id=23196011_27121543;turbo=0;user-id=27121543;user-type= :test!user@host WHISPER museun :this is a test\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Whisper::from_irc(msg).unwrap();
+            assert_eq!(msg.user_id(), Some(27121543));
         }
     }
 }
@@ -8,9 +8,10 @@ use std::time::{Duration, Instant};
 
 /// A preset number of tokens as described by Twitch
 #[non_exhaustive]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 pub enum RateClass {
     /// `20` per `30` seconds
+    #[default]
     Regular,
     /// `100` per `30` seconds
     Moderator,
@@ -20,12 +21,6 @@ pub enum RateClass {
     Verified,
 }
 
-impl Default for RateClass {
-    fn default() -> Self {
-        Self::Regular
-    }
-}
-
 impl RateClass {
     /// Number of tickets available for this class
     pub fn tickets(self) -> u64 {
@@ -43,6 +38,32 @@ impl RateClass {
     }
 }
 
+/// A preset number of `JOIN`s as described by Twitch
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum JoinRate {
+    /// `20` per `10` seconds
+    #[default]
+    Regular,
+    /// `2000` per `10` seconds
+    Verified,
+}
+
+impl JoinRate {
+    /// Number of tickets available for this rate
+    pub fn tickets(self) -> u64 {
+        match self {
+            Self::Regular => 20,
+            Self::Verified => 2000,
+        }
+    }
+
+    /// Period specified by Twitch
+    pub const fn period() -> Duration {
+        Duration::from_secs(10)
+    }
+}
+
 /// A leaky-bucket style token-based rate limiter
 #[derive(Debug, Clone)]
 pub struct RateLimit {
@@ -82,6 +103,11 @@ impl RateLimit {
         Self::full(rate_class.tickets(), RateClass::period())
     }
 
+    /// Create a rate limit from a JoinRate
+    pub fn from_join_rate(join_rate: JoinRate) -> Self {
+        Self::full(join_rate.tickets(), JoinRate::period())
+    }
+
     /// Create a new rate limiter of `capacity` with an `initial` number of
     /// token and the `period` between refills
     pub fn new(cap: u64, initial: u64, period: Duration) -> Self {
@@ -144,9 +170,13 @@ impl RateLimit {
     /// * Failure to consume (e.g. out of tokens) will return a Duration of when
     ///   the bucket will be refilled
     pub fn consume(&mut self, tokens: u64) -> Result<u64, Duration> {
+        self.consume_at(tokens, Instant::now())
+    }
+
+    // like `consume`, but using the provided time rather than the system's
+    pub(crate) fn consume_at(&mut self, tokens: u64, now: Instant) -> Result<u64, Duration> {
         let Self { bucket, .. } = self;
 
-        let now = Instant::now();
         if let Some(n) = bucket.refill(now) {
             bucket.tokens = std::cmp::min(bucket.tokens + n, self.cap);
         }
@@ -0,0 +1,39 @@
+/// A reusable buffer for [AsyncRunner::next_message_in](crate::AsyncRunner::next_message_in)
+///
+/// Messages read with this borrow their raw string from the arena instead of
+/// allocating their own copy. Once the arena has grown to fit the longest line
+/// it'll be reused for every message after that.
+///
+/// The message is only valid until the arena is used for the next one.
+#[derive(Default, Debug, Clone)]
+pub struct MessageArena {
+    pub(crate) buf: String,
+}
+
+impl MessageArena {
+    /// Create a new, empty arena
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new arena with room for a line of `capacity` bytes
+    ///
+    /// Twitch messages are at most `512` bytes, plus `8192` bytes of tags.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: String::with_capacity(capacity),
+        }
+    }
+
+    /// How many bytes the arena can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Copy the line into the arena, replacing the previous one
+    pub(crate) fn store(&mut self, raw: &str) -> &str {
+        self.buf.clear();
+        self.buf.push_str(raw);
+        &self.buf
+    }
+}
@@ -1,34 +1,56 @@
 cfg_async! {
 use crate::{
-    channel::Receiver,
+    channel::{Receiver, Sender},
     commands,
     connector::Connector,
     encoder::AsyncEncoder,
-    messages::{Capability, Commands, MessageId},
-    rate_limit::{RateClass, RateLimit},
-    twitch::UserConfig,
+    messages::{Commands, MessageId},
+    rate_limit::{JoinRate, RateClass, RateLimit},
+    twitch::{EmoteSet, UserConfig},
     util::{Notify, NotifyHandle},
     writer::{AsyncWriter, MpscWriter},
-    AsyncDecoder, DecodeError, Encodable, FromIrcMessage, IrcMessage,
+    AsyncDecoder, BoxedFuture, DecodeError, Encodable, FromIrcMessage, IrcMessage,
 };
 
 use super::{
     channel::Channels,
-    timeout::{TimeoutState, RATE_LIMIT_WINDOW, TIMEOUT, WINDOW},
-    Capabilities, Channel, Error, Identity, Status, StepResult,
+    rate_limit::JoinQueue,
+    stats::{Counters, Counting, Stats},
+    trace::{trace_lines, Direction, TraceHook, Traced},
+    timeout::{
+        TimeoutState, COMMAND_TIMEOUT, HANDSHAKE_TIMEOUT, RATE_LIMIT_WINDOW, RECONNECT_ATTEMPTS,
+        RECONNECT_DELAY, TIMEOUT, WINDOW,
+    },
+    Capabilities, Channel, Clock, ConnectionState, Error, Identity, MessageArena, RoomSettings,
+    RoomStateChange, RunnerStats, Status, StepResult, SystemClock,
 };
 
 use futures_lite::{AsyncRead, AsyncWrite, AsyncWriteExt, Stream};
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
+// a new connection that has finished the handshake
+struct Connection {
+    identity: Identity,
+    username: String,
+    emote_sets: Vec<EmoteSet>,
+    decoder: AsyncDecoder<Box<dyn AsyncRead + Send + Sync + Unpin>>,
+    encoder: AsyncEncoder<Box<dyn AsyncWrite + Send + Sync + Unpin>>,
+    missed_messages: VecDeque<Commands<'static>>,
+}
+
+type Reconnector = Box<dyn Fn() -> BoxedFuture<Result<Connection, Error>> + Send + Sync>;
+
 /// An asynchronous runner
 pub struct AsyncRunner {
     /// You identity that Twitch gives when you connected
     pub identity: Identity,
+    username: String,
+    emote_sets: Vec<EmoteSet>,
 
     channels: Channels,
 
@@ -40,6 +62,7 @@ pub struct AsyncRunner {
     notify_handle: NotifyHandle,
 
     timeout_state: TimeoutState,
+    clock: std::sync::Arc<dyn Clock>,
 
     decoder: AsyncDecoder<Box<dyn AsyncRead + Send + Sync + Unpin>>,
     encoder: AsyncEncoder<Box<dyn AsyncWrite + Send + Sync + Unpin>>,
@@ -47,7 +70,32 @@ pub struct AsyncRunner {
     writer: AsyncWriter<MpscWriter>,
     global_rate_limit: RateLimit,
 
+    join_queue: JoinQueue,
+    join_retry: Option<Duration>,
+
     missed_messages: VecDeque<Commands<'static>>,
+
+    room_states: HashMap<String, RoomSettings>,
+    room_state_txs: Vec<Sender<RoomStateChange>>,
+
+    // only set by AsyncRunner::connect_reconnecting
+    reconnect: Option<Reconnector>,
+
+    stats: Stats,
+    command_timeout: Duration,
+
+    // this is dropped when we've been disconnected
+    disconnect_tx: Option<Sender<()>>,
+    disconnect_rx: Receiver<()>,
+
+    // set once AsyncRunner::quit has finished
+    has_quit: bool,
+
+    // set by AsyncRunner::pause, nothing is read while this is set
+    paused: bool,
+
+    state: ConnectionState,
+    state_txs: Vec<Sender<ConnectionState>>,
 }
 
 impl std::fmt::Debug for AsyncRunner {
@@ -60,44 +108,130 @@ impl AsyncRunner {
     /// Connect with the provided connector and the provided UserConfig
     ///
     /// This returns the Runner with your identity set.
+    ///
+    /// # Errors
+    /// * [Error::ConnectionFailed] if the connector couldn't connect
+    /// * [Error::TlsHandshakeFailed] if it connected, but the TLS handshake failed
+    /// * [Error::LoginFailed] or [Error::InvalidToken] if Twitch rejected the login
+    /// * [Error::HandshakeTimeout] if Twitch didn't finish the handshake in time
     pub async fn connect<C>(connector: C, user_config: &UserConfig) -> Result<Self, Error>
     where
         C: Connector,
         for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
     {
-        log::debug!("connecting");
-        let mut stream = { connector }.connect().await?;
-        log::debug!("connection established");
+        Self::connect_inner(connector, user_config, None, HANDSHAKE_TIMEOUT).await
+    }
 
-        log::debug!("registering");
-        let mut buf = vec![];
-        commands::register(user_config).encode(&mut buf)?;
-        stream.write_all(&buf).await?;
-        log::debug!("registered");
+    /// Connect with the provided connector and the provided UserConfig, giving up on the
+    /// handshake after `timeout`
+    ///
+    /// If Twitch doesn't finish the handshake in time, [Error::HandshakeTimeout] is returned.
+    /// [AsyncRunner::connect] uses a timeout of 30 seconds.
+    ///
+    /// This returns the Runner with your identity set.
+    pub async fn connect_with_timeout<C>(
+        connector: C,
+        user_config: &UserConfig,
+        timeout: Duration,
+    ) -> Result<Self, Error>
+    where
+        C: Connector,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        Self::connect_inner(connector, user_config, None, timeout).await
+    }
 
-        let read = async_dup::Arc::new(stream);
-        let write = read.clone();
+    /// Connect with the provided connector and the provided UserConfig, calling `trace` for
+    /// every raw line
+    ///
+    /// The line is provided without its trailing `\r\n`. This includes the
+    /// lines sent and received during the initial handshake, which is useful
+    /// for debugging protocol issues. The token on the `PASS` line is redacted.
+    ///
+    /// This returns the Runner with your identity set.
+    pub async fn connect_with_trace<C, F>(
+        connector: C,
+        user_config: &UserConfig,
+        trace: F,
+    ) -> Result<Self, Error>
+    where
+        C: Connector,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+        F: Fn(Direction, &str) + Send + Sync + 'static,
+    {
+        let trace: TraceHook = std::sync::Arc::new(trace);
+        Self::connect_inner(connector, user_config, Some(trace), HANDSHAKE_TIMEOUT).await
+    }
 
-        let read: Box<dyn AsyncRead + Send + Sync + Unpin> = Box::new(read);
-        let write: Box<dyn AsyncWrite + Send + Sync + Unpin> = Box::new(write);
+    /// Connect with the provided connector and the provided UserConfig, reconnecting when
+    /// Twitch asks you to
+    ///
+    /// Rather than returning [Error::ShouldReconnect], the runner keeps the
+    /// connector and connects again with it and the same [UserConfig]. It
+    /// rejoins the channels you were on, and [AsyncRunner::next_message] keeps
+    /// producing messages from the new connection. Connecting is tried up to 3
+    /// times, waiting 1 second and then 2 seconds between attempts. If they
+    /// all fail, the last error is returned.
+    ///
+    /// Use [AsyncRunner::state_changes] to see when it reconnects.
+    ///
+    /// This returns the Runner with your identity set.
+    pub async fn connect_reconnecting<C>(
+        connector: C,
+        user_config: &UserConfig,
+    ) -> Result<Self, Error>
+    where
+        C: Connector + 'static,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        let mut this =
+            Self::connect_inner(connector.clone(), user_config, None, HANDSHAKE_TIMEOUT).await?;
+
+        let user_config = user_config.clone();
+        let counters = this.stats.counters.clone();
+        this.reconnect = Some(Box::new(move || -> BoxedFuture<Result<Connection, Error>> {
+            let connector = connector.clone();
+            let user_config = user_config.clone();
+            let counters = counters.clone();
+            Box::pin(async move {
+                Self::open(connector, &user_config, None, HANDSHAKE_TIMEOUT, counters).await
+            })
+        }));
+        Ok(this)
+    }
 
-        let mut decoder = AsyncDecoder::new(read);
-        let mut encoder = AsyncEncoder::new(write);
+    async fn connect_inner<C>(
+        connector: C,
+        user_config: &UserConfig,
+        trace: Option<TraceHook>,
+        handshake_timeout: Duration,
+    ) -> Result<Self, Error>
+    where
+        C: Connector,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        let stats = Stats::default();
 
-        log::debug!("waiting for the connection to be ready");
-        let mut missed_messages = VecDeque::new();
-        let identity = Self::wait_for_ready(
-            &mut decoder,
-            &mut encoder,
+        let Connection {
+            identity,
+            username,
+            emote_sets,
+            decoder,
+            encoder,
+            missed_messages,
+        } = Self::open(
+            connector,
             user_config,
-            &mut missed_messages,
+            trace,
+            handshake_timeout,
+            stats.counters.clone(),
         )
         .await?;
-        log::debug!("connection is ready: {:?}", identity);
 
         let (writer_tx, writer_rx) = crate::channel::unbounded();
         let (notify, notify_handle) = Notify::new();
         let (activity_tx, activity_rx) = crate::channel::bounded(32);
+        let (disconnect_tx, disconnect_rx) = crate::channel::bounded(1);
 
         let writer = AsyncWriter::new(MpscWriter::new(writer_tx), activity_tx);
 
@@ -105,9 +239,12 @@ impl AsyncRunner {
         let channels = Channels::default();
 
         let global_rate_limit = RateLimit::from_class(RateClass::Regular);
+        let join_queue = JoinQueue::new(JoinRate::default());
 
         Ok(Self {
             identity,
+            username,
+            emote_sets,
             channels,
 
             activity_rx,
@@ -117,6 +254,7 @@ impl AsyncRunner {
             notify_handle,
 
             timeout_state,
+            clock: std::sync::Arc::new(SystemClock),
 
             decoder,
             encoder,
@@ -124,15 +262,254 @@ impl AsyncRunner {
             writer,
             global_rate_limit,
 
+            join_queue,
+            join_retry: None,
+
+            missed_messages,
+
+            room_states: HashMap::new(),
+            room_state_txs: Vec::new(),
+
+            reconnect: None,
+
+            stats,
+            command_timeout: COMMAND_TIMEOUT,
+
+            disconnect_tx: Some(disconnect_tx),
+            disconnect_rx,
+
+            has_quit: false,
+
+            paused: false,
+
+            state: ConnectionState::Connected,
+            state_txs: Vec::new(),
+        })
+    }
+
+    // connect and finish the handshake, this is used for connecting and reconnecting
+    async fn open<C>(
+        mut connector: C,
+        user_config: &UserConfig,
+        trace: Option<TraceHook>,
+        handshake_timeout: Duration,
+        counters: std::sync::Arc<Counters>,
+    ) -> Result<Connection, Error>
+    where
+        C: Connector,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        use crate::util::{Either::*, FutExt as _};
+
+        log::debug!("connecting");
+        let mut stream = connector.connect().await.map_err(|err| {
+            if crate::connector::is_tls_handshake_error(&err) {
+                Error::TlsHandshakeFailed(err)
+            } else {
+                Error::ConnectionFailed(err)
+            }
+        })?;
+        log::debug!("connection established");
+
+        log::debug!("registering");
+        let mut buf = vec![];
+        commands::register(user_config).encode(&mut buf)?;
+        stream.write_all(&buf).await?;
+        counters.written(&buf);
+        if let Some(trace) = &trace {
+            trace_lines(&mut vec![], &buf, |line| trace(Direction::Outgoing, line));
+        }
+        log::debug!("registered");
+
+        let read = async_dup::Arc::new(stream);
+        let write = read.clone();
+
+        let read = Counting::new(read, counters.clone());
+        let write = Counting::new(write, counters);
+
+        let (read, write): (
+            Box<dyn AsyncRead + Send + Sync + Unpin>,
+            Box<dyn AsyncWrite + Send + Sync + Unpin>,
+        ) = match trace {
+            Some(trace) => (
+                Box::new(Traced::incoming(read, trace.clone())),
+                Box::new(Traced::outgoing(write, trace)),
+            ),
+            None => (Box::new(read), Box::new(write)),
+        };
+
+        let mut decoder = AsyncDecoder::new(read);
+        let mut encoder = AsyncEncoder::new(write);
+
+        log::debug!("waiting for the connection to be ready");
+        let mut missed_messages = VecDeque::new();
+        let identity = Self::wait_for_ready(
+            &mut decoder,
+            &mut encoder,
+            user_config,
+            &mut missed_messages,
+        )
+        .first(futures_timer::Delay::new(handshake_timeout))
+        .await;
+
+        let identity = match identity {
+            Left(identity) => identity?,
+            Right(_timeout) => {
+                log::warn!("the handshake didn't finish within {:?}", handshake_timeout);
+                return Err(Error::HandshakeTimeout);
+            }
+        };
+        log::debug!("connection is ready: {:?}", identity);
+
+        // an anonymous identity doesn't know which justinfan name we used
+        let username = match identity {
+            Identity::Anonymous { .. } => user_config.name.clone(),
+            ref identity => identity.username().to_string(),
+        };
+
+        // GLOBALUSERSTATE is only sent during the handshake
+        let emote_sets = missed_messages
+            .iter()
+            .find_map(|msg| match msg {
+                Commands::GlobalUserState(msg) => {
+                    msg.tags().get("emote-sets").map(EmoteSet::parse_list)
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        Ok(Connection {
+            identity,
+            username,
+            emote_sets,
+            decoder,
+            encoder,
             missed_messages,
         })
     }
 
+    /// Get the username you're connected as
+    ///
+    /// Unlike [Identity::username], this is the actual `justinfan` name for an
+    /// anonymous login.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Get the emote sets you have access to
+    ///
+    /// These are from the `GLOBALUSERSTATE` sent when you connected, and are
+    /// updated by each `USERSTATE`. This is empty without the `Tags` and
+    /// `Commands` capabilities. Use [EmoteSetDiff::between](crate::twitch::EmoteSetDiff::between)
+    /// to see which ones changed.
+    pub fn emote_sets(&self) -> &[EmoteSet] {
+        &self.emote_sets
+    }
+
+    /// Check whether the connection is still alive
+    ///
+    /// This becomes `false` once the runner has read an EOF, quit or encountered an error from
+    /// the connection
+    pub fn is_connected(&self) -> bool {
+        self.disconnect_tx.is_some()
+    }
+
+    /// Get a future that resolves when the connection ends, for any reason.
+    ///
+    /// This is independent of the runner, so you can wait on it elsewhere (e.g. to update a UI)
+    ///
+    /// This will also resolve if the runner is dropped.
+    pub fn on_disconnect(&self) -> impl std::future::Future<Output = ()> + Send + Sync + 'static {
+        let rx = self.disconnect_rx.clone();
+        async move { while rx.recv().await.is_some() {} }
+    }
+
+    fn disconnected(&mut self) {
+        if self.disconnect_tx.take().is_some() {
+            log::debug!("connection has ended");
+        }
+        self.set_state(ConnectionState::Disconnected);
+    }
+
+    /// Get the current state of the connection
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state
+    }
+
+    /// Get a [Stream] of the connection's state as it changes
+    ///
+    /// This starts with the current state, and then produces each change. The
+    /// state is updated as you drive the runner (e.g. with
+    /// [AsyncRunner::next_message]), so this is useful for showing the status
+    /// of the connection elsewhere (e.g. in a UI).
+    ///
+    /// The stream ends once the runner is dropped.
+    ///
+    /// [Stream]: futures_lite::Stream
+    pub fn state_changes(
+        &mut self,
+    ) -> impl Stream<Item = ConnectionState> + Send + Sync + 'static {
+        let (tx, rx) = crate::channel::unbounded();
+        let _ = tx.try_send(self.state);
+        self.state_txs.push(tx);
+        rx
+    }
+
+    fn set_state(&mut self, state: ConnectionState) {
+        if self.state == state {
+            return;
+        }
+        log::trace!("connection state: {:?} -> {:?}", self.state, state);
+        self.state = state;
+        // drop the streams that have gone away
+        self.state_txs.retain(|tx| tx.try_send(state).is_ok());
+    }
+
+    /// Get the capabilities Twitch acknowledged (or refused) when you connected
+    ///
+    /// If you're relying on `JOIN`/`PART` for other users, check
+    /// [Capabilities::membership_enabled].
+    pub fn capabilities(&self) -> &Capabilities {
+        self.identity.capabilities()
+    }
+
+    /// Get a snapshot of the counters this runner maintains
+    ///
+    /// This includes the messages received during the initial handshake.
+    pub fn stats(&self) -> RunnerStats {
+        self.stats.snapshot()
+    }
+
     /// Check whether you're on this channel
     pub fn is_on_channel(&self, channel: &str) -> bool {
         self.channels.is_on(channel)
     }
 
+    /// Get the chat settings of a channel you're on
+    ///
+    /// These are tracked from the `ROOMSTATE` messages that [AsyncRunner::next_message] returns.
+    pub fn room_state(&self, channel: &str) -> Option<&RoomSettings> {
+        self.room_states.get(channel)
+    }
+
+    /// Get a [Stream] of the settings that change on the channels you're on
+    ///
+    /// Twitch sends a `ROOMSTATE` with all of the settings when you join a
+    /// channel, and then one with just the changed setting whenever it
+    /// changes. The change is produced as [AsyncRunner::next_message] returns
+    /// that `ROOMSTATE`, so you'll have to keep driving the runner.
+    ///
+    /// The stream ends once the runner is dropped.
+    ///
+    /// [Stream]: futures_lite::Stream
+    pub fn room_state_changes(
+        &mut self,
+    ) -> impl Stream<Item = RoomStateChange> + Send + Sync + 'static {
+        let (tx, rx) = crate::channel::unbounded();
+        self.room_state_txs.push(tx);
+        rx
+    }
+
     /// Get a specific channel.
     ///
     /// This is useful for changing the rate limit/state manually.
@@ -140,6 +517,14 @@ impl AsyncRunner {
         self.channels.get_mut(channel)
     }
 
+    /// Set the [JoinRate] used to pace `JOIN`s
+    ///
+    /// `JOIN`s sent with [AsyncRunner::join] or the [AsyncWriter] are queued
+    /// and sent according to this. This defaults to [JoinRate::Regular].
+    pub fn set_join_rate(&mut self, join_rate: JoinRate) {
+        self.join_queue.set_join_rate(join_rate)
+    }
+
     /// Get a clonable writer you can use
     pub fn writer(&self) -> AsyncWriter<MpscWriter> {
         self.writer.clone()
@@ -148,11 +533,48 @@ impl AsyncRunner {
     /// Get a handle that you can trigger a normal 'quit'.
     ///
     /// You can also do `AsyncWriter::quit`.
+    ///
+    /// The handle is `Send + Sync + 'static` and [NotifyHandle::notify_sync] doesn't
+    /// block, so it can be used from another thread or a signal handler. After
+    /// it's notified, [AsyncRunner::next_message] will return [Status::Quit].
+    ///
+    /// Once the runner has quit, [AsyncRunner::on_disconnect] will resolve.
     pub fn quit_handle(&self) -> NotifyHandle {
         self.notify_handle.clone()
     }
 
+    /// Quit the connection, waiting for Twitch to close it
+    ///
+    /// This sends any queued messages, then `QUIT`, and then waits (for up to
+    /// 10 seconds) for the server to close the connection. Any messages
+    /// received while quitting are discarded.
+    ///
+    /// Afterwards, [AsyncRunner::next_message] will return [Status::Quit].
+    /// Calling this again, or after the connection has ended, does nothing.
+    pub async fn quit(&mut self) -> Result<(), Error> {
+        use crate::util::FutExt as _;
+
+        if self.has_quit || !self.is_connected() {
+            return Ok(());
+        }
+
+        log::debug!("quitting");
+        self.has_quit = true;
+        self.missed_messages.clear();
+        self.send_quit().await?;
+        self.disconnected();
+
+        let decoder = &mut self.decoder;
+        let closed = async move { while decoder.read_message().await.is_ok() {} };
+        let _ = closed.first(self.clock.delay(TIMEOUT)).await;
+
+        log::debug!("quit");
+        Ok(())
+    }
+
     /// Join `channel` and wait for it to complete
+    ///
+    /// This is paced according to the [JoinRate]. See [AsyncRunner::set_join_rate]
     pub async fn join(&mut self, channel: &str) -> Result<(), Error> {
         if self.is_on_channel(channel) {
             return Err(Error::AlreadyOnChannel {
@@ -161,9 +583,12 @@ impl AsyncRunner {
         }
 
         log::debug!("joining '{}'", channel);
-        self.encoder.encode(commands::join(channel)).await?;
+        let mut buf = vec![];
+        commands::join(channel).encode(&mut buf)?;
+        self.join_queue.enqueue(&buf);
+        self.drain_join_queue().await?;
 
-        let channel = crate::commands::Channel::new(channel).to_string();
+        let channel = commands::Channel::new(channel).to_string();
         log::debug!("waiting for a response");
 
         let mut queue = VecDeque::new();
@@ -172,7 +597,7 @@ impl AsyncRunner {
             .wait_for(&mut queue, |msg, this| match msg {
                 // check to see if it was us that joined the channel
                 Commands::Join(msg) => {
-                    Ok(msg.channel() == channel && msg.name() == this.identity.username())
+                    Ok(msg.channel() == channel && msg.name() == this.username)
                 }
 
                 // check to see if we were banned
@@ -211,7 +636,7 @@ impl AsyncRunner {
         log::debug!("leaving '{}'", channel);
         self.encoder.encode(commands::part(channel)).await?;
 
-        let channel = crate::commands::Channel::new(channel).to_string();
+        let channel = commands::Channel::new(channel).to_string();
         log::debug!("waiting for a response");
 
         let mut queue = VecDeque::new();
@@ -220,7 +645,7 @@ impl AsyncRunner {
             .wait_for(&mut queue, |msg, this| match msg {
                 // check to see if it was us that left the channel
                 Commands::Part(msg) => {
-                    Ok(msg.channel() == channel && msg.name() == this.identity.username())
+                    Ok(msg.channel() == channel && msg.name() == this.username)
                 }
                 _ => Ok(false),
             })
@@ -239,53 +664,361 @@ impl AsyncRunner {
         Ok(())
     }
 
+    /// Send a command to `channel` and wait for Twitch to respond to it
+    ///
+    /// This is for commands that Twitch responds to with a `NOTICE`, such as moderation commands.
+    ///
+    /// The response is correlated on a best-effort basis: the next `NOTICE` on
+    /// the channel with a `msg-id` is considered the response. If it's a
+    /// failure (see [Notice::as_command_error](crate::messages::Notice::as_command_error))
+    /// then [Error::CommandRejected] is returned.
+    ///
+    /// If no response arrives in time, [Error::NoCommandResponse] is returned.
+    /// See [AsyncRunner::set_command_timeout]
+    pub async fn send_command<E>(&mut self, channel: &str, cmd: E) -> Result<(), Error>
+    where
+        E: Encodable + Send + Sync,
+    {
+        use crate::util::{Either::*, FutExt as _};
+
+        self.encoder.encode(cmd).await?;
+
+        let channel = commands::Channel::new(channel).to_string();
+        log::debug!("waiting for a response on '{}'", channel);
+
+        let mut queue = VecDeque::new();
+        let timeout = self.clock.delay(self.command_timeout);
+
+        let status = self
+            .wait_for(&mut queue, |msg, _this| match msg {
+                Commands::Notice(msg) if msg.channel() == channel && msg.msg_id().is_some() => {
+                    match msg.as_command_error() {
+                        Some(error) => Err(Error::CommandRejected {
+                            channel: channel.clone(),
+                            error,
+                        }),
+                        None => Ok(true),
+                    }
+                }
+                _ => Ok(false),
+            })
+            .first(timeout)
+            .await;
+
+        let status = match status {
+            Left(status) => status,
+            Right(_timeout) => Err(Error::NoCommandResponse {
+                channel: channel.clone(),
+            }),
+        };
+
+        self.missed_messages.extend(queue);
+
+        match status? {
+            Some(Status::Quit) | Some(Status::Eof) => Err(Error::UnexpectedEof),
+            _ => Ok(()),
+        }
+    }
+
+    /// Ban `username` from `channel` and wait for Twitch to respond
+    ///
+    /// See [AsyncRunner::send_command]
+    pub async fn ban(
+        &mut self,
+        channel: &str,
+        username: &str,
+        reason: impl Into<Option<&str>>,
+    ) -> Result<(), Error> {
+        let reason = reason.into();
+        self.send_command(channel, commands::ban(channel, username, reason))
+            .await
+    }
+
+    /// Set how long [AsyncRunner::send_command] waits for a response
+    ///
+    /// This defaults to 10 seconds.
+    pub fn set_command_timeout(&mut self, timeout: Duration) {
+        self.command_timeout = timeout;
+    }
+
+    /// Use a different [Clock] for the idle detection and timeouts
+    ///
+    /// This defaults to the [SystemClock]. The handshake timeout used while
+    /// connecting always uses the system's time.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = std::sync::Arc::new(clock);
+    }
+
+    /// Stop reading from the connection until [AsyncRunner::resume] is called
+    ///
+    /// Use this when your message handler is falling behind. Nothing is read
+    /// while paused, so the connection's buffers fill up and TCP slows Twitch
+    /// down instead of messages piling up in memory. Any partially read line
+    /// is kept and finished once you resume.
+    ///
+    /// While paused, [AsyncRunner::next_message] won't produce any messages
+    /// (not even ones that were already read) but it'll keep sending what you
+    /// write and any queued joins, and returns if you [quit](AsyncRunner::quit_handle).
+    /// It's safe to drop its future once you're ready to resume.
+    ///
+    /// # NOTE
+    /// `PING`s from Twitch aren't read, so they won't be answered until you resume.
+    /// If you stay paused for more than a few minutes Twitch will disconnect you.
+    pub fn pause(&mut self) {
+        log::debug!("pausing reading");
+        self.paused = true;
+    }
+
+    /// Start reading from the connection again, after [AsyncRunner::pause]
+    pub fn resume(&mut self) {
+        if std::mem::replace(&mut self.paused, false) {
+            log::debug!("resuming reading");
+            // we weren't reading, so don't count that as being idle
+            self.timeout_state = TimeoutState::activity(self.clock.now());
+        }
+    }
+
+    /// Whether reading is [paused](AsyncRunner::pause)
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
     /// Get the next message. You'll usually want to call this in a loop
     pub async fn next_message(&mut self) -> Result<Status<'static>, Error> {
+        if self.has_quit {
+            return Ok(Status::Quit);
+        }
+
+        let status = self.next_status(&mut None).await;
+        let ended = match &status {
+            Ok(status) => status.is_finished(),
+            Err(err) => err.is_connection_error(),
+        };
+        if ended {
+            self.disconnected();
+        }
+        status
+    }
+
+    /// Get the next message, borrowing it from the provided [MessageArena]
+    ///
+    /// This is like [AsyncRunner::next_message] but the message's raw string
+    /// is copied into `arena` rather than into a new allocation. Reusing the
+    /// same arena in your loop avoids that allocation for every message.
+    ///
+    /// Parsing the tags still allocates one small index per tagged message.
+    ///
+    /// Messages queued up while [joining](AsyncRunner::join) or waiting on a
+    /// command were already owned, so those are returned as-is.
+    ///
+    /// ```no_run
+    /// # use twitchchat::{AsyncRunner, Status, runner::MessageArena};
+    /// # async fn demo(mut runner: AsyncRunner) -> Result<(), twitchchat::RunnerError> {
+    /// let mut arena = MessageArena::new();
+    /// while let Status::Message(msg) = runner.next_message_in(&mut arena).await? {
+    ///     println!("{}", msg.raw().escape_debug());
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn next_message_in<'a>(
+        &mut self,
+        arena: &'a mut MessageArena,
+    ) -> Result<Status<'a>, Error> {
+        if self.has_quit {
+            return Ok(Status::Quit);
+        }
+
+        let status = self.next_status(&mut Some(arena)).await;
+        let ended = match &status {
+            Ok(status) => status.is_finished(),
+            Err(err) => err.is_connection_error(),
+        };
+        if ended {
+            self.disconnected();
+        }
+        status
+    }
+
+    /// Turn this runner into a [Stream] of messages
+    ///
+    /// Each item is what [AsyncRunner::next_message] would've returned. The
+    /// final item is either [Status::Eof], [Status::Quit] or an error, after
+    /// which the stream ends.
+    ///
+    /// Unlike using the runner directly as a [Stream], this doesn't hide the errors or why it
+    /// stopped.
+    ///
+    /// [Stream]: futures_lite::Stream
+    pub fn into_stream(self) -> impl Stream<Item = Result<Status<'static>, Error>> + Send {
+        let stream = futures_lite::stream::unfold(Some(self), |runner| async move {
+            let mut runner = runner?;
+            let status = runner.next_message().await;
+            let runner = Some(runner)
+                .filter(|_| matches!(&status, Ok(status) if !status.is_finished()));
+            Some((status, runner))
+        });
+        // polling an unfold after it finished would panic
+        futures_lite::StreamExt::fuse(stream)
+    }
+
+    async fn next_status<'a>(
+        &mut self,
+        arena: &mut Option<&'a mut MessageArena>,
+    ) -> Result<Status<'a>, Error> {
         use crate::util::{Either::*, FutExt as _};
 
         loop {
-            match self.step().await? {
+            let step = match self.step_in(arena).await {
+                Err(Error::ShouldReconnect) if self.reconnect.is_some() => {
+                    self.reconnect().await?;
+                    continue;
+                }
+                step => step?,
+            };
+
+            match step {
                 StepResult::Nothing => continue,
                 StepResult::Status(Status::Quit) => {
                     if let Left(_notified) = self.notify.wait().now_or_never().await {
-                        // close everything
-                        self.writer_rx.close();
-                        self.activity_rx.close();
-
-                        // and then drain any remaining items
-                        while self.available_queued_messages() > 0 {
-                            self.drain_queued_messages().await?;
-                            futures_lite::future::yield_now().await;
-                        }
-
-                        // and finally send the quit
-                        self.encoder.encode(commands::raw("QUIT\r\n")).await?;
+                        self.send_quit().await?;
 
                         // and signal that we've quit
                         break Ok(Status::Quit);
                     }
                 }
+                StepResult::Status(Status::Message(Commands::RoomState(msg))) => {
+                    self.update_room_state(&msg);
+                    break Ok(Status::Message(Commands::RoomState(msg)));
+                }
                 StepResult::Status(status) => break Ok(status),
             }
         }
     }
 
+    async fn reconnect(&mut self) -> Result<(), Error> {
+        log::info!("twitch asked us to reconnect");
+        self.set_state(ConnectionState::Reconnecting);
+
+        let mut delay = RECONNECT_DELAY;
+        let mut attempt = 1;
+        let conn = loop {
+            // this is only called when connected with AsyncRunner::connect_reconnecting
+            let reconnect = self.reconnect.as_ref().expect("reconnector should be set");
+            match reconnect().await {
+                Ok(conn) => break conn,
+                Err(err) if attempt == RECONNECT_ATTEMPTS => return Err(err),
+                Err(err) => {
+                    log::warn!("could not reconnect ({}), trying again in {:?}", err, delay);
+                    self.clock.delay(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        };
+
+        self.identity = conn.identity;
+        self.username = conn.username;
+        self.emote_sets = conn.emote_sets;
+        self.decoder = conn.decoder;
+        self.encoder = conn.encoder;
+        self.missed_messages.extend(conn.missed_messages);
+        self.timeout_state = TimeoutState::Start;
+
+        // the channels are still tracked, so anything queued for them is kept
+        for channel in self.channels.map.keys() {
+            log::debug!("rejoining '{}'", channel);
+            let mut buf = vec![];
+            commands::join(channel).encode(&mut buf)?;
+            self.join_queue.enqueue(&buf);
+        }
+        self.drain_join_queue().await?;
+
+        self.stats.reconnected();
+        self.set_state(ConnectionState::Connected);
+        log::info!("reconnected");
+        Ok(())
+    }
+
+    // this is done as the ROOMSTATEs are returned, so any changes follow them in order
+    fn update_room_state(&mut self, msg: &crate::messages::RoomState<'_>) {
+        let settings = match self.room_states.get_mut(msg.channel()) {
+            Some(settings) => settings,
+            None => {
+                let settings = RoomSettings::from_room_state(msg);
+                self.room_states.insert(msg.channel().to_string(), settings);
+                return;
+            }
+        };
+
+        for setting in settings.update(msg) {
+            let change = RoomStateChange {
+                channel: msg.channel().to_string(),
+                setting,
+            };
+            // drop the streams that have gone away
+            self.room_state_txs
+                .retain(|tx| tx.try_send(change.clone()).is_ok());
+        }
+    }
+
+    async fn send_quit(&mut self) -> Result<(), Error> {
+        // close everything
+        self.writer_rx.close();
+        self.activity_rx.close();
+
+        // and then drain any remaining items
+        while self.available_queued_messages() > 0 {
+            self.drain_queued_messages().await?;
+            futures_lite::future::yield_now().await;
+        }
+
+        // and finally send the quit
+        self.encoder.encode(commands::raw("QUIT")).await?;
+        Ok(())
+    }
+
     /// Single step the loop. This is useful for testing.
     pub async fn step(&mut self) -> Result<StepResult<'static>, Error> {
+        self.step_in(&mut None).await
+    }
+
+    // if an arena is provided the message is borrowed from it, otherwise its owned
+    async fn step_in<'a>(
+        &mut self,
+        arena: &mut Option<&'a mut MessageArena>,
+    ) -> Result<StepResult<'a>, Error> {
         use crate::util::*;
         use crate::IntoOwned as _;
 
-        if let Some(msg) = self.missed_messages.pop_front() {
-            return Ok(StepResult::Status(Status::Message(msg)));
+        if !self.paused {
+            if let Some(msg) = self.missed_messages.pop_front() {
+                self.stats.received(msg.kind(), self.clock.now());
+                return Ok(StepResult::Status(Status::Message(msg)));
+            }
         }
 
-        let select = self
-            .decoder
-            .read_message()
+        let paused = self.paused;
+        let decoder = &mut self.decoder;
+        let read = async move {
+            if paused {
+                futures_lite::future::pending().await
+            } else {
+                decoder.read_message().await
+            }
+        };
+
+        // while waiting for a PONG, wake up when we should give up on it
+        let idle = match self.timeout_state {
+            TimeoutState::WaitingForPong(..) => self.clock.delay(TIMEOUT),
+            _ => self.clock.delay(WINDOW),
+        };
+        let join_delay = Self::join_delay(&*self.clock, self.join_retry);
+
+        let select = read
             .either(self.activity_rx.recv())
             .either(self.writer_rx.recv())
             .either(self.notify.wait())
-            .either(super::timeout::next_delay())
+            .either(idle.either(join_delay))
             .await;
 
         match select {
@@ -293,60 +1026,75 @@ impl AsyncRunner {
                 let msg = match msg {
                     Err(DecodeError::Eof) => {
                         log::info!("got an EOF, exiting main loop");
+                        self.disconnected();
                         return Ok(StepResult::Status(Status::Eof));
                     }
                     Err(err) => {
                         log::warn!("read an error: {}", err);
+                        self.disconnected();
                         return Err(err.into());
                     }
                     Ok(msg) => msg,
                 };
 
-                self.timeout_state = TimeoutState::activity();
+                self.timeout_state = TimeoutState::activity(self.clock.now());
 
-                let all = Commands::from_irc(msg) //
-                    .expect("msg identity conversion should be upheld")
-                    .into_owned();
+                let all = match arena.take() {
+                    Some(arena) => Commands::from_irc(msg.with_raw(arena.store(msg.get_raw()))),
+                    None => Commands::from_irc(msg).map(|all| all.into_owned()),
+                }
+                .expect("msg identity conversion should be upheld");
 
+                self.stats.received(all.kind(), self.clock.now());
                 self.check_messages(&all).await?;
 
                 return Ok(StepResult::Status(Status::Message(all)));
             }
 
             Left(Left(Left(Right(Some(_activity))))) => {
-                self.timeout_state = TimeoutState::activity();
+                self.timeout_state = TimeoutState::activity(self.clock.now());
             }
 
             Left(Left(Right(Some(write_data)))) => {
                 // TODO provide a 'bytes' flavored parser
-                let msg = std::str::from_utf8(&*write_data).map_err(Error::InvalidUtf8)?;
+                let msg = std::str::from_utf8(&write_data).map_err(Error::InvalidUtf8)?;
                 let res = crate::irc::parse_one(msg) //
                     .expect("encoder should produce valid IRC messages");
                 let msg = res.1;
 
-                if let crate::irc::IrcMessage::PRIVMSG = msg.get_command() {
+                if let IrcMessage::PRIVMSG = msg.get_command() {
                     if let Some(ch) = msg.nth_arg(0) {
                         if !self.channels.is_on(ch) {
                             self.channels.add(ch)
                         }
 
                         let ch = self.channels.get_mut(ch).unwrap();
-                        if ch.rated_limited_at.map(|s| s.elapsed()) > Some(RATE_LIMIT_WINDOW) {
-                            ch.reset_rate_limit();
-                        }
+                        ch.expire_rate_limit(self.clock.now(), RATE_LIMIT_WINDOW);
 
                         ch.rate_limited.enqueue(write_data)
                     }
+                } else if let IrcMessage::JOIN = msg.get_command() {
+                    self.join_queue.enqueue(&write_data)
                 }
             }
 
             Left(Right(_notified)) => return Ok(StepResult::Status(Status::Quit)),
 
-            Right(_timeout) => {
+            // we can try to send more queued JOINs
+            Right(Right(_join)) => {}
+
+            // we can't read the PONG while paused
+            Right(Left(_timeout)) if self.paused => {}
+
+            // we've already sent a PING, this is checked below
+            Right(Left(_timeout))
+                if matches!(self.timeout_state, TimeoutState::WaitingForPong(..)) => {}
+
+            Right(Left(_timeout)) => {
                 log::info!("idle connection detected, sending a ping");
                 let ts = timestamp().to_string();
                 self.encoder.encode(commands::ping(&ts)).await?;
-                self.timeout_state = TimeoutState::waiting_for_pong();
+                self.timeout_state = TimeoutState::waiting_for_pong(self.clock.now());
             }
 
             _ => {
@@ -355,18 +1103,19 @@ impl AsyncRunner {
         }
 
         match self.timeout_state {
+            _ if self.paused => {}
             TimeoutState::WaitingForPong(dt) => {
-                if dt.elapsed() > TIMEOUT {
+                if self.clock.now().saturating_duration_since(dt) > TIMEOUT {
                     log::warn!("PING timeout detected, exiting");
                     return Err(Error::TimedOut);
                 }
             }
             TimeoutState::Activity(dt) => {
-                if dt.elapsed() > WINDOW {
+                if self.clock.now().saturating_duration_since(dt) > WINDOW {
                     log::warn!("idle connectiond detected, sending a PING");
                     let ts = timestamp().to_string();
-                    self.encoder.encode(crate::commands::ping(&ts)).await?;
-                    self.timeout_state = TimeoutState::waiting_for_pong();
+                    self.encoder.encode(commands::ping(&ts)).await?;
+                    self.timeout_state = TimeoutState::waiting_for_pong(self.clock.now());
                 }
             }
             TimeoutState::Start => {}
@@ -378,7 +1127,7 @@ impl AsyncRunner {
         Ok(StepResult::Nothing)
     }
 
-    async fn check_messages(&mut self, all: &Commands<'static>) -> Result<(), Error> {
+    async fn check_messages(&mut self, all: &Commands<'_>) -> Result<(), Error> {
         use {Commands::*, TimeoutState::*};
 
         log::trace!("< {}", all.raw().escape_debug());
@@ -391,23 +1140,28 @@ impl AsyncRunner {
                     token
                 );
                 self.encoder.encode(commands::pong(token)).await?;
-                self.timeout_state = TimeoutState::activity();
+                self.timeout_state = TimeoutState::activity(self.clock.now());
             }
 
             Pong(..) if matches!(self.timeout_state, WaitingForPong {..}) => {
-                self.timeout_state = TimeoutState::activity()
+                self.timeout_state = TimeoutState::activity(self.clock.now())
             }
 
-            Join(msg) if msg.name() == self.identity.username() => {
+            Join(msg) if msg.name() == self.username => {
                 log::debug!("starting tracking channel for '{}'", msg.channel());
                 self.channels.add(msg.channel());
             }
 
-            Part(msg) if msg.name() == self.identity.username() => {
+            Part(msg) if msg.name() == self.username => {
                 log::debug!("stopping tracking of channel '{}'", msg.channel());
                 self.channels.remove(msg.channel());
+                self.room_states.remove(msg.channel());
             }
 
+            // GLOBALUSERSTATE has them on connect, and USERSTATE updates them
+            GlobalUserState(msg) => self.update_emote_sets(msg.tags()),
+            UserState(msg) => self.update_emote_sets(msg.tags()),
+
             RoomState(msg) => {
                 if let Some(dur) = msg.is_slow_mode() {
                     if let Some(ch) = self.channels.get_mut(msg.channel()) {
@@ -424,7 +1178,9 @@ impl AsyncRunner {
                     // we should disable slow mode
                     (Some(MessageId::SlowOff), Some(ch)) => ch.disable_slow_mode(),
                     // we've been rate limited on the channel
-                    (Some(MessageId::MsgRatelimit), Some(ch)) => ch.set_rate_limited(),
+                    (Some(MessageId::MsgRatelimit), Some(ch)) => {
+                        ch.set_rate_limited_at(self.clock.now())
+                    }
                     // we cannot join/send to the channel because we're banned
                     (Some(MessageId::MsgBanned), ..) => self.channels.remove(msg.channel()),
                     _ => {}
@@ -438,6 +1194,12 @@ impl AsyncRunner {
 
         Ok(())
     }
+
+    fn update_emote_sets(&mut self, tags: crate::irc::Tags<'_>) {
+        if let Some(emote_sets) = tags.get("emote-sets") {
+            self.emote_sets = EmoteSet::parse_list(emote_sets);
+        }
+    }
 }
 
 impl AsyncRunner {
@@ -471,22 +1233,39 @@ impl AsyncRunner {
             .sum()
     }
 
+    async fn drain_join_queue(&mut self) -> std::io::Result<()> {
+        let now = self.clock.now();
+        self.join_retry = self
+            .join_queue
+            .drain_until_blocked(&mut self.encoder, now)
+            .await?;
+        Ok(())
+    }
+
+    async fn join_delay(clock: &dyn Clock, retry: Option<Duration>) {
+        match retry {
+            Some(retry) => clock.delay(retry).await,
+            None => futures_lite::future::pending().await,
+        }
+    }
+
     async fn drain_queued_messages(&mut self) -> std::io::Result<()> {
+        self.drain_join_queue().await?;
+
         let enc = &mut self.encoder;
         let limit = &mut self.global_rate_limit.get_available_tokens();
 
         let start = *limit;
 
         // for each channel, try to take up to 'limit' tokens
+        let now = self.clock.now();
         for channel in self.channels.map.values_mut() {
-            if channel.rated_limited_at.map(|s| s.elapsed()) > Some(RATE_LIMIT_WINDOW) {
-                channel.reset_rate_limit();
-            }
+            channel.expire_rate_limit(now, RATE_LIMIT_WINDOW);
 
             // drain until we're out of messages, or tokens
             channel
                 .rate_limited
-                .drain_until_blocked(&channel.name, limit, enc)
+                .drain_until_blocked(&channel.name, limit, enc, now)
                 .await?;
 
             let left = std::cmp::max(start, *limit);
@@ -500,7 +1279,7 @@ impl AsyncRunner {
             }
 
             // and throttle the global one
-            match self.global_rate_limit.consume(diff) {
+            match self.global_rate_limit.consume_at(diff, now) {
                 // use the new remaining amount of tokens
                 Ok(rem) => *limit = rem,
 
@@ -546,7 +1325,7 @@ impl AsyncRunner {
         //
         // We'll check for both Tags and Commands
         //
-        let will_be_getting_global_user_state_hopefully =
+        let mut will_be_getting_global_user_state_hopefully =
             user_config.capabilities.contains(&TwitchCap::Tags) &&
             user_config.capabilities.contains(&TwitchCap::Commands);
 
@@ -585,10 +1364,12 @@ impl AsyncRunner {
                     }
                 }
 
-                Cap(msg) => match msg.capability() {
-                    Capability::Acknowledged(name) => {
-                        use crate::twitch::Capability as Cap;
+                Cap(msg) if msg.is_acknowledged() => {
+                    use crate::twitch::Capability as Cap;
 
+                    // all of the capabilities are requested at once,
+                    // so they'll be acknowledged at once
+                    for name in msg.capabilities() {
                         let cap = match Cap::maybe_from_str(name) {
                             Some(cap) => cap,
                             // Twitch sent us an unknown capability
@@ -607,15 +1388,66 @@ impl AsyncRunner {
                         looking_for.remove(&cap);
                     }
 
-                    Capability::NotAcknowledged(name) => {
-                        return Err(Error::InvalidCap {
-                            cap: name.to_string(),
-                        })
+                    // if a batched request was refused,
+                    // the individual ones may come after we've gotten our name
+                    if our_name.is_some() && !will_be_getting_global_user_state_hopefully && looking_for.is_empty() {
+                        break Identity::Basic {
+                            name: our_name.take().unwrap(),
+                            caps,
+                        };
                     }
-                },
+                }
+
+                Cap(msg) => {
+                    use crate::twitch::Capability as Cap;
+
+                    let mut refused = Vec::new();
+                    for name in msg.capabilities() {
+                        match Cap::maybe_from_str(name) {
+                            Some(cap) => refused.push(cap),
+                            None => {
+                                return Err(Error::InvalidCap {
+                                    cap: name.to_string(),
+                                })
+                            }
+                        }
+                    }
+
+                    // Twitch refuses the entire request if any of them are bad,
+                    // so request them one at a time to find out which one it was
+                    if refused.len() > 1 {
+                        log::debug!(
+                            "batched capability request was refused, requesting them individually"
+                        );
+                        for cap in refused {
+                            encoder.encode(commands::raw(cap.encode_as_str())).await?;
+                        }
+                        continue;
+                    }
+
+                    for cap in refused {
+                        log::warn!("capability '{}' was refused", cap.as_str());
+
+                        // we won't be getting this without both of these
+                        if matches!(cap, Cap::Tags | Cap::Commands) {
+                            will_be_getting_global_user_state_hopefully = false;
+                        }
+
+                        caps.refused.push(cap);
+                        looking_for.remove(&cap);
+                    }
+
+                    if our_name.is_some() && !will_be_getting_global_user_state_hopefully && looking_for.is_empty() {
+                        break Identity::Basic {
+                            name: our_name.take().unwrap(),
+                            caps,
+                        };
+                    }
+                }
 
                 // NOTE: This will only be sent when there's both Commands and atleast one other CAP requested
                 GlobalUserState(msg) => {
+
                     // TODO: this is so shitty.
                     let id = match msg.user_id {
                         Some(id) => id.parse().unwrap(),
@@ -639,6 +1471,12 @@ impl AsyncRunner {
 
                 }
 
+                Notice(msg) if msg.channel() == "*" => {
+                    if let Some(err) = Error::from_login_notice(&msg) {
+                        return Err(err);
+                    }
+                }
+
                 // Reply to any PINGs while waiting. Although Twitch doesn't
                 // currently send a PING for spoof detection on initial
                 // handshake, one day they may. Most IRC servers do this
@@ -678,4 +1516,1098 @@ impl Stream for AsyncRunner {
         }
     }
 }
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::test::{TestConn, TestConnector};
+
+    const HANDSHAKE: &str = ":tmi.twitch.tv 001 justinfan5678 :Welcome, GLHF!\r\n\
+                             :tmi.twitch.tv 376 justinfan5678 :>\r\n";
+
+    // a connector with the handshake for an anonymous login queued up, and its config
+    fn anonymous_handshake() -> (TestConnector, UserConfig) {
+        let connector = TestConnector::default();
+        futures_lite::future::block_on(connector.conn.write_data(HANDSHAKE));
+
+        let user_config = UserConfig {
+            name: "justinfan5678".to_string(),
+            token: "justinfan5678".to_string(),
+            capabilities: vec![],
+        };
+        (connector, user_config)
+    }
+
+    // a connector where each connect gets the next connection
+    #[derive(Clone)]
+    struct Sessions(std::sync::Arc<std::sync::Mutex<VecDeque<TestConn>>>);
+
+    impl Connector for Sessions {
+        type Output = TestConn;
+
+        fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
+            let conn = self.0.lock().unwrap().pop_front();
+            Box::pin(async move {
+                conn.ok_or_else(|| std::io::ErrorKind::ConnectionRefused.into())
+            })
+        }
+    }
+
+    impl Sessions {
+        fn new(conns: Vec<TestConn>) -> Self {
+            Self(std::sync::Arc::new(std::sync::Mutex::new(conns.into())))
+        }
+    }
+
+    // a connection that reads what was queued and then goes quiet, capturing what was written
+    #[derive(Clone, Default)]
+    struct Quiet {
+        read: std::sync::Arc<std::sync::Mutex<VecDeque<u8>>>,
+        written: std::sync::Arc<std::sync::Mutex<Vec<u8>>>,
+    }
+
+    impl Quiet {
+        fn take_written(&self) -> String {
+            String::from_utf8(std::mem::take(&mut *self.written.lock().unwrap())).unwrap()
+        }
+    }
+
+    impl AsyncRead for &Quiet {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let mut read = self.read.lock().unwrap();
+            if read.is_empty() {
+                return Poll::Pending;
+            }
+            let n = std::io::Read::read(&mut *read, buf);
+            Poll::Ready(n)
+        }
+    }
+
+    impl AsyncWrite for &Quiet {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.written.lock().unwrap().extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncRead for Quiet {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut &*self).poll_read(cx, buf)
+        }
+    }
+
+    impl AsyncWrite for Quiet {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut &*self).poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut &*self).poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut &*self).poll_close(cx)
+        }
+    }
+
+    impl Connector for Quiet {
+        type Output = Self;
+
+        fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
+            let this = self.clone();
+            Box::pin(async move { Ok(this) })
+        }
+    }
+
+    #[test]
+    fn connect_anonymous() {
+        futures_lite::future::block_on(async move {
+            let (connector, user_config) = anonymous_handshake();
+
+            let runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            assert!(runner.identity.is_anonymous());
+            assert!(matches!(runner.identity, Identity::Anonymous { .. }));
+            assert_eq!(runner.username(), "justinfan5678");
+        });
+    }
+
+    #[test]
+    fn disconnect() {
+        futures_lite::future::block_on(async move {
+            let (connector, user_config) = anonymous_handshake();
+
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+            assert!(runner.is_connected());
+
+            let on_disconnect = runner.on_disconnect();
+
+            // the missed messages from the handshake
+            for _ in 0..2 {
+                assert!(matches!(
+                    runner.next_message().await.unwrap(),
+                    Status::Message(..)
+                ));
+            }
+            assert!(runner.is_connected());
+
+            assert!(matches!(runner.next_message().await.unwrap(), Status::Eof));
+            assert!(!runner.is_connected());
+
+            on_disconnect.await;
+        });
+    }
+
+    #[test]
+    fn into_stream() {
+        use futures_lite::StreamExt as _;
+
+        futures_lite::future::block_on(async move {
+            let (connector, user_config) = anonymous_handshake();
+            connector
+                .conn
+                .write_data(
+                    ":test!test@test PRIVMSG #museun :hello\r\n\
+                     PING :1234567890\r\n",
+                )
+                .await;
+
+            let runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            let mut stream = Box::pin(runner.into_stream());
+
+            let mut kinds = vec![];
+            while let Some(status) = stream.next().await {
+                kinds.push(match status.unwrap() {
+                    Status::Message(msg) => Some(msg.kind()),
+                    _ => None,
+                });
+            }
+
+            use crate::messages::CommandKind;
+            assert_eq!(
+                kinds,
+                vec![
+                    Some(CommandKind::IrcReady),
+                    Some(CommandKind::Ready),
+                    Some(CommandKind::Privmsg),
+                    Some(CommandKind::Ping),
+                    None
+                ]
+            );
+
+            // it stays finished
+            assert!(stream.next().await.is_none());
+        });
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        futures_lite::future::block_on(async move {
+            let (connector, user_config) = anonymous_handshake();
+
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            runner.pause();
+            assert!(runner.is_paused());
+
+            connector
+                .conn
+                .write_data(":test!test@test PRIVMSG #museun :hello\r\n")
+                .await;
+
+            // nothing is delivered while paused, not even the handshake messages
+            let status = futures_lite::future::or(
+                async { Some(runner.next_message().await) },
+                async {
+                    futures_timer::Delay::new(Duration::from_millis(50)).await;
+                    None
+                },
+            )
+            .await;
+            assert!(status.is_none());
+            assert!(runner.stats().received.is_empty());
+
+            runner.resume();
+            assert!(!runner.is_paused());
+
+            let mut kinds = vec![];
+            while let Status::Message(msg) = runner.next_message().await.unwrap() {
+                kinds.push(msg.kind());
+            }
+
+            use crate::messages::CommandKind;
+            assert_eq!(
+                kinds,
+                vec![
+                    CommandKind::IrcReady,
+                    CommandKind::Ready,
+                    CommandKind::Privmsg
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn next_message_in_arena() {
+        futures_lite::future::block_on(async move {
+            let (connector, user_config) = anonymous_handshake();
+            connector
+                .conn
+                .write_data(
+                    ":test!test@test PRIVMSG #museun :hello\r\n\
+                     PING :1234567890\r\n",
+                )
+                .await;
+
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+            // skip over the registration
+            connector.conn.read_all_lines().await.unwrap();
+
+            let mut arena = MessageArena::with_capacity(512);
+            let start = arena.capacity();
+
+            // (raw, borrowed from the arena)
+            let mut seen = vec![];
+            loop {
+                let arena_ptr = arena.buf.as_ptr();
+                match runner.next_message_in(&mut arena).await.unwrap() {
+                    Status::Message(msg) => seen.push((
+                        msg.raw().to_string(),
+                        std::ptr::eq(msg.raw().as_ptr(), arena_ptr),
+                    )),
+                    Status::Eof => break,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+
+            assert_eq!(
+                seen,
+                vec![
+                    // these were queued up during the handshake, so they're owned
+                    (
+                        ":tmi.twitch.tv 001 justinfan5678 :Welcome, GLHF!\r\n".to_string(),
+                        false
+                    ),
+                    (":tmi.twitch.tv 376 justinfan5678 :>\r\n".to_string(), false),
+                    (
+                        ":test!test@test PRIVMSG #museun :hello\r\n".to_string(),
+                        true
+                    ),
+                    ("PING :1234567890\r\n".to_string(), true),
+                ]
+            );
+            assert_eq!(arena.capacity(), start);
+
+            // the messages are still handled
+            assert_eq!(
+                connector.conn.read_all_lines().await.unwrap(),
+                vec!["PONG :1234567890\r\n"]
+            );
+        });
+    }
+
+    #[test]
+    fn quit() {
+        futures_lite::future::block_on(async move {
+            let (connector, user_config) = anonymous_handshake();
+
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+            let _ = connector.conn.read_all_lines().await.unwrap();
+
+            let on_disconnect = runner.on_disconnect();
+
+            runner.quit().await.unwrap();
+            assert!(!runner.is_connected());
+            assert_eq!(
+                connector.conn.read_all_lines().await.unwrap(),
+                vec!["QUIT\r\n".to_string()]
+            );
+            on_disconnect.await;
+
+            assert!(matches!(runner.next_message().await.unwrap(), Status::Quit));
+
+            // quitting again does nothing
+            runner.quit().await.unwrap();
+            assert!(connector.conn.read_all_lines().await.unwrap().is_empty());
+            assert!(matches!(runner.next_message().await.unwrap(), Status::Quit));
+        });
+    }
+
+    #[test]
+    fn quit_handle_from_another_thread() {
+        fn assert_handle<T: Clone + Send + Sync + 'static>(_: &T) {}
+
+        // the last line is never read, so only quitting can end the runner
+        let log = "\
+            :tmi.twitch.tv 001 justinfan5678 :Welcome, GLHF!\r\n\
+            :tmi.twitch.tv 376 justinfan5678 :>\r\n\
+            @tmi-sent-ts=1000 :test!test@test PRIVMSG #museun :hello\r\n\
+            @tmi-sent-ts=9999999 :test!test@test PRIVMSG #museun :never\r\n";
+        let connector = crate::test::ReplaySource::new(log)
+            .pace(crate::test::Pace::Original { speed: 1.0 })
+            .clock(crate::test::ManualClock::new());
+
+        futures_lite::future::block_on(async move {
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(connector, &user_config)
+                .await
+                .unwrap();
+
+            let quit_handle = runner.quit_handle();
+            assert_handle(&quit_handle);
+
+            let thread = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                quit_handle.notify_sync()
+            });
+
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Quit => break,
+                    Status::Message(Commands::Privmsg(pm)) => assert_eq!(pm.data(), "hello"),
+                    Status::Message(..) => {}
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+            assert!(thread.join().unwrap());
+            assert!(!runner.is_connected());
+
+            // notifying again, or after the runner is gone, is fine
+            let quit_handle = runner.quit_handle();
+            assert!(quit_handle.notify_sync());
+            drop(runner);
+            assert!(!quit_handle.notify_sync());
+        });
+    }
+
+    #[test]
+    fn room_state_changes() {
+        use futures_lite::StreamExt as _;
+
+        futures_lite::future::block_on(async move {
+            let (connector, user_config) = anonymous_handshake();
+            connector
+                .conn
+                .write_data(
+                    ":justinfan5678!justinfan5678@justinfan5678.tmi.twitch.tv JOIN #museun\r\n\
+                     @emote-only=0;followers-only=-1;r9k=0;room-id=23196011;slow=0;subs-only=0 :tmi.twitch.tv ROOMSTATE #museun\r\n\
+                     @room-id=23196011;slow=30 :tmi.twitch.tv ROOMSTATE #museun\r\n\
+                     :test!test@test PRIVMSG #museun :hello\r\n",
+                )
+                .await;
+
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+            let changes = runner.room_state_changes();
+
+            let mut seen = vec![];
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(msg) => seen.push(format!("{:?}", msg.kind())),
+                    Status::Eof => break,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+            assert_eq!(
+                seen.iter().skip(2).map(|s| &**s).collect::<Vec<_>>(),
+                ["Join", "RoomState", "RoomState", "Privmsg"]
+            );
+
+            let settings = runner.room_state("#museun").unwrap();
+            assert_eq!(settings.slow, Some(30));
+            assert_eq!(
+                settings.followers_only,
+                crate::messages::FollowersOnly::Disabled
+            );
+
+            // only the second ROOMSTATE changed anything
+            drop(runner);
+            let changes = changes
+                .map(|change| format!("{} {:?}", change.channel, change.setting))
+                .collect::<Vec<_>>()
+                .await;
+            assert_eq!(changes, ["#museun Slow(Some(30))"]);
+        });
+    }
+
+    #[test]
+    fn auto_reconnect() {
+        futures_lite::future::block_on(async move {
+            let (first, user_config) = anonymous_handshake();
+            first
+                .conn
+                .write_data(
+                    ":justinfan5678!justinfan5678@justinfan5678.tmi.twitch.tv JOIN #museun\r\n\
+                     :test!test@test PRIVMSG #museun :before\r\n\
+                     :tmi.twitch.tv RECONNECT\r\n",
+                )
+                .await;
+
+            let (second, _) = anonymous_handshake();
+            let second = second.conn;
+            second
+                .write_data(
+                    ":justinfan5678!justinfan5678@justinfan5678.tmi.twitch.tv JOIN #museun\r\n\
+                     :test!test@test PRIVMSG #museun :after\r\n",
+                )
+                .await;
+
+            let connector = Sessions::new(vec![first.conn, second.clone()]);
+
+            let mut runner = AsyncRunner::connect_reconnecting(connector, &user_config)
+                .await
+                .unwrap();
+            runner.join("museun").await.unwrap();
+
+            let mut seen = vec![];
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::Privmsg(pm)) => seen.push(pm.data().to_string()),
+                    Status::Message(..) => {}
+                    Status::Eof => break,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+            assert_eq!(seen, ["before", "after"]);
+            assert_eq!(runner.stats().reconnects, 1);
+
+            // it registered again, and then rejoined the channel
+            assert_eq!(
+                second.read_all_lines().await.unwrap(),
+                [
+                    "PASS justinfan5678\r\n",
+                    "NICK justinfan5678\r\n",
+                    "JOIN #museun\r\n"
+                ]
+            );
+            assert_eq!(runner.username(), "justinfan5678");
+        });
+    }
+
+    #[test]
+    fn state_changes() {
+        use futures_lite::StreamExt as _;
+
+        futures_lite::future::block_on(async move {
+            let (first, user_config) = anonymous_handshake();
+            first
+                .conn
+                .write_data(":tmi.twitch.tv RECONNECT\r\n")
+                .await;
+
+            let (second, _) = anonymous_handshake();
+            let connector = Sessions::new(vec![first.conn, second.conn]);
+            let mut runner = AsyncRunner::connect_reconnecting(connector, &user_config)
+                .await
+                .unwrap();
+
+            let states = runner.state_changes();
+            assert_eq!(runner.connection_state(), ConnectionState::Connected);
+
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(..) => {}
+                    Status::Eof => break,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+            assert_eq!(runner.connection_state(), ConnectionState::Disconnected);
+
+            // the stream ends with the runner
+            drop(runner);
+            assert_eq!(
+                states.collect::<Vec<_>>().await,
+                [
+                    ConnectionState::Connected,
+                    ConnectionState::Reconnecting,
+                    ConnectionState::Connected,
+                    ConnectionState::Disconnected,
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn reconnect_disabled() {
+        futures_lite::future::block_on(async move {
+            let (connector, user_config) = anonymous_handshake();
+            connector
+                .conn
+                .write_data(
+                    ":tmi.twitch.tv RECONNECT\r\n",
+                )
+                .await;
+
+            let mut runner = AsyncRunner::connect(connector, &user_config)
+                .await
+                .unwrap();
+
+            let err = loop {
+                match runner.next_message().await {
+                    Ok(Status::Message(..)) => {}
+                    Ok(status) => panic!("unexpected status: {:?}", status),
+                    Err(err) => break err,
+                }
+            };
+            assert!(matches!(err, Error::ShouldReconnect));
+
+            // twitch closes the connection after this, the runner doesn't
+            assert!(runner.is_connected());
+        });
+    }
+
+    #[test]
+    fn stats() {
+        use crate::messages::CommandKind;
+
+        futures_lite::future::block_on(async move {
+            let input = "PING :1234567890\r\n\
+                         :test!test@test PRIVMSG #museun :hello world\r\n";
+
+            let (connector, user_config) = anonymous_handshake();
+            connector.conn.write_data(input).await;
+
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            let clock = crate::test::ManualClock::new();
+            runner.set_clock(clock.clone());
+
+            let stats = runner.stats();
+            assert_eq!(stats.messages_received(), 0);
+            assert_eq!(stats.messages_sent, 2);
+            assert!(stats.last_activity.is_none());
+            assert_eq!(stats.reconnects, 0);
+
+            clock.advance(Duration::from_secs(60));
+            while let Status::Message(..) = runner.next_message().await.unwrap() {}
+
+            let stats = runner.stats();
+            assert_eq!(stats.messages_received(), 4);
+            for kind in &[
+                CommandKind::IrcReady,
+                CommandKind::Ready,
+                CommandKind::Ping,
+                CommandKind::Privmsg,
+            ] {
+                assert_eq!(stats.received[kind], 1);
+            }
+            assert_eq!(stats.last_activity, Some(clock.now()));
+            assert_eq!(stats.reconnects, 0);
+
+            let written = connector.conn.read_all_lines().await.unwrap();
+            assert_eq!(
+                written,
+                vec![
+                    "PASS justinfan5678\r\n",
+                    "NICK justinfan5678\r\n",
+                    "PONG :1234567890\r\n",
+                ]
+            );
+
+            assert_eq!(stats.messages_sent, 3);
+            assert_eq!(stats.bytes_read, (HANDSHAKE.len() + input.len()) as u64);
+        });
+    }
+
+    #[test]
+    fn connect_with_trace() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(
+                    ":tmi.twitch.tv 001 justinfan5678 :Welcome, GLHF!\r\n\
+                     PING :1234567890\r\n\
+                     :tmi.twitch.tv 376 justinfan5678 :>\r\n",
+                )
+                .await;
+
+            // the PING arrives during the handshake, so only the config is shared
+            let (_, user_config) = anonymous_handshake();
+
+            let lines = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+            let trace = {
+                let lines = lines.clone();
+                move |dir: Direction, line: &str| {
+                    lines.lock().unwrap().push((dir, line.to_string()))
+                }
+            };
+
+            let _runner = AsyncRunner::connect_with_trace(connector.clone(), &user_config, trace)
+                .await
+                .unwrap();
+
+            let lines = lines.lock().unwrap();
+            let filter = |direction| {
+                lines
+                    .iter()
+                    .filter(|(dir, _)| *dir == direction)
+                    .map(|(_, line)| line.as_str())
+                    .collect::<Vec<_>>()
+            };
+
+            // incoming lines are traced as they're read,
+            // so they aren't interleaved with the outgoing ones
+            assert_eq!(
+                filter(Direction::Outgoing),
+                vec![
+                    "PASS ***",
+                    "NICK justinfan5678",
+                    "PONG :1234567890"
+                ]
+            );
+            assert_eq!(
+                filter(Direction::Incoming),
+                vec![
+                    ":tmi.twitch.tv 001 justinfan5678 :Welcome, GLHF!",
+                    "PING :1234567890",
+                    ":tmi.twitch.tv 376 justinfan5678 :>",
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn connect_with_trace_redacts_token() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(
+                    ":tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n\
+                     :tmi.twitch.tv 376 shaken_bot :>\r\n",
+                )
+                .await;
+
+            let token = "a".repeat(30);
+            let user_config = UserConfig::builder()
+                .name("shaken_bot")
+                .token(format!("oauth:{}", token))
+                .build()
+                .unwrap();
+
+            let lines = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+            let trace = {
+                let lines = lines.clone();
+                move |_: Direction, line: &str| lines.lock().unwrap().push(line.to_string())
+            };
+
+            let _runner = AsyncRunner::connect_with_trace(connector.clone(), &user_config, trace)
+                .await
+                .unwrap();
+
+            let lines = lines.lock().unwrap();
+            assert!(lines.iter().any(|line| line == "PASS oauth:***"));
+            assert!(lines.iter().all(|line| !line.contains(&token)));
+        });
+    }
+
+    #[test]
+    fn send_command() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(
+                    ":tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n\
+                     :tmi.twitch.tv 376 shaken_bot :>\r\n\
+                     :test!test@test PRIVMSG #museun :hello world\r\n\
+                     @msg-id=ban_success :tmi.twitch.tv NOTICE #museun :test is now banned from this channel.\r\n\
+                     @msg-id=no_permission :tmi.twitch.tv NOTICE #museun :You don't have permission to perform that action.\r\n",
+                )
+                .await;
+
+            let user_config = UserConfig::builder()
+                .name("shaken_bot")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .build()
+                .unwrap();
+
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            // skip the handshake messages
+            runner.missed_messages.clear();
+
+            runner.ban("museun", "test", None).await.unwrap();
+
+            let err = runner.ban("#museun", "museun", "nope").await.unwrap_err();
+            assert!(matches!(
+                err,
+                Error::CommandRejected {
+                    channel,
+                    error: crate::messages::CommandError::NoPermission
+                } if channel == "#museun"
+            ));
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            assert_eq!(lines[2], "PRIVMSG #museun :/ban test\r\n");
+            assert_eq!(lines[3], "PRIVMSG #museun :/ban museun nope\r\n");
+
+            // unrelated messages are still produced
+            assert!(matches!(
+                runner.next_message().await.unwrap(),
+                Status::Message(Commands::Privmsg(..))
+            ));
+        });
+    }
+
+    #[test]
+    fn idle_timeout_with_manual_clock() {
+        use crate::test::ManualClock;
+
+        futures_lite::future::block_on(async move {
+            let conn = Quiet::default();
+            conn.read.lock().unwrap().extend(HANDSHAKE.as_bytes());
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(conn.clone(), &user_config)
+                .await
+                .unwrap();
+
+            let clock = ManualClock::new();
+            runner.set_clock(clock.clone());
+
+            // the handshake messages
+            for _ in 0..2 {
+                assert!(matches!(
+                    runner.step().await.unwrap(),
+                    StepResult::Status(Status::Message(..))
+                ));
+            }
+            conn.take_written();
+
+            // after being idle for the window, a PING is sent
+            let (res, ()) = futures_lite::future::zip(runner.step(), async {
+                clock.advance(WINDOW);
+            })
+            .await;
+            assert!(matches!(res.unwrap(), StepResult::Nothing));
+            assert!(conn.take_written().starts_with("PING :"));
+
+            // and without a PONG, we give up after the timeout
+            let on_disconnect = runner.on_disconnect();
+            let (res, ()) = futures_lite::future::zip(runner.next_message(), async {
+                clock.advance(TIMEOUT + Duration::from_secs(1));
+            })
+            .await;
+            assert!(matches!(res.unwrap_err(), Error::TimedOut));
+
+            // which ends the connection
+            assert!(!runner.is_connected());
+            assert_eq!(runner.connection_state(), ConnectionState::Disconnected);
+            on_disconnect.await;
+
+            // we didn't PING again while waiting
+            assert!(conn.take_written().is_empty());
+        });
+    }
+
+    #[test]
+    fn connect_handshake_timeout() {
+        futures_lite::future::block_on(async move {
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+
+            // nothing is ever read
+            let err = AsyncRunner::connect_with_timeout(
+                Quiet::default(),
+                &user_config,
+                Duration::from_millis(50),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(err, Error::HandshakeTimeout));
+        });
+    }
+
+    #[test]
+    fn connect_failed() {
+        #[derive(Clone)]
+        struct Refused(fn() -> std::io::Error);
+
+        impl Connector for Refused {
+            type Output = TestConn;
+
+            fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
+                let err = (self.0)();
+                Box::pin(async move { Err(err) })
+            }
+        }
+
+        futures_lite::future::block_on(async move {
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+
+            let refused = || std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+            let err = AsyncRunner::connect(Refused(refused), &user_config)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                &err,
+                Error::ConnectionFailed(err) if err.kind() == std::io::ErrorKind::ConnectionRefused
+            ));
+
+            let tls = || crate::connector::TlsHandshakeError::wrap("invalid certificate");
+            let err = AsyncRunner::connect(Refused(tls), &user_config)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::TlsHandshakeFailed(..)));
+            assert_eq!(
+                err.to_string(),
+                "could not connect: tls handshake failed: invalid certificate"
+            );
+        });
+    }
+
+    #[test]
+    fn connect_login_failed() {
+        futures_lite::future::block_on(async move {
+            let user_config = UserConfig::builder()
+                .name("shaken_bot")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .capabilities(&[crate::twitch::Capability::Tags])
+                .build()
+                .unwrap();
+
+            let tests = vec![
+                (
+                    ":tmi.twitch.tv CAP * ACK :twitch.tv/tags\r\n\
+                     :tmi.twitch.tv NOTICE * :Login authentication failed\r\n",
+                    Error::LoginFailed,
+                ),
+                (
+                    ":tmi.twitch.tv NOTICE * :Improperly formatted auth\r\n",
+                    Error::InvalidToken,
+                ),
+            ];
+
+            for (input, expected) in tests {
+                let connector = TestConnector::default();
+                connector.conn.write_data(input).await;
+
+                let err = AsyncRunner::connect(connector.clone(), &user_config)
+                    .await
+                    .unwrap_err();
+                assert_eq!(
+                    std::mem::discriminant(&err),
+                    std::mem::discriminant(&expected)
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn connect_refused_capability() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(
+                    ":tmi.twitch.tv CAP * NAK :twitch.tv/tags\r\n\
+                     :tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n\
+                     :tmi.twitch.tv 376 shaken_bot :>\r\n",
+                )
+                .await;
+
+            let user_config = UserConfig::builder()
+                .name("shaken_bot")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .capabilities(&[crate::twitch::Capability::Tags])
+                .build()
+                .unwrap();
+
+            let runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            assert!(matches!(runner.identity, Identity::Basic { .. }));
+
+            let caps = runner.capabilities();
+            assert!(!caps.tags);
+            assert!(!caps.membership_enabled());
+            assert_eq!(caps.refused(), &[crate::twitch::Capability::Tags]);
+        });
+    }
+
+    #[test]
+    fn connect_batched_capabilities() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(
+                    ":tmi.twitch.tv CAP * ACK :twitch.tv/membership twitch.tv/commands\r\n\
+                     :tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n\
+                     :tmi.twitch.tv 376 shaken_bot :>\r\n\
+                     :tmi.twitch.tv GLOBALUSERSTATE\r\n",
+                )
+                .await;
+
+            let user_config = UserConfig::builder()
+                .name("shaken_bot")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .capabilities(&[
+                    crate::twitch::Capability::Membership,
+                    crate::twitch::Capability::Commands,
+                ])
+                .build()
+                .unwrap();
+
+            let runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            assert_eq!(
+                lines[0],
+                "CAP REQ :twitch.tv/membership twitch.tv/commands\r\n"
+            );
+
+            let caps = runner.capabilities();
+            assert!(caps.membership_enabled());
+            assert!(caps.commands);
+            assert!(!caps.tags);
+            assert!(caps.refused().is_empty());
+        });
+    }
+
+    #[test]
+    fn connect_batched_capabilities_fallback() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(
+                    ":tmi.twitch.tv CAP * NAK :twitch.tv/membership twitch.tv/tags\r\n\
+                     :tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n\
+                     :tmi.twitch.tv 376 shaken_bot :>\r\n\
+                     :tmi.twitch.tv CAP * ACK :twitch.tv/membership\r\n\
+                     :tmi.twitch.tv CAP * NAK :twitch.tv/tags\r\n",
+                )
+                .await;
+
+            let user_config = UserConfig::builder()
+                .name("shaken_bot")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .capabilities(&[
+                    crate::twitch::Capability::Membership,
+                    crate::twitch::Capability::Tags,
+                ])
+                .build()
+                .unwrap();
+
+            let runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            assert_eq!(
+                lines[0],
+                "CAP REQ :twitch.tv/membership twitch.tv/tags\r\n"
+            );
+            assert_eq!(lines[3], "CAP REQ :twitch.tv/membership\r\n");
+            assert_eq!(lines[4], "CAP REQ :twitch.tv/tags\r\n");
+
+            assert!(matches!(runner.identity, Identity::Basic { .. }));
+
+            let caps = runner.capabilities();
+            assert!(caps.membership);
+            assert!(!caps.tags);
+            assert_eq!(caps.refused(), &[crate::twitch::Capability::Tags]);
+        });
+    }
+
+    #[test]
+    fn emote_sets() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(
+                    ":tmi.twitch.tv CAP * ACK :twitch.tv/tags twitch.tv/commands\r\n\
+                     :tmi.twitch.tv 001 shaken_bot :Welcome, GLHF!\r\n\
+                     :tmi.twitch.tv 376 shaken_bot :>\r\n\
+                     @color=#FF69B4;display-name=shaken_bot;emote-sets=0,33,50;user-id=241015868;user-type= :tmi.twitch.tv GLOBALUSERSTATE\r\n\
+                     @color=#FF69B4;display-name=shaken_bot;mod=0 :tmi.twitch.tv USERSTATE #museun\r\n\
+                     @color=#FF69B4;display-name=shaken_bot;emote-sets=0,50,1234;mod=0 :tmi.twitch.tv USERSTATE #museun\r\n",
+                )
+                .await;
+
+            let user_config = UserConfig::builder()
+                .name("shaken_bot")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .capabilities(&[
+                    crate::twitch::Capability::Tags,
+                    crate::twitch::Capability::Commands,
+                ])
+                .build()
+                .unwrap();
+
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+            assert!(matches!(runner.identity, Identity::Full { .. }));
+
+            let old = runner.emote_sets().to_vec();
+            assert_eq!(old, EmoteSet::parse_list("0,33,50"));
+
+            let mut user_states = 0;
+            while let Status::Message(msg) = runner.next_message().await.unwrap() {
+                if let Commands::UserState(..) = msg {
+                    user_states += 1;
+                    // the first one doesn't have the tag, so nothing changes
+                    if user_states == 1 {
+                        assert_eq!(runner.emote_sets(), &*old);
+                    }
+                }
+            }
+            assert_eq!(user_states, 2);
+
+            let diff = crate::twitch::EmoteSetDiff::between(&old, runner.emote_sets());
+            assert_eq!(diff.added, vec![EmoteSet::new("1234")]);
+            assert_eq!(diff.removed, vec![EmoteSet::new("33")]);
+        });
+    }
+}
 }
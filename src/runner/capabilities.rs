@@ -1,3 +1,4 @@
+use crate::twitch::Capability;
 use std::collections::HashSet;
 
 /// Capabiltiies Twitch acknowledged.
@@ -11,4 +12,51 @@ pub struct Capabilities {
     pub tags: bool,
     /// A set of unknown capabilities Twitch sent to use
     pub unknown: HashSet<String>,
+
+    pub(crate) refused: Vec<Capability>,
+}
+
+impl Capabilities {
+    /// Capabilities that you requested, but Twitch refused (with a `CAP NAK`)
+    ///
+    /// For example, if [Capability::Tags] was refused then you won't get any tags on messages.
+    pub fn refused(&self) -> &[Capability] {
+        &self.refused
+    }
+
+    /// Whether Twitch acknowledged the [membership](https://dev.twitch.tv/docs/irc/membership) capability
+    ///
+    /// Without it, Twitch won't send `JOIN`/`PART` for other users, or the
+    /// `NAMES` list (`353`/`366`) when you join a channel. You'll still get the
+    /// `JOIN` and `PART` for yourself.
+    pub fn membership_enabled(&self) -> bool {
+        self.membership
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn membership_enabled() {
+        let caps = Capabilities {
+            tags: true,
+            commands: true,
+            ..Capabilities::default()
+        };
+        assert!(!caps.membership_enabled());
+
+        let caps = Capabilities {
+            membership: true,
+            ..Capabilities::default()
+        };
+        assert!(caps.membership_enabled());
+
+        let caps = Capabilities {
+            refused: vec![Capability::Membership],
+            ..Capabilities::default()
+        };
+        assert!(!caps.membership_enabled());
+    }
 }
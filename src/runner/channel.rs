@@ -70,8 +70,20 @@ impl Channel {
 
     /// Mark that you've been rate limited on this channel
     pub fn set_rate_limited(&mut self) {
+        self.set_rate_limited_at(std::time::Instant::now())
+    }
+
+    // like `set_rate_limited`, but using the runner's clock
+    pub(crate) fn set_rate_limited_at(&mut self, now: std::time::Instant) {
         self.rate_limited.rate_limit.set_cap(1);
-        self.rated_limited_at.replace(std::time::Instant::now());
+        self.rated_limited_at.replace(now);
+    }
+
+    // reset the rate limit if it was limited long enough ago
+    pub(crate) fn expire_rate_limit(&mut self, now: std::time::Instant, window: Duration) {
+        if self.rated_limited_at.map(|s| now.saturating_duration_since(s)) > Some(window) {
+            self.reset_rate_limit();
+        }
     }
 
     /// Reset to the default rate class
@@ -0,0 +1,32 @@
+use crate::BoxedFuture;
+use std::time::{Duration, Instant};
+
+/// A source of time for the [AsyncRunner](crate::AsyncRunner)
+///
+/// The runner uses this for its idle detection (sending a `PING` when the
+/// connection is quiet and giving up if there's no `PONG`), its rate limits
+/// (including join pacing), command timeouts and the time in its
+/// [RunnerStats](crate::runner::RunnerStats). The default is [SystemClock].
+///
+/// Replacing it with [ManualClock](crate::test::ManualClock) (with the `testing` feature) lets you test those without waiting.
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    /// The current time
+    fn now(&self) -> Instant;
+
+    /// A future that completes once `duration` has passed
+    fn delay(&self, duration: Duration) -> BoxedFuture<()>;
+}
+
+/// A [Clock] using the system's time
+#[derive(Default, Debug, Copy, Clone)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn delay(&self, duration: Duration) -> BoxedFuture<()> {
+        Box::pin(futures_timer::Delay::new(duration))
+    }
+}
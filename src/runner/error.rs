@@ -1,15 +1,26 @@
-use crate::{DecodeError, MessageError};
+use crate::{
+    messages::{CommandError, Notice},
+    DecodeError, MessageError,
+};
 
 /// An error returned by a Runner
 #[derive(Debug)]
 pub enum Error {
     /// An I/O error occured
     Io(std::io::Error),
+    /// The connector couldn't connect to Twitch
+    ConnectionFailed(std::io::Error),
+    /// The connection was made, but the TLS handshake failed
+    ///
+    /// See [TlsHandshakeError](crate::connector::TlsHandshakeError)
+    TlsHandshakeFailed(std::io::Error),
     /// Invalid utf-8 was parsed (either you sent invalid utf-8, or Twitch did and we read it).
     InvalidUtf8(std::str::Utf8Error),
     /// We could not parse a message -- this should never happen
     ParsingFailure(MessageError),
-    /// You requested a capability and Twitch rejected it
+    /// Twitch rejected an unknown capability
+    ///
+    /// Known capabilities that were rejected are available from [Capabilities::refused](crate::runner::Capabilities::refused)
     InvalidCap {
         /// The capability name
         cap: String,
@@ -29,18 +40,38 @@ pub enum Error {
         /// The channel name
         channel: String,
     },
+    /// Twitch rejected your login, the OAuth token was wrong or has expired
+    LoginFailed,
+    /// Twitch rejected the OAuth token because it wasn't in the form of `oauth:<token>`
+    InvalidToken,
+    /// Twitch didn't finish the handshake in time
+    HandshakeTimeout,
     /// Your connection timed out.
     TimedOut,
     /// Twitch restarted the server, you should reconnect.
     ShouldReconnect,
     /// An unexpected EOF was found -- this means the connectionc losed abnormally.
     UnexpectedEof,
+    /// Twitch rejected a command you sent
+    CommandRejected {
+        /// The channel name
+        channel: String,
+        /// Why it was rejected
+        error: CommandError,
+    },
+    /// Twitch didn't respond to a command you sent in time
+    NoCommandResponse {
+        /// The channel name
+        channel: String,
+    },
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Io(err) => write!(f, "io error: {}", err),
+            Self::ConnectionFailed(err) => write!(f, "could not connect: {}", err),
+            Self::TlsHandshakeFailed(err) => write!(f, "could not connect: {}", err),
             Self::InvalidUtf8(err) => write!(f, "invalid utf-8 while parsing: {}", err),
             Self::ParsingFailure(err) => write!(f, "could not parse message: {}", err),
             Self::InvalidCap { cap } => {
@@ -49,9 +80,46 @@ impl std::fmt::Display for Error {
             Self::AlreadyOnChannel { channel } => write!(f, "already on channel '{}'", channel),
             Self::NotOnChannel { channel } => write!(f, "not on channel '{}'", channel),
             Self::BannedFromChannel { channel } => write!(f, "banned from channel '{}'", channel),
+            Self::LoginFailed => write!(f, "login authentication failed"),
+            Self::InvalidToken => write!(f, "improperly formatted oauth token"),
+            Self::HandshakeTimeout => write!(f, "the handshake with Twitch timed out"),
             Self::TimedOut => write!(f, "your connection timed out"),
             Self::ShouldReconnect => write!(f, "you should reconnect. Twitch restarted the server"),
             Self::UnexpectedEof => write!(f, "reached an unexpected EOF"),
+            Self::CommandRejected { channel, error } => {
+                write!(f, "{} on channel '{}'", error, channel)
+            }
+            Self::NoCommandResponse { channel } => {
+                write!(f, "no response to command on channel '{}'", channel)
+            }
+        }
+    }
+}
+
+impl Error {
+    // whether this error means the connection is gone
+    #[cfg(feature = "async")]
+    pub(crate) fn is_connection_error(&self) -> bool {
+        matches!(
+            self,
+            Self::Io(..)
+                | Self::UnexpectedEof
+                | Self::InvalidUtf8(..)
+                | Self::ParsingFailure(..)
+                | Self::TimedOut
+        )
+    }
+
+    // Twitch sends a global NOTICE and then closes the connection if the login was bad
+    pub(crate) fn from_login_notice(msg: &Notice<'_>) -> Option<Self> {
+        if msg.channel() != "*" {
+            return None;
+        }
+
+        match msg.message() {
+            "Login authentication failed" => Some(Self::LoginFailed),
+            "Improperly formatted auth" => Some(Self::InvalidToken),
+            _ => None,
         }
     }
 }
@@ -59,9 +127,10 @@ impl std::fmt::Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Self::Io(err) => Some(err),
+            Self::Io(err) | Self::ConnectionFailed(err) | Self::TlsHandshakeFailed(err) => Some(err),
             Self::InvalidUtf8(err) => Some(err),
             Self::ParsingFailure(err) => Some(err),
+            Self::CommandRejected { error, .. } => Some(error),
             _ => None,
         }
     }
@@ -72,8 +141,13 @@ impl From<DecodeError> for Error {
         match err {
             DecodeError::Io(err) => Self::Io(err),
             DecodeError::InvalidUtf8(err) => Self::InvalidUtf8(err),
-            DecodeError::ParseError(err) => Self::ParsingFailure(err),
+            DecodeError::ParseError(err) | DecodeError::CommandError(err) => {
+                Self::ParsingFailure(err)
+            }
             DecodeError::Eof => Self::UnexpectedEof,
+            DecodeError::LineTooLong => {
+                Self::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            }
         }
     }
 }
@@ -6,6 +6,8 @@ use crate::{runner::Capabilities, twitch::Color};
 #[derive(Debug, Clone)]
 pub enum Identity {
     /// An anonymous identity.
+    ///
+    /// This is created if your name starts with `justinfan`.
     Anonymous {
         /// The capabilities you'll have
         caps: Capabilities,
@@ -41,12 +43,28 @@ pub enum Identity {
 impl Identity {
     /// Get your username from this identity
     ///
-    /// If its anonymous, it'll be `justinfan1234`
+    /// If its anonymous, it'll be `justinfan1234`. Use
+    /// [AsyncRunner::username](crate::AsyncRunner::username) for the name you
+    /// actually connected with.
     pub fn username(&self) -> &str {
         let (nick, _) = crate::ANONYMOUS_LOGIN;
         match self {
             Self::Anonymous { .. } => nick,
-            Self::Basic { name, .. } | Self::Full { name, .. } => &*name,
+            Self::Basic { name, .. } | Self::Full { name, .. } => name,
         }
     }
+
+    /// Get the capabilities Twitch acknowledged for this identity
+    pub fn capabilities(&self) -> &Capabilities {
+        match self {
+            Self::Anonymous { caps, .. } | Self::Basic { caps, .. } | Self::Full { caps, .. } => {
+                caps
+            }
+        }
+    }
+
+    /// Determines whether this is an anonymous identity
+    pub fn is_anonymous(&self) -> bool {
+        matches!(self, Self::Anonymous { .. })
+    }
 }
@@ -11,11 +11,14 @@
 //!
 
 mod status;
-pub use status::{Status, StepResult};
+pub use status::{ConnectionState, Status, StepResult};
 
 mod capabilities;
 pub use capabilities::Capabilities;
 
+mod room_state;
+pub use room_state::{RoomSetting, RoomSettings, RoomStateChange};
+
 mod identity;
 pub use identity::Identity;
 
@@ -25,6 +28,8 @@ pub use error::Error;
 #[allow(dead_code)]
 mod timeout;
 
+pub mod wait_for;
+
 cfg_async! {
     mod rate_limit;
 }
@@ -34,6 +39,26 @@ cfg_async! {
     pub use channel::Channel;
 }
 
+cfg_async! {
+    mod stats;
+    pub use stats::RunnerStats;
+}
+
+cfg_async! {
+    mod trace;
+    pub use trace::Direction;
+}
+
+cfg_async! {
+    mod clock;
+    pub use clock::{Clock, SystemClock};
+}
+
+cfg_async! {
+    mod arena;
+    pub use arena::MessageArena;
+}
+
 cfg_async! {
     mod async_runner;
     pub use async_runner::AsyncRunner;
@@ -1,6 +1,9 @@
-use crate::rate_limit::{RateClass, RateLimit};
+use crate::rate_limit::{JoinRate, RateClass, RateLimit};
 use futures_lite::{AsyncWrite, AsyncWriteExt};
-use std::{collections::VecDeque, time::Duration};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 pub struct RateLimitedEncoder {
     pub(crate) rate_limit: RateLimit,
@@ -13,20 +16,21 @@ impl RateLimitedEncoder {
         name: &str,
         limit: &mut u64,
         sink: &mut W,
+        now: Instant,
     ) -> std::io::Result<()>
     where
         W: AsyncWrite + Send + Sync + Unpin + ?Sized,
     {
         while let Some(data) = self.queue.pop_front() {
-            match self.rate_limit.consume(1) {
+            match self.rate_limit.consume_at(1, now) {
                 Ok(..) => {
                     *limit = limit.saturating_sub(1);
                     log::trace!(
                         target: "twitchchat::encoder",
                         "> {}",
-                        std::str::from_utf8(&*data).unwrap().escape_debug()
+                        std::str::from_utf8(&data).unwrap().escape_debug()
                     );
-                    sink.write_all(&*data).await?;
+                    sink.write_all(&data).await?;
                 }
                 Err(..) => {
                     log::warn!(
@@ -50,6 +54,72 @@ impl RateLimitedEncoder {
     }
 }
 
+pub struct JoinQueue {
+    pub(crate) rate_limit: RateLimit,
+    pub(crate) queue: VecDeque<Box<[u8]>>,
+}
+
+impl JoinQueue {
+    pub fn new(join_rate: JoinRate) -> Self {
+        Self {
+            rate_limit: RateLimit::from_join_rate(join_rate),
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn set_join_rate(&mut self, join_rate: JoinRate) {
+        self.rate_limit = RateLimit::from_join_rate(join_rate);
+    }
+
+    /// Drain as many queued `JOIN`s as the rate limit allows
+    ///
+    /// This returns how long to wait before trying again, if any are left
+    pub async fn drain_until_blocked<W>(
+        &mut self,
+        sink: &mut W,
+        now: Instant,
+    ) -> std::io::Result<Option<Duration>>
+    where
+        W: AsyncWrite + Send + Sync + Unpin + ?Sized,
+    {
+        while let Some(data) = self.queue.front() {
+            // a line with more channels than the cap would never be sent otherwise
+            let cost = join_cost(data).min(self.rate_limit.get_cap());
+            if let Err(retry) = self.rate_limit.consume_at(cost, now) {
+                log::warn!(
+                    target: "twitchchat::rate_limit",
+                    "join rate limit hit, {} joins queued",
+                    self.queue.len()
+                );
+                return Ok(Some(retry));
+            }
+
+            log::trace!(
+                target: "twitchchat::encoder",
+                "> {}",
+                std::str::from_utf8(data).unwrap().escape_debug()
+            );
+            sink.write_all(data).await?;
+            self.queue.pop_front();
+        }
+
+        Ok(None)
+    }
+
+    pub fn enqueue(&mut self, msg: &[u8]) {
+        // join_many can produce several lines, each is paced on its own
+        self.queue.extend(
+            msg.split_inclusive(|&b| b == b'\n')
+                .map(|line| line.to_vec().into_boxed_slice()),
+        );
+    }
+}
+
+// Twitch counts each channel in a `JOIN #a,#b,#c` line against the join rate
+fn join_cost(line: &[u8]) -> u64 {
+    1 + line.iter().filter(|&&b| b == b',').count() as u64
+}
+
 pub struct PreviousRate {
     pub cap: u64,
     pub period: Duration,
@@ -63,3 +133,71 @@ impl Default for PreviousRate {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Encodable as _;
+
+    #[test]
+    fn join_queue_paced() {
+        futures_lite::future::block_on(async move {
+            let mut queue = JoinQueue::new(JoinRate::Regular);
+            for i in 0..30 {
+                let mut buf = vec![];
+                crate::commands::join(&format!("chan{}", i))
+                    .encode(&mut buf)
+                    .unwrap();
+                queue.enqueue(&buf);
+            }
+
+            let now = Instant::now();
+            let mut out = vec![];
+            let retry = queue.drain_until_blocked(&mut out, now).await.unwrap();
+
+            let lines = std::str::from_utf8(&out).unwrap().lines().count();
+            assert_eq!(lines, JoinRate::Regular.tickets() as usize);
+            assert_eq!(queue.queue.len(), 10);
+
+            // and we should be told to wait before sending the rest
+            let retry = retry.unwrap();
+            assert!(retry > Duration::from_secs(0) && retry <= JoinRate::period());
+
+            // nothing more should be sent until the period elapses
+            let retry = queue.drain_until_blocked(&mut out, now).await.unwrap();
+            assert!(retry.is_some());
+            assert_eq!(std::str::from_utf8(&out).unwrap().lines().count(), 20);
+
+            // and then the rest are sent
+            let later = now + JoinRate::period() + Duration::from_secs(1);
+            let retry = queue.drain_until_blocked(&mut out, later).await.unwrap();
+            assert!(retry.is_none());
+            assert_eq!(std::str::from_utf8(&out).unwrap().lines().count(), 30);
+        });
+    }
+
+    #[test]
+    fn join_queue_charges_per_channel() {
+        futures_lite::future::block_on(async move {
+            let channels = (0..25).map(|i| format!("chan{}", i)).collect::<Vec<_>>();
+            let mut buf = vec![];
+            crate::commands::join_many(channels.iter().map(|s| s.as_str()))
+                .encode(&mut buf)
+                .unwrap();
+
+            let mut queue = JoinQueue::new(JoinRate::Regular);
+            queue.enqueue(&buf);
+            assert_eq!(queue.queue.len(), 3);
+
+            // the first two lines have 10 channels each, which uses up all 20 tokens
+            let mut out = vec![];
+            let retry = queue
+                .drain_until_blocked(&mut out, Instant::now())
+                .await
+                .unwrap();
+            assert!(retry.is_some());
+            assert_eq!(std::str::from_utf8(&out).unwrap().lines().count(), 2);
+            assert_eq!(queue.queue.len(), 1);
+        });
+    }
+}
@@ -0,0 +1,145 @@
+use crate::messages::FollowersOnly;
+
+/// The chat settings of a channel, tracked from its `ROOMSTATE` messages
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RoomSettings {
+    /// Only emotes may be used in chat
+    pub emote_only: bool,
+    /// Whether only followers may chat
+    pub followers_only: FollowersOnly,
+    /// Messages must be unique (r9k mode)
+    pub r9k: bool,
+    /// How many seconds users must wait between messages, if slow mode is on
+    pub slow: Option<u64>,
+    /// Only subscribers may chat
+    pub subs_only: bool,
+}
+
+impl Default for RoomSettings {
+    fn default() -> Self {
+        Self {
+            emote_only: false,
+            followers_only: FollowersOnly::Disabled,
+            r9k: false,
+            slow: None,
+            subs_only: false,
+        }
+    }
+}
+
+cfg_async! {
+use crate::messages::RoomState;
+
+impl RoomSettings {
+    pub(crate) fn from_room_state(msg: &RoomState<'_>) -> Self {
+        let mut this = Self::default();
+        this.update(msg);
+        this
+    }
+
+    /// Apply the settings that are in `msg`, returning the ones that changed
+    ///
+    /// Twitch only sends the settings that changed after the first `ROOMSTATE`,
+    /// so anything missing from `msg` is left alone.
+    pub(crate) fn update(&mut self, msg: &RoomState<'_>) -> Vec<RoomSetting> {
+        let tags = msg.tags();
+        let mut changed = vec![];
+
+        macro_rules! apply {
+            ($field:ident, $setting:ident, $new:expr) => {
+                if let Some(new) = $new {
+                    if self.$field != new {
+                        self.$field = new;
+                        changed.push(RoomSetting::$setting(new));
+                    }
+                }
+            };
+        }
+
+        apply!(emote_only, EmoteOnly, tags.get("emote-only").map(|_| msg.is_emote_only()));
+        apply!(followers_only, FollowersOnly, msg.is_followers_only());
+        apply!(r9k, R9k, tags.get("r9k").map(|_| msg.is_r9k()));
+        apply!(slow, Slow, tags.get("slow").map(|_| msg.is_slow_mode()));
+        apply!(subs_only, SubsOnly, tags.get("subs-only").map(|_| msg.is_subs_only()));
+
+        changed
+    }
+}
+}
+
+/// A chat setting of a channel, with its new value
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RoomSetting {
+    /// Emote-only mode was turned on or off
+    EmoteOnly(bool),
+    /// Followers-only mode changed
+    FollowersOnly(FollowersOnly),
+    /// r9k mode was turned on or off
+    R9k(bool),
+    /// Slow mode changed, this is the number of seconds between messages (or `None` if it was turned off)
+    Slow(Option<u64>),
+    /// Subscribers-only mode was turned on or off
+    SubsOnly(bool),
+}
+
+/// A setting changed on a channel you're on
+///
+/// See [AsyncRunner::room_state_changes](crate::AsyncRunner::room_state_changes)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomStateChange {
+    /// The channel the setting changed on
+    pub channel: String,
+    /// The setting that changed, and its new value
+    pub setting: RoomSetting,
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use super::*;
+    use crate::{irc::IrcMessage, FromIrcMessage as _, MaybeOwned};
+
+    fn room_state(input: &str) -> RoomState<'_> {
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        RoomState::from_irc(msg).unwrap()
+    }
+
+    #[test]
+    fn update_room_settings() {
+        let mut settings = RoomSettings::from_room_state(&room_state(
+            "@emote-only=0;followers-only=10;r9k=0;rituals=0;room-id=23196011;slow=0;subs-only=0 :tmi.twitch.tv ROOMSTATE #museun\r\n",
+        ));
+        assert_eq!(
+            settings,
+            RoomSettings {
+                followers_only: FollowersOnly::Limit(10),
+                ..RoomSettings::default()
+            }
+        );
+
+        let changed = settings.update(&room_state(
+            "@room-id=23196011;slow=30 :tmi.twitch.tv ROOMSTATE #museun\r\n",
+        ));
+        assert_eq!(changed, vec![RoomSetting::Slow(Some(30))]);
+        assert_eq!(settings.slow, Some(30));
+        assert_eq!(settings.followers_only, FollowersOnly::Limit(10));
+
+        // the same value again isn't a change
+        let changed = settings.update(&room_state(
+            "@room-id=23196011;slow=30 :tmi.twitch.tv ROOMSTATE #museun\r\n",
+        ));
+        assert!(changed.is_empty());
+
+        let changed = settings.update(&room_state(
+            "@followers-only=-1;room-id=23196011;slow=0;subs-only=1 :tmi.twitch.tv ROOMSTATE #museun\r\n",
+        ));
+        assert_eq!(
+            changed,
+            vec![
+                RoomSetting::FollowersOnly(FollowersOnly::Disabled),
+                RoomSetting::Slow(None),
+                RoomSetting::SubsOnly(true),
+            ]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::messages::CommandKind;
+
+use futures_lite::{AsyncRead, AsyncWrite};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Instant,
+};
+
+/// A snapshot of the counters an [AsyncRunner](crate::runner::AsyncRunner) maintains
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunnerStats {
+    /// How many messages were received, by their kind
+    pub received: HashMap<CommandKind, u64>,
+    /// How many lines were sent
+    pub messages_sent: u64,
+    /// How many bytes were read from the connection
+    pub bytes_read: u64,
+    /// How many bytes were written to the connection
+    pub bytes_written: u64,
+    /// When a message was last received, according to the runner's [Clock](crate::runner::Clock)
+    pub last_activity: Option<Instant>,
+    /// How many times the runner has reconnected
+    pub reconnects: u64,
+}
+
+impl RunnerStats {
+    /// The total number of messages received
+    pub fn messages_received(&self) -> u64 {
+        self.received.values().sum()
+    }
+}
+
+// these are updated by the reader/writer wrappers, so they have to be shared
+#[derive(Default, Debug)]
+pub(crate) struct Counters {
+    messages_sent: AtomicU64,
+    bytes_read: AtomicU64,
+    bytes_written: AtomicU64,
+}
+
+impl Counters {
+    pub(crate) fn read(&self, data: &[u8]) {
+        self.bytes_read
+            .fetch_add(data.len() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn written(&self, data: &[u8]) {
+        let lines = data.iter().filter(|&&c| c == b'\n').count();
+        self.messages_sent
+            .fetch_add(lines as u64, Ordering::Relaxed);
+        self.bytes_written
+            .fetch_add(data.len() as u64, Ordering::Relaxed);
+    }
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct Stats {
+    pub(crate) counters: Arc<Counters>,
+    received: HashMap<CommandKind, u64>,
+    last_activity: Option<Instant>,
+    reconnects: u64,
+}
+
+impl Stats {
+    pub(crate) fn received(&mut self, kind: CommandKind, now: Instant) {
+        *self.received.entry(kind).or_default() += 1;
+        self.last_activity.replace(now);
+    }
+
+    pub(crate) fn reconnected(&mut self) {
+        self.reconnects += 1;
+    }
+
+    pub(crate) fn snapshot(&self) -> RunnerStats {
+        RunnerStats {
+            received: self.received.clone(),
+            messages_sent: self.counters.messages_sent.load(Ordering::Relaxed),
+            bytes_read: self.counters.bytes_read.load(Ordering::Relaxed),
+            bytes_written: self.counters.bytes_written.load(Ordering::Relaxed),
+            last_activity: self.last_activity,
+            reconnects: self.reconnects,
+        }
+    }
+}
+
+/// Counts the bytes read and written through it
+pub(crate) struct Counting<T> {
+    inner: T,
+    counters: Arc<Counters>,
+}
+
+impl<T> Counting<T> {
+    pub(crate) fn new(inner: T, counters: Arc<Counters>) -> Self {
+        Self { inner, counters }
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Counting<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        let n = futures_lite::ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.counters.read(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Counting<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        let n = futures_lite::ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        this.counters.written(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
@@ -19,3 +19,25 @@ pub enum Status<'a> {
     /// Loop run to completion
     Eof,
 }
+
+/// The state of the runner's connection
+///
+/// See [AsyncRunner::state_changes](crate::AsyncRunner::state_changes)
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConnectionState {
+    /// Connected to Twitch
+    Connected,
+    /// Twitch asked you to reconnect, and the runner is reconnecting
+    Reconnecting,
+    /// The connection has ended, it won't be reconnected
+    Disconnected,
+}
+
+#[cfg(feature = "async")]
+impl<'a> Status<'a> {
+    /// Whether the loop has ended
+    pub(crate) fn is_finished(&self) -> bool {
+        matches!(self, Self::Quit | Self::Eof)
+    }
+}
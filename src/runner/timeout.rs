@@ -8,21 +8,19 @@ pub enum TimeoutState {
 }
 
 impl TimeoutState {
-    pub fn activity() -> Self {
-        Self::Activity(Instant::now())
+    pub fn activity(now: Instant) -> Self {
+        Self::Activity(now)
     }
 
-    pub fn waiting_for_pong() -> Self {
-        Self::WaitingForPong(Instant::now())
+    pub fn waiting_for_pong(now: Instant) -> Self {
+        Self::WaitingForPong(now)
     }
 }
 
 pub const WINDOW: Duration = Duration::from_secs(45);
 pub const TIMEOUT: Duration = Duration::from_secs(10);
 pub const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(30);
-
-cfg_async! {
-    pub async fn next_delay() {
-        futures_timer::Delay::new(WINDOW).await
-    }
-}
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+pub const RECONNECT_ATTEMPTS: u32 = 3;
@@ -0,0 +1,140 @@
+use futures_lite::{AsyncRead, AsyncWrite};
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+/// The direction of a traced line
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// The line was received from the server
+    Incoming,
+    /// The line was sent to the server
+    Outgoing,
+}
+
+pub(crate) type TraceHook = Arc<dyn Fn(Direction, &str) + Send + Sync>;
+
+/// Calls the hook for each complete line read or written through it
+pub(crate) struct Traced<T> {
+    inner: T,
+    hook: TraceHook,
+    direction: Direction,
+    buf: Vec<u8>,
+}
+
+impl<T> Traced<T> {
+    pub(crate) fn incoming(inner: T, hook: TraceHook) -> Self {
+        Self::new(inner, hook, Direction::Incoming)
+    }
+
+    pub(crate) fn outgoing(inner: T, hook: TraceHook) -> Self {
+        Self::new(inner, hook, Direction::Outgoing)
+    }
+
+    fn new(inner: T, hook: TraceHook, direction: Direction) -> Self {
+        Self {
+            inner,
+            hook,
+            direction,
+            buf: Vec::new(),
+        }
+    }
+
+    fn trace(&mut self, data: &[u8]) {
+        let (hook, direction) = (&self.hook, self.direction);
+        trace_lines(&mut self.buf, data, |line| hook(direction, line))
+    }
+}
+
+// buffers partial lines in `buf`, calling `f` for each complete line without its `\r\n`
+//
+// the token on a `PASS` line is redacted so it never reaches the hook
+pub(crate) fn trace_lines(buf: &mut Vec<u8>, data: &[u8], mut f: impl FnMut(&str)) {
+    buf.extend_from_slice(data);
+
+    let mut start = 0;
+    while let Some(pos) = buf[start..].iter().position(|&c| c == b'\n') {
+        let line = &buf[start..start + pos];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        f(redact(&String::from_utf8_lossy(line)));
+        start += pos + 1;
+    }
+    buf.drain(..start);
+}
+
+fn redact(line: &str) -> &str {
+    match line.strip_prefix("PASS ") {
+        Some(token) if token.starts_with("oauth:") => "PASS oauth:***",
+        Some(..) => "PASS ***",
+        None => line,
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Traced<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        let n = futures_lite::ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.trace(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Traced<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        let n = futures_lite::ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        this.trace(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_partial_lines() {
+        let mut buf = vec![];
+        let mut lines = vec![];
+
+        trace_lines(&mut buf, b"PING :12", |s| lines.push(s.to_string()));
+        assert!(lines.is_empty());
+
+        trace_lines(&mut buf, b"34\r\nPONG :1234\r\nPRIV", |s| {
+            lines.push(s.to_string())
+        });
+        assert_eq!(lines, vec!["PING :1234", "PONG :1234"]);
+        assert_eq!(buf, b"PRIV");
+    }
+
+    #[test]
+    fn trace_redacts_pass() {
+        let mut buf = vec![];
+        let mut lines = vec![];
+
+        trace_lines(
+            &mut buf,
+            b"PASS oauth:abcdefghijklmnopqrstuvwxyz0123\r\nNICK museun\r\nPASS hunter2\r\n",
+            |s| lines.push(s.to_string()),
+        );
+        assert_eq!(lines, vec!["PASS oauth:***", "NICK museun", "PASS ***"]);
+    }
+}
@@ -0,0 +1,198 @@
+//! Helpers for waiting on Twitch, for those driving their own loop
+//!
+//! The [AsyncRunner](crate::AsyncRunner) waits for these for you, but if
+//! you're using a [Decoder] directly you can use these to wait for the same
+//! responses.
+//!
+//! ```no_run
+//! use twitchchat::{commands, runner::wait_for, Decoder, Encodable as _};
+//!
+//! // this is a connection you've already registered as `shaken_bot`, e.g. with the `handshake` module
+//! let mut stream = std::net::TcpStream::connect(twitchchat::TWITCH_IRC_ADDRESS).unwrap();
+//!
+//! commands::join("museun").encode(&mut stream).unwrap();
+//!
+//! let mut decoder = Decoder::new(&stream);
+//! match wait_for::join_confirmed(&mut decoder, "museun", "shaken_bot") {
+//!     Ok(()) => println!("joined #museun"),
+//!     Err(err) => eprintln!("could not join: {}", err),
+//! }
+//! ```
+use crate::{
+    messages::{Commands, MessageId},
+    twitch::normalize_channel,
+    DecodeError, Decoder, FromIrcMessage as _,
+};
+
+use std::io::Read;
+
+/// An error returned by [join_confirmed]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum JoinError {
+    /// You could not join this channel, you were banned prior.
+    Banned {
+        /// The channel name
+        channel: String,
+    },
+    /// The channel has been suspended
+    Suspended {
+        /// The channel name
+        channel: String,
+    },
+    /// The channel does not exist
+    NotFound {
+        /// The channel name
+        channel: String,
+    },
+    /// The connection closed before the join was confirmed
+    UnexpectedEof,
+    /// The decoder failed to read a message
+    Decode(DecodeError),
+}
+
+impl std::fmt::Display for JoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Banned { channel } => write!(f, "banned from channel '{}'", channel),
+            Self::Suspended { channel } => write!(f, "channel '{}' is suspended", channel),
+            Self::NotFound { channel } => write!(f, "channel '{}' does not exist", channel),
+            Self::UnexpectedEof => write!(f, "reached an unexpected EOF"),
+            Self::Decode(err) => write!(f, "could not decode message: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DecodeError> for JoinError {
+    fn from(err: DecodeError) -> Self {
+        match err {
+            DecodeError::Eof => Self::UnexpectedEof,
+            err => Self::Decode(err),
+        }
+    }
+}
+
+/// Read messages until Twitch confirms that `username` has joined `channel`
+///
+/// A join is confirmed by a `JOIN` from `username` or a `USERSTATE` for the
+/// channel, and it has failed if Twitch sends a `NOTICE` for the channel
+/// saying you're banned or that the channel is suspended or doesn't exist.
+///
+/// This only reads, so you should've already sent the [join](crate::commands::join).
+///
+/// # NOTE
+/// Any other messages read while waiting are discarded, and `PING`s aren't
+/// answered. With the `Membership` capability, the `JOIN`s for other users
+/// are skipped.
+pub fn join_confirmed<R>(
+    decoder: &mut Decoder<R>,
+    channel: &str,
+    username: &str,
+) -> Result<(), JoinError>
+where
+    R: Read,
+{
+    let channel = normalize_channel(channel);
+
+    loop {
+        let msg = Commands::from_irc(decoder.read_message()?)
+            .map_err(|err| JoinError::Decode(DecodeError::CommandError(err)))?;
+
+        match msg {
+            Commands::Join(msg) if msg.channel() == channel && msg.name() == username => {
+                return Ok(())
+            }
+            Commands::UserState(msg) if msg.channel() == channel => return Ok(()),
+            Commands::Notice(msg) if msg.channel() == channel => {
+                let channel = channel.to_string();
+                match msg.msg_id() {
+                    Some(MessageId::MsgBanned) => return Err(JoinError::Banned { channel }),
+                    Some(MessageId::MsgChannelSuspended) => {
+                        return Err(JoinError::Suspended { channel })
+                    }
+                    Some(MessageId::MsgRoomNotFound) => return Err(JoinError::NotFound { channel }),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_confirmed_by_join() {
+        let input = ":test!test@test PRIVMSG #museun :hello\r\n\
+                     :museun!museun@museun.tmi.twitch.tv JOIN #museun\r\n";
+        let mut decoder = Decoder::new(input.as_bytes());
+        join_confirmed(&mut decoder, "Museun", "museun").unwrap();
+
+        // it read up to and including the JOIN
+        assert!(matches!(decoder.read_message(), Err(DecodeError::Eof)));
+    }
+
+    #[test]
+    fn join_confirmed_skips_other_users() {
+        // with the membership capability, other users' JOINs aren't ours
+        let input = ":test!test@test.tmi.twitch.tv JOIN #museun\r\n\
+                     :shaken_bot!shaken_bot@shaken_bot.tmi.twitch.tv JOIN #museun\r\n";
+        let mut decoder = Decoder::new(input.as_bytes());
+        join_confirmed(&mut decoder, "museun", "shaken_bot").unwrap();
+        assert!(matches!(decoder.read_message(), Err(DecodeError::Eof)));
+
+        let input = ":test!test@test.tmi.twitch.tv JOIN #museun\r\n";
+        let mut decoder = Decoder::new(input.as_bytes());
+        assert!(matches!(
+            join_confirmed(&mut decoder, "museun", "shaken_bot").unwrap_err(),
+            JoinError::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn join_confirmed_by_user_state() {
+        let input = ":museun!museun@museun.tmi.twitch.tv JOIN #shaken_bot\r\n\
+                     @badge-info=;badges=;color=#FF69B4;display-name=museun;emote-sets=0;mod=0;subscriber=0;user-type= :tmi.twitch.tv USERSTATE #museun\r\n";
+        let mut decoder = Decoder::new(input.as_bytes());
+        join_confirmed(&mut decoder, "#museun", "museun").unwrap();
+    }
+
+    #[test]
+    fn join_confirmed_failure_notice() {
+        let input = "@msg-id=msg_channel_suspended :tmi.twitch.tv NOTICE #museun :This channel has been suspended.\r\n";
+        let mut decoder = Decoder::new(input.as_bytes());
+        assert!(matches!(
+            join_confirmed(&mut decoder, "museun", "museun").unwrap_err(),
+            JoinError::Suspended { channel } if channel == "#museun"
+        ));
+
+        let input = "@msg-id=msg_banned :tmi.twitch.tv NOTICE #museun :You are permanently banned from talking in museun.\r\n";
+        let mut decoder = Decoder::new(input.as_bytes());
+        assert!(matches!(
+            join_confirmed(&mut decoder, "museun", "museun").unwrap_err(),
+            JoinError::Banned { channel } if channel == "#museun"
+        ));
+    }
+
+    #[test]
+    fn join_confirmed_eof() {
+        // a notice for another channel isn't ours
+        let input = "@msg-id=msg_banned :tmi.twitch.tv NOTICE #shaken_bot :You are permanently banned from talking in shaken_bot.\r\n";
+        let mut decoder = Decoder::new(input.as_bytes());
+        assert!(matches!(
+            join_confirmed(&mut decoder, "museun", "museun").unwrap_err(),
+            JoinError::UnexpectedEof
+        ));
+    }
+}
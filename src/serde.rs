@@ -5,7 +5,8 @@ use serde::{
     Deserialize, Deserializer,
 };
 
-use std::marker::PhantomData;
+use alloc::{boxed::Box, format};
+use core::marker::PhantomData;
 
 impl<'de, 'a> Deserialize<'de> for MaybeOwned<'a> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -27,10 +28,10 @@ impl<'a, T> Default for RawVisitor<'a, T> {
 impl<'de, 'a, T> Visitor<'de> for RawVisitor<'a, T>
 where
     T: FromIrcMessage<'a>,
-    T::Error: std::error::Error,
+    T::Error: core::error::Error,
 {
     type Value = T;
-    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(formatter, "map")
     }
 
@@ -56,7 +57,7 @@ where
         let irc = IrcMessage::parse(raw).map_err(|err| {
             A::Error::custom(format!(
                 "cannot parse '{}' from the irc message: {}",
-                std::any::type_name::<T>(),
+                core::any::type_name::<T>(),
                 err
             ))
         })?;
@@ -64,7 +65,7 @@ where
         T::from_irc(irc).map_err(|err| {
             A::Error::custom(format!(
                 "cannot parse '{}' because: {}",
-                std::any::type_name::<T>(),
+                core::any::type_name::<T>(),
                 err
             ))
         })
@@ -74,8 +75,8 @@ where
 #[cfg(test)]
 pub(crate) fn round_trip_json<'a, T>(input: &'a str)
 where
-    T: FromIrcMessage<'a> + PartialEq + std::fmt::Debug,
-    T::Error: std::fmt::Debug,
+    T: FromIrcMessage<'a> + PartialEq + core::fmt::Debug,
+    T::Error: core::fmt::Debug,
     for<'de> T: ::serde::Serialize + ::serde::Deserialize<'de>,
 {
     let (_, msg) = crate::irc::parse_one(input).unwrap();
@@ -88,8 +89,8 @@ where
 #[cfg(test)]
 pub(crate) fn round_trip_rmp<'a, T>(input: &'a str)
 where
-    T: FromIrcMessage<'a> + PartialEq + std::fmt::Debug,
-    T::Error: std::fmt::Debug,
+    T: FromIrcMessage<'a> + PartialEq + core::fmt::Debug,
+    T::Error: core::fmt::Debug,
     for<'de> T: ::serde::Serialize + ::serde::Deserialize<'de>,
 {
     let (_, msg) = crate::irc::parse_one(input).unwrap();
@@ -0,0 +1,109 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+use crate::{runner::Clock, BoxedFuture};
+
+/// A [Clock] that only moves when you [advance](ManualClock::advance) it
+///
+/// Clones share the same time, so you can give one to the
+/// [AsyncRunner](crate::AsyncRunner) and advance another.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    now: Instant,
+    wakers: Vec<Waker>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    /// Create a new ManualClock, starting at the current time
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                now: Instant::now(),
+                wakers: Vec::new(),
+            })),
+        }
+    }
+
+    /// Move the clock forward by `duration`, completing any delays that have elapsed
+    pub fn advance(&self, duration: Duration) {
+        let wakers = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.now += duration;
+            std::mem::take(&mut inner.wakers)
+        };
+
+        // the pending delays will re-register themselves
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.inner.lock().unwrap().now
+    }
+
+    fn delay(&self, duration: Duration) -> BoxedFuture<()> {
+        Box::pin(Delay {
+            deadline: self.now() + duration,
+            inner: self.inner.clone(),
+        })
+    }
+}
+
+struct Delay {
+    deadline: Instant,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.now >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        inner.wakers.push(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_delay() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+
+        let mut delay = clock.delay(Duration::from_secs(10));
+        let mut poll =
+            || futures_lite::future::block_on(futures_lite::future::poll_once(&mut delay));
+
+        assert!(poll().is_none());
+        clock.advance(Duration::from_secs(5));
+        assert!(poll().is_none());
+
+        clock.clone().advance(Duration::from_secs(5));
+        assert!(poll().is_some());
+        assert_eq!(clock.now() - start, Duration::from_secs(10));
+    }
+}
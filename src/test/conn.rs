@@ -1,6 +1,6 @@
 use std::{
     future::Future,
-    io::{Error, ErrorKind, Result},
+    io::{Error, Result},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -42,11 +42,12 @@ impl TestConn {
 
     /// Write `data` to the underlying buffers.
     ///
-    /// Whatever uses `AsyncRead` on this type will read from this buffer
+    /// Whatever uses `AsyncRead` on this type will read from this buffer.
+    /// This is appended after any data that hasn't been read yet.
     pub async fn write_data(&self, data: impl AsRef<[u8]>) {
         let mut read = self.read.lock().await;
         let p = read.position();
-        read.write_all(data.as_ref()).await.unwrap();
+        read.get_mut().extend_from_slice(data.as_ref());
         read.set_position(p);
     }
 
@@ -54,7 +55,7 @@ impl TestConn {
     pub async fn read_all_lines(&self) -> Result<Vec<String>> {
         let data = take_cursor(&mut *self.write.lock().await);
         Ok(String::from_utf8(data)
-            .map_err(|err| Error::new(ErrorKind::Other, err))?
+            .map_err(Error::other)?
             .lines()
             .map(|s| format!("{}\r\n", s))
             .collect())
@@ -73,7 +74,7 @@ impl TestConn {
             line.extend_from_slice(&buf);
         }
 
-        String::from_utf8(line).map_err(|err| Error::new(ErrorKind::Other, err))
+        String::from_utf8(line).map_err(Error::other)
     }
 }
 
@@ -1,8 +1,17 @@
 mod str;
 pub use self::str::*;
 
-mod tags_builder;
-pub use tags_builder::{BuilderError, TagsBuilder, UserTags};
+cfg_std! { mod tags_builder; }
+cfg_std! { pub use tags_builder::{BuilderError, TagsBuilder, UserTags}; }
+
+cfg_std! { mod privmsg_builder; }
+cfg_std! { pub use privmsg_builder::PrivmsgBuilder; }
+
+cfg_std! { mod notice_builder; }
+cfg_std! { pub use notice_builder::NoticeBuilder; }
+
+cfg_std! { mod replay; }
+cfg_std! { pub use replay::{replay_reader, Replay}; }
 
 #[cfg(feature = "testing")]
 #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
@@ -11,3 +20,19 @@ mod conn;
 #[cfg(feature = "testing")]
 #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
 pub use conn::{TestConn, TestConnector};
+
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+mod clock;
+
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub use clock::ManualClock;
+
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+mod replay_source;
+
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub use replay_source::{Pace, ReplayConn, ReplaySource};
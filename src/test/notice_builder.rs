@@ -0,0 +1,188 @@
+use std::borrow::Cow;
+
+use super::{BuilderError, TagsBuilder};
+use crate::{commands::Channel, messages::Notice, FromIrcMessage as _, IrcMessage, MaybeOwned};
+
+/// A builder for a synthetic [Notice] -- this is useful for testing
+///
+/// This generates the raw IRC line internally, so the resulting message is
+/// indistinguishable from one received from the server.
+///
+/// ```rust
+/// use twitchchat::{messages::MessageId, test::NoticeBuilder};
+///
+/// let notice = NoticeBuilder::new("museun", "This room is now in slow mode.")
+///     .msg_id("slow_on")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(notice.channel(), "#museun");
+/// assert_eq!(notice.message(), "This room is now in slow mode.");
+/// assert_eq!(notice.msg_id(), Some(MessageId::SlowOn));
+/// ```
+///
+/// [Notice]: crate::messages::Notice
+#[derive(Debug, Clone)]
+pub struct NoticeBuilder<'a> {
+    channel: Cow<'a, str>,
+    message: Cow<'a, str>,
+    tags: TagsBuilder<'a>,
+}
+
+impl<'a> NoticeBuilder<'a> {
+    /// Create a new NoticeBuilder for this `channel` and `message`
+    ///
+    /// # NOTE
+    /// The channel will be normalized (e.g. `Museun` becomes `#museun`),
+    /// except for `*` which Twitch uses for notices that aren't for a channel
+    pub fn new<C, M>(channel: C, message: M) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+        M: Into<Cow<'a, str>>,
+    {
+        Self {
+            channel: channel.into(),
+            message: message.into(),
+            tags: TagsBuilder::new(),
+        }
+    }
+
+    /// Replace the channel
+    pub fn channel<C>(mut self, channel: C) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        self.channel = channel.into();
+        self
+    }
+
+    /// Replace the message text
+    pub fn message<M>(mut self, message: M) -> Self
+    where
+        M: Into<Cow<'a, str>>,
+    {
+        self.message = message.into();
+        self
+    }
+
+    /// Set the `msg-id` tag, e.g. `msg_banned`
+    pub fn msg_id<V>(self, msg_id: V) -> Self
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        self.tag("msg-id", msg_id)
+    }
+
+    /// Add this `key` with this `value` to the tags
+    ///
+    /// # NOTE
+    /// `key` will replace any previous keys
+    pub fn tag<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        self.tags = self.tags.add(key, value);
+        self
+    }
+
+    /// Build the raw line and parse it into a [Notice]
+    ///
+    /// # Errors
+    /// If the channel or message is empty, or an empty tag key was provided
+    /// then an error will be returned.
+    ///
+    /// [Notice]: crate::messages::Notice
+    pub fn build(self) -> Result<Notice<'static>, BuilderError> {
+        if self.channel.is_empty() {
+            return Err(BuilderError::EmptyChannel);
+        }
+        if self.message.is_empty() {
+            return Err(BuilderError::EmptyData);
+        }
+
+        let tags = match self.tags.build() {
+            Ok(tags) => format!("{} ", &*tags.data),
+            Err(BuilderError::EmptyTags) => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        let channel = match &*self.channel {
+            "*" => "*".to_string(),
+            channel => Channel::new(channel).to_string(),
+        };
+
+        let raw = format!(
+            "{tags}:tmi.twitch.tv NOTICE {channel} :{message}\r\n",
+            tags = tags,
+            channel = channel,
+            message = self.message,
+        );
+
+        IrcMessage::parse(MaybeOwned::Owned(raw.into_boxed_str()))
+            .and_then(Notice::from_irc)
+            .map_err(|_| BuilderError::InvalidMessage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{CommandError, MessageId};
+
+    #[test]
+    fn notice_builder() {
+        let notice = NoticeBuilder::new(
+            "Museun",
+            "You are permanently banned from talking in museun.",
+        )
+        .msg_id("msg_banned")
+        .build()
+        .unwrap();
+
+        assert_eq!(notice.channel(), "#museun");
+        assert_eq!(
+            notice.message(),
+            "You are permanently banned from talking in museun."
+        );
+        assert_eq!(notice.msg_id(), Some(MessageId::MsgBanned));
+        assert_eq!(notice.as_command_error(), Some(CommandError::Banned));
+
+        // it should look exactly like a parsed message
+        assert_eq!(
+            notice.raw(),
+            "@msg-id=msg_banned :tmi.twitch.tv NOTICE #museun :You are permanently banned from talking in museun.\r\n"
+        );
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(notice.raw())).unwrap();
+        assert_eq!(Notice::from_irc(msg).unwrap(), notice);
+    }
+
+    #[test]
+    fn notice_builder_no_channel() {
+        let notice = NoticeBuilder::new("*", "Login authentication failed")
+            .build()
+            .unwrap();
+        assert_eq!(
+            notice.raw(),
+            ":tmi.twitch.tv NOTICE * :Login authentication failed\r\n"
+        );
+        assert_eq!(notice.channel(), "*");
+        assert_eq!(notice.msg_id(), None);
+    }
+
+    #[test]
+    fn notice_builder_errors() {
+        assert!(matches!(
+            NoticeBuilder::new("", "hello").build(),
+            Err(BuilderError::EmptyChannel)
+        ));
+        assert!(matches!(
+            NoticeBuilder::new("museun", "").build(),
+            Err(BuilderError::EmptyData)
+        ));
+        assert!(matches!(
+            NoticeBuilder::new("museun", "hello").tag("", "foo").build(),
+            Err(BuilderError::EmptyKey)
+        ));
+    }
+}
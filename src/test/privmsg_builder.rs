@@ -0,0 +1,233 @@
+use std::borrow::Cow;
+
+use super::{BuilderError, TagsBuilder};
+use crate::{commands::Channel, messages::Privmsg, FromIrcMessage as _, IrcMessage, MaybeOwned};
+
+/// A builder for a synthetic [Privmsg] -- this is useful for testing
+///
+/// This generates the raw IRC line internally, so the resulting message is
+/// indistinguishable from one received from the server.
+///
+/// ```rust
+/// use twitchchat::test::PrivmsgBuilder;
+///
+/// let pm = PrivmsgBuilder::new("museun", "shaken_bot", "hello world")
+///     // badges are rendered into the 'badges' tag
+///     .badge("broadcaster", "1")
+///     .badge("subscriber", "6")
+///     // and any other tag can be added
+///     .tag("color", "#FF69B4")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(pm.channel(), "#museun");
+/// assert_eq!(pm.name(), "shaken_bot");
+/// assert_eq!(pm.data(), "hello world");
+/// assert!(pm.is_broadcaster());
+/// assert_eq!(pm.tags().get("color").unwrap(), "#FF69B4");
+/// ```
+///
+/// [Privmsg]: crate::messages::Privmsg
+#[derive(Debug, Clone)]
+pub struct PrivmsgBuilder<'a> {
+    channel: Cow<'a, str>,
+    sender: Cow<'a, str>,
+    data: Cow<'a, str>,
+    badges: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    tags: TagsBuilder<'a>,
+}
+
+impl<'a> PrivmsgBuilder<'a> {
+    /// Create a new PrivmsgBuilder for this `channel`, `sender` and `data`
+    ///
+    /// # NOTE
+    /// The channel will be normalized (e.g. `Museun` becomes `#museun`)
+    pub fn new<C, S, D>(channel: C, sender: S, data: D) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+        S: Into<Cow<'a, str>>,
+        D: Into<Cow<'a, str>>,
+    {
+        Self {
+            channel: channel.into(),
+            sender: sender.into(),
+            data: data.into(),
+            badges: Vec::new(),
+            tags: TagsBuilder::new(),
+        }
+    }
+
+    /// Replace the channel
+    pub fn channel<C>(mut self, channel: C) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        self.channel = channel.into();
+        self
+    }
+
+    /// Replace the sender
+    pub fn sender<S>(mut self, sender: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.sender = sender.into();
+        self
+    }
+
+    /// Replace the message body
+    pub fn data<D>(mut self, data: D) -> Self
+    where
+        D: Into<Cow<'a, str>>,
+    {
+        self.data = data.into();
+        self
+    }
+
+    /// Add a badge with this `name` and `version`
+    ///
+    /// # NOTE
+    /// If any badges are added, they'll replace a `badges` tag set with [PrivmsgBuilder::tag]
+    pub fn badge<K, V>(mut self, name: K, version: V) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        self.badges.push((name.into(), version.into()));
+        self
+    }
+
+    /// Add this `key` with this `value` to the tags
+    ///
+    /// # NOTE
+    /// `key` will replace any previous keys
+    pub fn tag<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        self.tags = self.tags.add(key, value);
+        self
+    }
+
+    /// Build the raw line and parse it into a [Privmsg]
+    ///
+    /// # Errors
+    /// If the channel, sender or data is empty, or an empty tag key was
+    /// provided then an error will be returned.
+    ///
+    /// [Privmsg]: crate::messages::Privmsg
+    pub fn build(self) -> Result<Privmsg<'static>, BuilderError> {
+        if self.channel.is_empty() {
+            return Err(BuilderError::EmptyChannel);
+        }
+        if self.sender.is_empty() {
+            return Err(BuilderError::EmptySender);
+        }
+        if self.data.is_empty() {
+            return Err(BuilderError::EmptyData);
+        }
+
+        let mut tags = self.tags;
+        if !self.badges.is_empty() {
+            let badges = self
+                .badges
+                .iter()
+                .map(|(name, version)| format!("{}/{}", name, version))
+                .collect::<Vec<_>>()
+                .join(",");
+            tags = tags.add("badges", badges);
+        }
+
+        let tags = match tags.build() {
+            Ok(tags) => format!("{} ", &*tags.data),
+            Err(BuilderError::EmptyTags) => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        let raw = format!(
+            "{tags}:{name}!{name}@{name}.tmi.twitch.tv PRIVMSG {channel} :{data}\r\n",
+            tags = tags,
+            name = self.sender,
+            channel = Channel::new(&self.channel),
+            data = self.data,
+        );
+
+        IrcMessage::parse(MaybeOwned::Owned(raw.into_boxed_str()))
+            .and_then(Privmsg::from_irc)
+            .map_err(|_| BuilderError::InvalidMessage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twitch::{color::RGB, BadgeKind};
+
+    #[test]
+    fn privmsg_builder() {
+        let pm = PrivmsgBuilder::new("Museun", "shaken_bot", "hello world Kappa")
+            .badge("moderator", "1")
+            .badge("subscriber", "12")
+            .tag("color", "#FF69B4")
+            .tag("display-name", "Shaken_Bot")
+            .tag("emotes", "25:12-16")
+            .build()
+            .unwrap();
+
+        assert_eq!(pm.channel(), "#museun");
+        assert_eq!(pm.name(), "shaken_bot");
+        assert_eq!(pm.data(), "hello world Kappa");
+        assert_eq!(pm.display_name().unwrap(), "Shaken_Bot");
+        assert_eq!(pm.color().unwrap().rgb, RGB(0xFF, 0x69, 0xB4));
+
+        assert!(pm.is_moderator());
+        assert!(!pm.is_broadcaster());
+
+        let badges = pm.iter_badges().map(|b| b.kind).collect::<Vec<_>>();
+        assert_eq!(badges, vec![BadgeKind::Moderator, BadgeKind::Subscriber]);
+
+        let emotes = pm.iter_emotes().collect::<Vec<_>>();
+        assert_eq!(emotes.len(), 1);
+        assert_eq!(emotes[0].id, 25);
+        assert_eq!(emotes[0].ranges, vec![12..16]);
+
+        // it should look exactly like a parsed message
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(pm.raw())).unwrap();
+        assert_eq!(Privmsg::from_irc(msg).unwrap(), pm);
+    }
+
+    #[test]
+    fn privmsg_builder_no_tags() {
+        let pm = PrivmsgBuilder::new("#museun", "museun", "hello")
+            .build()
+            .unwrap();
+        assert_eq!(
+            pm.raw(),
+            ":museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n"
+        );
+        assert!(pm.tags().is_empty());
+    }
+
+    #[test]
+    fn privmsg_builder_errors() {
+        assert!(matches!(
+            PrivmsgBuilder::new("", "museun", "hello").build(),
+            Err(BuilderError::EmptyChannel)
+        ));
+        assert!(matches!(
+            PrivmsgBuilder::new("museun", "", "hello").build(),
+            Err(BuilderError::EmptySender)
+        ));
+        assert!(matches!(
+            PrivmsgBuilder::new("museun", "museun", "").build(),
+            Err(BuilderError::EmptyData)
+        ));
+        assert!(matches!(
+            PrivmsgBuilder::new("museun", "museun", "hello")
+                .tag("", "foo")
+                .build(),
+            Err(BuilderError::EmptyKey)
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    time::Duration,
+};
+
+use crate::{
+    messages::Commands, DecodeError, Decoder, FromIrcMessage as _, IrcMessage,
+};
+
+/// Replay a captured log of raw messages from a file
+///
+/// This is [Replay::new] over the file, see it for the details.
+///
+/// ```no_run
+/// use twitchchat::messages::Commands;
+///
+/// let replay = twitchchat::test::replay_reader("chat.log")
+///     .unwrap()
+///     .at_speed(10.0);
+///
+/// for msg in replay {
+///     if let Commands::Privmsg(pm) = msg.unwrap() {
+///         println!("{}: {}", pm.name(), pm.data());
+///     }
+/// }
+/// ```
+pub fn replay_reader(path: impl AsRef<Path>) -> std::io::Result<Replay<BufReader<File>>> {
+    File::open(path).map(BufReader::new).map(Replay::new)
+}
+
+/// An iterator that replays raw messages from a `Read`er, producing [Commands]
+///
+/// The input is what you'd read from the connection: one message per line.
+///
+/// By default the messages are produced as fast as you read them. With
+/// [Replay::at_speed] it'll wait between messages using their `tmi-sent-ts`
+/// tags, so they're produced at the pace they were originally sent.
+///
+/// Messages without a `tmi-sent-ts` (e.g. `PING`) don't wait. If a timestamp
+/// is earlier than one already replayed, it doesn't wait either.
+pub struct Replay<R> {
+    decoder: Decoder<R>,
+    speed: Option<f64>,
+    last_sent: Option<u64>,
+    sleep: Box<dyn FnMut(Duration) + Send>,
+}
+
+impl<R> std::fmt::Debug for Replay<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Replay")
+            .field("speed", &self.speed)
+            .field("last_sent", &self.last_sent)
+            .finish()
+    }
+}
+
+impl<R: Read> Replay<R> {
+    /// Replay the messages read from `reader`
+    pub fn new(reader: R) -> Self {
+        Self {
+            decoder: Decoder::new(reader),
+            speed: None,
+            last_sent: None,
+            sleep: Box::new(std::thread::sleep),
+        }
+    }
+
+    /// Honor the `tmi-sent-ts` timestamps, scaled by `speed`
+    ///
+    /// A speed of `1.0` is the original pace, `2.0` is twice as fast and `0.5` is half as fast.
+    ///
+    /// # Panics
+    /// If `speed` isn't a positive, finite number
+    pub fn at_speed(mut self, speed: f64) -> Self {
+        assert!(
+            speed.is_finite() && speed > 0.0,
+            "speed must be a positive number"
+        );
+        self.speed.replace(speed);
+        self
+    }
+
+    /// Use `sleep` to wait between messages, rather than [std::thread::sleep]
+    ///
+    /// This is useful for testing the timing without actually waiting.
+    pub fn with_sleep(mut self, sleep: impl FnMut(Duration) + Send + 'static) -> Self {
+        self.sleep = Box::new(sleep);
+        self
+    }
+
+    /// Consume the replay, returning the inner Reader
+    pub fn into_inner(self) -> R {
+        self.decoder.into_inner()
+    }
+
+    fn wait_for(&mut self, msg: &IrcMessage<'_>) {
+        let speed = match self.speed {
+            Some(speed) => speed,
+            None => return,
+        };
+
+        let sent = match sent_ts(msg) {
+            Some(sent) => sent,
+            None => return,
+        };
+
+        let last = self.last_sent.replace(sent.max(self.last_sent.unwrap_or(sent)));
+        let elapsed = match last {
+            Some(last) if sent > last => sent - last,
+            _ => return,
+        };
+
+        (self.sleep)(Duration::from_millis(elapsed).div_f64(speed))
+    }
+}
+
+/// This will produce `Result<Commands<'static>, DecodeError>` until an `Eof` is received
+impl<R: Read> Iterator for Replay<R> {
+    type Item = Result<Commands<'static>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let msg = match self.decoder.next()? {
+            Ok(msg) => msg,
+            Err(err) => return Some(Err(err)),
+        };
+
+        self.wait_for(&msg);
+        Some(Commands::from_irc(msg).map_err(DecodeError::CommandError))
+    }
+}
+
+pub(super) fn sent_ts(msg: &IrcMessage<'_>) -> Option<u64> {
+    msg.get_tags()?
+        .trim_start_matches('@')
+        .split(';')
+        .find_map(|tag| tag.strip_prefix("tmi-sent-ts="))?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    const FIXTURE: &str = "\
+        @tmi-sent-ts=1000 :a!a@a PRIVMSG #museun :first\r\n\
+        PING :tmi.twitch.tv\r\n\
+        @tmi-sent-ts=1500 :b!b@b PRIVMSG #museun :second\r\n\
+        @login=a;tmi-sent-ts=3500 :tmi.twitch.tv CLEARCHAT #museun :a\r\n\
+        @tmi-sent-ts=3000 :c!c@c PRIVMSG #museun :late\r\n\
+        @tmi-sent-ts=3600 :d!d@d PRIVMSG #museun :last\r\n";
+
+    fn summary(msg: Commands<'_>) -> String {
+        match msg {
+            Commands::Privmsg(pm) => pm.data().to_string(),
+            Commands::Ping(..) => "PING".to_string(),
+            Commands::ClearChat(..) => "CLEARCHAT".to_string(),
+            msg => panic!("unexpected message: {:?}", msg),
+        }
+    }
+
+    #[test]
+    fn replay_at_speed() {
+        let sleeps = Arc::new(Mutex::new(Vec::new()));
+
+        let replay = Replay::new(FIXTURE.as_bytes()).at_speed(2.0).with_sleep({
+            let sleeps = sleeps.clone();
+            move |dur| sleeps.lock().unwrap().push(dur)
+        });
+
+        let order = replay
+            .map(Result::unwrap)
+            .map(summary)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            ["first", "PING", "second", "CLEARCHAT", "late", "last"]
+        );
+
+        // the PING and the out of order message don't wait
+        assert_eq!(
+            *sleeps.lock().unwrap(),
+            [
+                Duration::from_millis(250),
+                Duration::from_millis(1000),
+                Duration::from_millis(50),
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_without_speed() {
+        let replay = Replay::new(FIXTURE.as_bytes())
+            .with_sleep(|dur| panic!("should not have waited for {:?}", dur));
+        assert_eq!(replay.map(Result::unwrap).count(), 6);
+    }
+
+    #[test]
+    fn replay_reader_file() {
+        let path = std::env::temp_dir().join(format!(
+            "twitchchat-replay-{}.log",
+            std::process::id()
+        ));
+        std::fs::write(&path, FIXTURE).unwrap();
+
+        let order = replay_reader(&path)
+            .unwrap()
+            .map(Result::unwrap)
+            .map(summary)
+            .collect::<Vec<_>>();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(order.first().map(|s| &**s), Some("first"));
+        assert_eq!(order.last().map(|s| &**s), Some("last"));
+    }
+}
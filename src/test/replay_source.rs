@@ -0,0 +1,344 @@
+use std::{
+    collections::VecDeque,
+    io::Result,
+    pin::Pin,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_lite::{AsyncRead, AsyncWrite};
+
+use crate::{
+    connector::Connector,
+    runner::{Clock, SystemClock},
+    BoxedFuture,
+};
+
+/// How fast a [ReplaySource] produces its lines
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Pace {
+    /// All of the lines are available immediately
+    Instant,
+    /// Wait this long before each line
+    Interval(Duration),
+    /// Wait between lines using their `tmi-sent-ts` tags, scaled by `speed`
+    ///
+    /// A speed of `1.0` is the original pace and `2.0` is twice as fast.
+    /// Lines without a timestamp, or with one earlier than what's already
+    /// been replayed, don't wait.
+    Original {
+        /// How much faster than the original pace to replay
+        speed: f64,
+    },
+}
+
+/// A [Connector] that replays a recorded session
+///
+/// The log is what you'd read from the connection: one raw message per line.
+/// Each time this connects, the session is replayed from the start. Anything
+/// written to the connection is discarded, and once the log has been replayed
+/// the connection reaches EOF.
+///
+/// ```rust
+/// # futures_lite::future::block_on(async {
+/// use twitchchat::{messages::Commands, test::ReplaySource, AsyncRunner, Status, UserConfig};
+///
+/// let log = "\
+///     :tmi.twitch.tv 001 justinfan1234 :Welcome, GLHF!\r\n\
+///     :tmi.twitch.tv 376 justinfan1234 :>\r\n\
+///     :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n";
+///
+/// let user_config = UserConfig::builder().anonymous().build().unwrap();
+/// let mut runner = AsyncRunner::connect(ReplaySource::new(log), &user_config)
+///     .await
+///     .unwrap();
+///
+/// // the handshake messages are produced as well
+/// while let Status::Message(msg) = runner.next_message().await.unwrap() {
+///     if let Commands::Privmsg(pm) = msg {
+///         assert_eq!(pm.data(), "hello");
+///     }
+/// }
+/// # });
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplaySource {
+    lines: Arc<[Box<str>]>,
+    pace: Pace,
+    clock: Arc<dyn Clock>,
+}
+
+impl FromStr for ReplaySource {
+    type Err = std::convert::Infallible;
+
+    fn from_str(log: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self::new(log))
+    }
+}
+
+impl ReplaySource {
+    /// Create a source replaying this `log`, with all of the lines available immediately
+    ///
+    /// Empty lines are skipped, and a `\r\n` is added to any line without one.
+    pub fn new(log: &str) -> Self {
+        let lines = log
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.is_empty())
+            .map(|line| format!("{}\r\n", line).into_boxed_str())
+            .collect();
+
+        Self {
+            lines,
+            pace: Pace::Instant,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Produce the lines at this [Pace]
+    pub fn pace(mut self, pace: Pace) -> Self {
+        if let Pace::Original { speed } = pace {
+            assert!(
+                speed.is_finite() && speed > 0.0,
+                "speed must be a positive number"
+            );
+        }
+        self.pace = pace;
+        self
+    }
+
+    /// Use this [Clock] for the pacing, rather than the [SystemClock]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+}
+
+impl Connector for ReplaySource {
+    type Output = ReplayConn;
+
+    fn connect(&mut self) -> BoxedFuture<Result<Self::Output>> {
+        let conn = ReplayConn {
+            inner: Arc::new(Mutex::new(Inner {
+                lines: self.lines.iter().cloned().collect(),
+                current: None,
+                last_sent: None,
+                delay: None,
+                pace: self.pace,
+                clock: self.clock.clone(),
+            })),
+        };
+        Box::pin(async move { Ok(conn) })
+    }
+}
+
+/// A connection to a [ReplaySource]
+#[derive(Debug, Clone)]
+pub struct ReplayConn {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    lines: VecDeque<Box<str>>,
+    current: Option<(Box<str>, usize)>,
+    last_sent: Option<u64>,
+    delay: Option<BoxedFuture<()>>,
+    pace: Pace,
+    clock: Arc<dyn Clock>,
+}
+
+impl std::fmt::Debug for Inner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Inner")
+            .field("remaining", &self.lines.len())
+            .field("pace", &self.pace)
+            .finish()
+    }
+}
+
+impl Inner {
+    fn next_delay(&mut self, line: &str) -> Option<Duration> {
+        match self.pace {
+            Pace::Instant => None,
+            Pace::Interval(interval) => Some(interval),
+            Pace::Original { speed } => {
+                let msg = crate::irc::parse_one(line).ok()?.1;
+                let sent = super::replay::sent_ts(&msg)?;
+                let last = self
+                    .last_sent
+                    .replace(sent.max(self.last_sent.unwrap_or(sent)))?;
+                let elapsed = sent.checked_sub(last).filter(|&n| n > 0)?;
+                Some(Duration::from_millis(elapsed).div_f64(speed))
+            }
+        }
+    }
+
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        loop {
+            if let Some((line, pos)) = &mut self.current {
+                let rest = &line.as_bytes()[*pos..];
+                let n = rest.len().min(buf.len());
+                buf[..n].copy_from_slice(&rest[..n]);
+                *pos += n;
+                if *pos == line.len() {
+                    self.current.take();
+                }
+                return Poll::Ready(Ok(n));
+            }
+
+            let line = match self.lines.front() {
+                Some(line) => line.clone(),
+                None => return Poll::Ready(Ok(0)),
+            };
+
+            if self.delay.is_none() {
+                if let Some(delay) = self.next_delay(&line) {
+                    self.delay.replace(self.clock.delay(delay));
+                }
+            }
+
+            if let Some(delay) = &mut self.delay {
+                futures_lite::ready!(delay.as_mut().poll(cx));
+                self.delay.take();
+            }
+
+            self.lines.pop_front();
+            self.current.replace((line, 0));
+        }
+    }
+}
+
+macro_rules! impls {
+    ($($ty:ty)*) => {
+        $(
+        impl AsyncRead for $ty {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<Result<usize>> {
+                self.inner.lock().unwrap().poll_read(cx, buf)
+            }
+        }
+
+        impl AsyncWrite for $ty {
+            fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+                Poll::Ready(Ok(buf.len()))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+        )*
+    };
+}
+
+impls! {
+    &ReplayConn
+    ReplayConn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{messages::Commands, test::ManualClock, AsyncRunner, Status, UserConfig};
+    use futures_lite::{future, AsyncBufReadExt as _, StreamExt as _};
+
+    const SESSION: &str = "\
+        :tmi.twitch.tv 001 justinfan1234 :Welcome, GLHF!\r\n\
+        :tmi.twitch.tv 376 justinfan1234 :>\r\n\
+        @tmi-sent-ts=1000 :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :hello\r\n\
+        \r\n\
+        @login=museun;tmi-sent-ts=1500 :tmi.twitch.tv CLEARCHAT #museun :museun\n\
+        @tmi-sent-ts=2500 :shaken_bot!shaken_bot@shaken_bot.tmi.twitch.tv PRIVMSG #museun :world\r\n";
+
+    #[test]
+    fn replay_session() {
+        future::block_on(async move {
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner =
+                AsyncRunner::connect(SESSION.parse::<ReplaySource>().unwrap(), &user_config)
+                    .await
+                    .unwrap();
+            assert_eq!(runner.identity.username(), "justinfan1234");
+
+            let mut messages = vec![];
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::Privmsg(pm)) => {
+                        messages.push(format!("{}: {}", pm.name(), pm.data()))
+                    }
+                    Status::Message(Commands::ClearChat(cc)) => {
+                        messages.push(format!("cleared {}", cc.name().unwrap()))
+                    }
+                    // the handshake is replayed too
+                    Status::Message(Commands::IrcReady(..))
+                    | Status::Message(Commands::Ready(..)) => {}
+                    Status::Message(msg) => panic!("unexpected message: {:?}", msg),
+                    Status::Eof => break,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+
+            assert_eq!(
+                messages,
+                ["museun: hello", "cleared museun", "shaken_bot: world"]
+            );
+        });
+    }
+
+    #[test]
+    fn replay_paced() {
+        fn poll_line(
+            lines: &mut (impl futures_lite::Stream<Item = Result<String>> + Unpin),
+        ) -> Option<String> {
+            future::block_on(future::poll_once(lines.next())).map(|line| line.unwrap().unwrap())
+        }
+
+        let tests = [
+            (
+                Pace::Interval(Duration::from_secs(1)),
+                [Duration::from_secs(1); 5],
+            ),
+            (
+                Pace::Original { speed: 2.0 },
+                [
+                    Duration::from_secs(0),
+                    Duration::from_secs(0),
+                    Duration::from_secs(0),
+                    Duration::from_millis(250),
+                    Duration::from_millis(500),
+                ],
+            ),
+        ];
+
+        for (pace, waits) in tests.iter().copied() {
+            let clock = ManualClock::new();
+            let mut source = ReplaySource::new(SESSION).pace(pace).clock(clock.clone());
+            let conn = future::block_on(source.connect()).unwrap();
+            let mut lines = futures_lite::io::BufReader::new(conn).lines();
+
+            let mut read = vec![];
+            for wait in waits.iter().copied() {
+                if wait > Duration::from_secs(0) {
+                    assert!(poll_line(&mut lines).is_none(), "{:?}", pace);
+                    clock.advance(wait - Duration::from_millis(1));
+                    assert!(poll_line(&mut lines).is_none(), "{:?}", pace);
+                    clock.advance(Duration::from_millis(1));
+                }
+                read.push(poll_line(&mut lines).unwrap());
+            }
+            assert!(read[4].ends_with(":world"), "{:?}", pace);
+
+            // and then it's done
+            assert!(future::block_on(lines.next()).is_none());
+        }
+    }
+}
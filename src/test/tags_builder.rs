@@ -7,12 +7,20 @@ use crate::MaybeOwned;
 #[derive(Debug)]
 #[non_exhaustive]
 #[allow(missing_copy_implementations)]
-/// An error returned by the Tags builder
+/// An error returned by the Tags and Privmsg builders
 pub enum BuilderError {
     /// An empty key was provided
     EmptyKey,
     /// An empty set of tags was provided
     EmptyTags,
+    /// An empty channel was provided
+    EmptyChannel,
+    /// An empty sender was provided
+    EmptySender,
+    /// An empty message body was provided
+    EmptyData,
+    /// The built message could not be parsed
+    InvalidMessage,
 }
 
 impl std::fmt::Display for BuilderError {
@@ -20,6 +28,10 @@ impl std::fmt::Display for BuilderError {
         match self {
             Self::EmptyKey => f.write_str("an empty key was provided"),
             Self::EmptyTags => f.write_str("an empty set of tags was provided"),
+            Self::EmptyChannel => f.write_str("an empty channel was provided"),
+            Self::EmptySender => f.write_str("an empty sender was provided"),
+            Self::EmptyData => f.write_str("an empty message body was provided"),
+            Self::InvalidMessage => f.write_str("the built message could not be parsed"),
         }
     }
 }
@@ -183,7 +195,7 @@ mod tests {
             (r"the_win_end\r", r"the_win_end\\r"),
         ];
         for (input, expected) in tests {
-            assert_eq!(tags::escape_str(*input), *expected)
+            assert_eq!(tags::escape_str(input), *expected)
         }
 
         let tests = &["dont_escape+me", "foo=1234"];
@@ -236,7 +248,7 @@ mod tests {
         use crate::FromIrcMessage as _;
 
         let msg = "@badge-info=;badges=broadcaster/1;color=#FF69B4;display-name=museun;emote-only=1;emotes=25:0-4,6-10/81274:12-17;flags=;id=4e160a53-5482-4764-ba28-f224cd59a51f;mod=0;room-id=23196011;subscriber=0;tmi-sent-ts=1601079032426;turbo=0;user-id=23196011;user-type= :museun!museun@museun.tmi.twitch.tv PRIVMSG #museun :Kappa Kappa VoHiYo\r\n";
-        let msg = crate::IrcMessage::parse(crate::MaybeOwned::Borrowed(msg)).unwrap();
+        let msg = crate::IrcMessage::parse(MaybeOwned::Borrowed(msg)).unwrap();
         let pm = crate::messages::Privmsg::from_irc(msg).unwrap();
         let tags = pm.tags();
 
@@ -20,6 +20,8 @@ pub enum BadgeKind<'a> {
     Moderator,
     /// Subscriber badge
     Subscriber,
+    /// Founder badge, for the channel's earliest subscribers
+    Founder,
     /// Staff badge
     Staff,
     /// Turbo badge
@@ -34,6 +36,110 @@ pub enum BadgeKind<'a> {
     Unknown(&'a str),
 }
 
+/// A single role a user can have, see [Roles]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum Role {
+    /// The user is the broadcaster
+    Broadcaster,
+    /// The user is a moderator
+    Moderator,
+    /// The user is a global moderator
+    GlobalModerator,
+    /// The user is a vip
+    Vip,
+    /// The user is a subscriber
+    ///
+    /// This includes founders
+    Subscriber,
+    /// The user is a founder, one of the channel's earliest subscribers
+    Founder,
+    /// The user is a staff member
+    Staff,
+    /// The user has turbo
+    Turbo,
+    /// The user is a partner
+    Partner,
+}
+
+/// The roles a user has, derived from their [badges]
+///
+/// [badges]: Badge
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Roles {
+    /// The user is the broadcaster
+    pub broadcaster: bool,
+    /// The user is a moderator
+    pub moderator: bool,
+    /// The user is a global moderator
+    pub global_moderator: bool,
+    /// The user is a vip
+    pub vip: bool,
+    /// The user is a subscriber
+    ///
+    /// Twitch treats founders as subscribers, so this is also set for them
+    pub subscriber: bool,
+    /// The user is a founder, one of the channel's earliest subscribers
+    pub founder: bool,
+    /// The user is a staff member
+    pub staff: bool,
+    /// The user has turbo
+    pub turbo: bool,
+    /// The user is a partner
+    pub partner: bool,
+}
+
+impl Roles {
+    /// Get the roles from these badges, in a single pass
+    pub fn from_badges<'a, I>(badges: I) -> Self
+    where
+        I: IntoIterator<Item = Badge<'a>>,
+    {
+        badges
+            .into_iter()
+            .fold(Self::default(), |mut roles, badge| {
+                match badge.kind {
+                    BadgeKind::Broadcaster => roles.broadcaster = true,
+                    BadgeKind::Moderator => roles.moderator = true,
+                    BadgeKind::GlobalMod => roles.global_moderator = true,
+                    BadgeKind::VIP => roles.vip = true,
+                    BadgeKind::Subscriber => roles.subscriber = true,
+                    BadgeKind::Founder => {
+                        roles.founder = true;
+                        roles.subscriber = true;
+                    }
+                    BadgeKind::Staff => roles.staff = true,
+                    BadgeKind::Turbo => roles.turbo = true,
+                    BadgeKind::Partner => roles.partner = true,
+                    _ => {}
+                }
+                roles
+            })
+    }
+
+    /// Whether this contains the [Role]
+    pub const fn has(&self, role: Role) -> bool {
+        match role {
+            Role::Broadcaster => self.broadcaster,
+            Role::Moderator => self.moderator,
+            Role::GlobalModerator => self.global_moderator,
+            Role::Vip => self.vip,
+            Role::Subscriber => self.subscriber,
+            Role::Founder => self.founder,
+            Role::Staff => self.staff,
+            Role::Turbo => self.turbo,
+            Role::Partner => self.partner,
+        }
+    }
+
+    /// Whether this contains any of the [roles](Role)
+    pub fn has_any(&self, roles: &[Role]) -> bool {
+        roles.iter().any(|&role| self.has(role))
+    }
+}
+
 /// Badges attached to a message
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
@@ -62,6 +168,7 @@ impl<'a> Badge<'a> {
             "global_mod" => GlobalMod,
             "moderator" => Moderator,
             "subscriber" => Subscriber,
+            "founder" => Founder,
             "staff" => Staff,
             "turbo" => Turbo,
             "premium" => Premium,
@@ -85,6 +192,7 @@ impl<'a> Badge<'a> {
             GlobalMod => "global_mod",
             Moderator => "moderator",
             Subscriber => "subscriber",
+            Founder => "founder",
             Staff => "staff",
             Turbo => "turbo",
             Premium => "premium",
@@ -93,6 +201,18 @@ impl<'a> Badge<'a> {
             Unknown(s) => s,
         }
     }
+
+    /// The [`BadgeKind`] of this badge
+    pub const fn kind(&self) -> &BadgeKind<'a> {
+        &self.kind
+    }
+
+    /// The version of this badge
+    ///
+    /// This is the same value as [`Badge::data`]
+    pub const fn version(&self) -> &'a str {
+        self.data
+    }
 }
 
 /// Metadata to the chat badges
@@ -112,6 +232,7 @@ mod tests {
             ("global_mod", BadgeKind::GlobalMod),
             ("moderator", BadgeKind::Moderator),
             ("subscriber", BadgeKind::Subscriber),
+            ("founder", BadgeKind::Founder),
             ("staff", BadgeKind::Staff),
             ("turbo", BadgeKind::Turbo),
             ("premium", BadgeKind::Premium),
@@ -145,6 +266,51 @@ mod tests {
         assert_eq!(badge.kind_raw(), "this_badge_does_not_exist")
     }
 
+    #[test]
+    fn parse_badge_set_in_order() {
+        let input = "broadcaster/1,subscriber/3012,my_custom_badge/42,glhf-pledge/1,premium/1";
+        let badges = crate::twitch::parse_badges(input);
+
+        let expected: &[(BadgeKind<'_>, &str)] = &[
+            (BadgeKind::Broadcaster, "1"),
+            (BadgeKind::Subscriber, "3012"),
+            (BadgeKind::Unknown("my_custom_badge"), "42"),
+            (BadgeKind::Unknown("glhf-pledge"), "1"),
+            (BadgeKind::Premium, "1"),
+        ];
+
+        assert_eq!(badges.len(), expected.len());
+        for (badge, (kind, version)) in badges.iter().zip(expected) {
+            assert_eq!(badge.kind(), kind);
+            assert_eq!(badge.version(), *version);
+        }
+    }
+
+    #[test]
+    fn roles_has_any() {
+        let badges = crate::twitch::parse_badges("moderator/1,subscriber/12");
+        let roles = Roles::from_badges(badges);
+
+        assert!(roles.has(Role::Moderator));
+        assert!(roles.has(Role::Subscriber));
+        assert!(!roles.has(Role::Vip));
+
+        assert!(roles.has_any(&[Role::Broadcaster, Role::Moderator]));
+        assert!(!roles.has_any(&[Role::Broadcaster, Role::Vip]));
+        assert!(!roles.has_any(&[]));
+    }
+
+    #[test]
+    fn roles_founder_is_subscriber() {
+        let roles = Roles::from_badges(crate::twitch::parse_badges("founder/0"));
+        assert!(roles.has(Role::Founder));
+        assert!(roles.has(Role::Subscriber));
+
+        let roles = Roles::from_badges(crate::twitch::parse_badges("subscriber/12"));
+        assert!(!roles.has(Role::Founder));
+        assert!(roles.has(Role::Subscriber));
+    }
+
     #[test]
     fn parse_invalid() {
         let badge_str = "this_badge_is_invalid";
@@ -20,6 +20,15 @@ pub enum Capability {
 }
 
 impl Capability {
+    /// The name of this capability, e.g. `twitch.tv/tags`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Membership => "twitch.tv/membership",
+            Self::Tags => "twitch.tv/tags",
+            Self::Commands => "twitch.tv/commands",
+        }
+    }
+
     /// Encode this capability as a string, to be sent to the server
     pub fn encode_as_str(self) -> &'static str {
         match self {
@@ -29,6 +38,14 @@ impl Capability {
         }
     }
 
+    /// Whether this capability only makes sense for an authenticated (non-anonymous) login
+    ///
+    /// Twitch will acknowledge [Capability::Commands] for an anonymous login,
+    /// but it won't send the messages (e.g. `USERSTATE`) that it enables.
+    pub fn requires_auth(self) -> bool {
+        matches!(self, Self::Commands)
+    }
+
     /// Attempts to 'parse' this capability from a string
     ///
     /// This will take the form of `twitch.tv/$tag` and produce a [Capability]
@@ -0,0 +1,64 @@
+use alloc::{borrow::Cow, format};
+
+/// Normalize a channel name the way Twitch does, e.g. `Museun` becomes `#museun`
+///
+/// This lowercases the name and ensures it has a leading `#`. Normalizing an already normalized name returns it as-is, without allocating.
+///
+/// ```rust
+/// use twitchchat::twitch::normalize_channel;
+///
+/// assert_eq!(normalize_channel("Museun"), "#museun");
+/// assert_eq!(normalize_channel("#Museun"), "#museun");
+/// assert_eq!(normalize_channel("#museun"), "#museun");
+/// ```
+pub fn normalize_channel(channel: &str) -> Cow<'_, str> {
+    // TODO IRC has special casing rules. We should implement it
+    let has_prefix = channel.starts_with('#');
+    if has_prefix && !channel.chars().any(char::is_uppercase) {
+        return Cow::Borrowed(channel);
+    }
+
+    let channel = channel.to_lowercase();
+    if has_prefix {
+        Cow::Owned(channel)
+    } else {
+        Cow::Owned(format!("#{}", channel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_channel("museun"), "#museun");
+        assert_eq!(normalize_channel("#museun"), "#museun");
+        assert_eq!(normalize_channel("Museun"), "#museun");
+        assert_eq!(normalize_channel("#Museun"), "#museun");
+        assert_eq!(normalize_channel("MUSEUN"), "#museun");
+        assert_eq!(normalize_channel("#MUSEUN"), "#museun");
+    }
+
+    #[test]
+    fn normalize_borrows_when_correct() {
+        let input = "#museun";
+        match normalize_channel(input) {
+            Cow::Borrowed(s) => assert!(std::ptr::eq(s, input)),
+            Cow::Owned(..) => panic!("an already normalized name should not allocate"),
+        }
+
+        assert!(matches!(normalize_channel("#MUSEUN"), Cow::Owned(..)));
+        assert!(matches!(normalize_channel("museun"), Cow::Owned(..)));
+    }
+
+    #[test]
+    fn normalize_idempotent() {
+        for input in &["museun", "#museun", "Museun", "#MuSeUn"] {
+            let once = normalize_channel(input);
+            let twice = normalize_channel(&once);
+            assert_eq!(once, twice);
+            assert!(matches!(twice, Cow::Borrowed(..)));
+        }
+    }
+}
@@ -57,7 +57,8 @@ let rgb: RGB = twitch_color.into();
 ```
 */
 
-use std::{convert::TryFrom, str::FromStr};
+use alloc::string::ToString;
+use core::{convert::TryFrom, str::FromStr};
 
 /// An error returned when trying to parse a string as an RGB triplet
 #[non_exhaustive]
@@ -69,8 +70,8 @@ pub enum ParseError {
     UnknownColor,
 }
 
-impl std::fmt::Display for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::InvalidHexString => f.write_str("invalid hex string"),
             Self::UnknownColor => f.write_str("unknown color"),
@@ -78,7 +79,7 @@ impl std::fmt::Display for ParseError {
     }
 }
 
-impl std::error::Error for ParseError {}
+impl core::error::Error for ParseError {}
 
 impl FromStr for RGB {
     type Err = ParseError;
@@ -114,8 +115,8 @@ impl Default for RGB {
     }
 }
 
-impl std::fmt::Display for RGB {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for RGB {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let Self(r, g, b) = self;
         write!(f, "#{:02X}{:02X}{:02X}", r, g, b)
     }
@@ -198,7 +199,7 @@ These can be [parsed] from their **name** in
 - `"snake_case"`
 - `"lower case"`
 
-[parsed]: std::str::FromStr
+[parsed]: core::str::FromStr
 */
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Ord, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
@@ -255,6 +256,138 @@ impl FromStr for Color {
     }
 }
 
+// the float math here needs `std`
+#[cfg(any(not(feature = "no_std"), feature = "async"))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "no_std"))))]
+impl Color {
+    /// The relative luminance of this color, from `0.0` (black) to `1.0` (white)
+    ///
+    /// This uses the standard sRGB relative luminance formula
+    pub fn luminance(self) -> f32 {
+        fn linear(c: u8) -> f32 {
+            let c = f32::from(c) / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+
+        let RGB(r, g, b) = self.rgb;
+        0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+    }
+
+    /// The contrast ratio between this color and `other`, from `1.0` to `21.0`
+    ///
+    /// The order doesn't matter, `a.contrast_ratio(b) == b.contrast_ratio(a)`
+    pub fn contrast_ratio(self, other: Self) -> f32 {
+        let (a, b) = (self.luminance(), other.luminance());
+        let (hi, lo) = if a > b { (a, b) } else { (b, a) };
+        (hi + 0.05) / (lo + 0.05)
+    }
+
+    /// Convert this color to HSL
+    ///
+    /// This returns `(hue, saturation, lightness)` where the hue is in degrees
+    /// (`0.0..360.0`) and the saturation and lightness are from `0.0` to `1.0`
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let RGB(r, g, b) = self.rgb;
+        let (r, g, b) = (
+            f32::from(r) / 255.0,
+            f32::from(g) / 255.0,
+            f32::from(b) / 255.0,
+        );
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let lightness = (max + min) / 2.0;
+        if delta == 0.0 {
+            return (0.0, 0.0, lightness);
+        }
+
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * (((g - b) / delta) % 6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        (hue.rem_euclid(360.0), saturation, lightness)
+    }
+
+    /// Lighten this color by mixing it with white
+    ///
+    /// `factor` is clamped from `0.0` (unchanged) to `1.0` (white)
+    pub fn lighten(self, factor: f32) -> Self {
+        self.mix(RGB(0xFF, 0xFF, 0xFF), factor)
+    }
+
+    /// Darken this color by mixing it with black
+    ///
+    /// `factor` is clamped from `0.0` (unchanged) to `1.0` (black)
+    pub fn darken(self, factor: f32) -> Self {
+        self.mix(RGB(0x00, 0x00, 0x00), factor)
+    }
+
+    /// The minimum contrast ratio [Color::readable_on] will produce (WCAG's `AA` level for normal text)
+    pub const READABLE_CONTRAST_RATIO: f32 = 4.5;
+
+    /// Get a variant of this color that is readable on the `background`
+    ///
+    /// If this color doesn't have a contrast ratio of atleast
+    /// [Color::READABLE_CONTRAST_RATIO] with the background, it'll be lightened
+    /// or darkened (whichever direction the background allows for more
+    /// contrast) until it does. This preserves the hue.
+    ///
+    /// A color identical to the background will end up near-black or near-white.
+    pub fn readable_on(self, background: Self) -> Self {
+        if self.contrast_ratio(background) >= Self::READABLE_CONTRAST_RATIO {
+            return self;
+        }
+
+        let white = Self::from(RGB(0xFF, 0xFF, 0xFF));
+        let black = Self::from(RGB(0x00, 0x00, 0x00));
+        let lighten = white.contrast_ratio(background) > black.contrast_ratio(background);
+
+        const STEPS: u8 = 20;
+        for step in 1..=STEPS {
+            let factor = f32::from(step) / f32::from(STEPS);
+            let color = if lighten {
+                self.lighten(factor)
+            } else {
+                self.darken(factor)
+            };
+
+            if color.contrast_ratio(background) >= Self::READABLE_CONTRAST_RATIO {
+                return color;
+            }
+        }
+
+        // pure black or white will always have enough contrast
+        if lighten {
+            white
+        } else {
+            black
+        }
+    }
+
+    fn mix(self, target: RGB, factor: f32) -> Self {
+        let factor = factor.clamp(0.0, 1.0);
+        let mix = |from: u8, to: u8| {
+            let (from, to) = (f32::from(from), f32::from(to));
+            (from + (to - from) * factor).round() as u8
+        };
+
+        let RGB(r, g, b) = self.rgb;
+        let RGB(tr, tg, tb) = target;
+        RGB(mix(r, tr), mix(g, tg), mix(b, tb)).into()
+    }
+}
+
 impl Default for Color {
     /// Defaults to having a kind of [Turbo] and RGB of #FFFFFF (white)
     ///
@@ -267,8 +400,8 @@ impl Default for Color {
     }
 }
 
-impl std::fmt::Display for Color {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Color {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         use TwitchColor::*;
         let name = match self.kind {
             Blue => "Blue",
@@ -344,6 +477,24 @@ impl From<Color> for RGB {
     }
 }
 
+impl From<RGB> for Color {
+    fn from(rgb: RGB) -> Self {
+        Self {
+            kind: rgb.into(),
+            rgb,
+        }
+    }
+}
+
+impl From<TwitchColor> for Color {
+    fn from(kind: TwitchColor) -> Self {
+        Self {
+            kind,
+            rgb: kind.into(),
+        }
+    }
+}
+
 impl From<RGB> for TwitchColor {
     fn from(rgb: RGB) -> Self {
         twitch_colors()
@@ -488,6 +639,139 @@ mod tests {
         assert_eq!(rgb.to_string(), "#27FF52")
     }
 
+    #[test]
+    fn color_from_rgb() {
+        let color = Color::from(RGB(0x8A, 0x2B, 0xE2));
+        assert_eq!(color.kind, TwitchColor::BlueViolet);
+        assert_eq!(color.to_string(), "BlueViolet");
+
+        let color = Color::from(RGB(0x12, 0x34, 0x56));
+        assert_eq!(color.kind, TwitchColor::Turbo);
+        assert_eq!(color.to_string(), "#123456");
+
+        let color = Color::from(TwitchColor::Coral);
+        assert_eq!(color.rgb, RGB(0xFF, 0x7F, 0x50));
+    }
+
+    #[test]
+    #[cfg(any(not(feature = "no_std"), feature = "async"))]
+    fn luminance() {
+        let close = |a: f32, b: f32| (a - b).abs() < 0.001;
+
+        assert!(close(Color::from(RGB(0xFF, 0xFF, 0xFF)).luminance(), 1.0));
+        assert!(close(Color::from(RGB(0x00, 0x00, 0x00)).luminance(), 0.0));
+        assert!(close(
+            Color::from(RGB(0xFF, 0x00, 0x00)).luminance(),
+            0.2126
+        ));
+        assert!(close(
+            Color::from(RGB(0x00, 0x80, 0x00)).luminance(),
+            0.1544
+        ));
+        assert!(close(
+            Color::from(RGB(0x80, 0x80, 0x80)).luminance(),
+            0.2159
+        ));
+    }
+
+    #[test]
+    #[cfg(any(not(feature = "no_std"), feature = "async"))]
+    fn contrast_ratio() {
+        let close = |a: f32, b: f32| (a - b).abs() < 0.01;
+
+        let white = Color::from(RGB(0xFF, 0xFF, 0xFF));
+        let black = Color::from(RGB(0x00, 0x00, 0x00));
+        assert!(close(white.contrast_ratio(black), 21.0));
+        assert!(close(black.contrast_ratio(white), 21.0));
+        assert!(close(white.contrast_ratio(white), 1.0));
+
+        let blue = Color::from(TwitchColor::Blue);
+        assert!(close(blue.contrast_ratio(white), 8.59));
+    }
+
+    #[test]
+    #[cfg(any(not(feature = "no_std"), feature = "async"))]
+    fn to_hsl() {
+        let close = |(a, b, c): (f32, f32, f32), (x, y, z): (f32, f32, f32)| {
+            (a - x).abs() < 0.5 && (b - y).abs() < 0.01 && (c - z).abs() < 0.01
+        };
+
+        assert!(close(
+            Color::from(TwitchColor::Red).to_hsl(),
+            (0.0, 1.0, 0.5)
+        ));
+        assert!(close(
+            Color::from(TwitchColor::Blue).to_hsl(),
+            (240.0, 1.0, 0.5)
+        ));
+        assert!(close(
+            Color::from(TwitchColor::BlueViolet).to_hsl(),
+            (271.1, 0.759, 0.527)
+        ));
+        assert!(close(
+            Color::from(RGB(0x80, 0x80, 0x80)).to_hsl(),
+            (0.0, 0.0, 0.502)
+        ));
+    }
+
+    #[test]
+    #[cfg(any(not(feature = "no_std"), feature = "async"))]
+    fn lighten_darken() {
+        let red = Color::from(TwitchColor::Red);
+
+        assert_eq!(red.lighten(0.0), red);
+        assert_eq!(red.lighten(1.0).rgb, RGB(0xFF, 0xFF, 0xFF));
+        assert_eq!(red.lighten(0.5).rgb, RGB(0xFF, 0x80, 0x80));
+
+        assert_eq!(red.darken(0.0), red);
+        assert_eq!(red.darken(1.0).rgb, RGB(0x00, 0x00, 0x00));
+        assert_eq!(red.darken(0.5).rgb, RGB(0x80, 0x00, 0x00));
+
+        // the hue is preserved
+        let (hue, ..) = red.darken(0.5).to_hsl();
+        assert_eq!(hue, 0.0);
+
+        // and it changes the kind if it's no longer a named color
+        assert_eq!(red.darken(0.5).kind, TwitchColor::Turbo);
+    }
+
+    #[test]
+    #[cfg(any(not(feature = "no_std"), feature = "async"))]
+    fn readable_on() {
+        let white = Color::from(RGB(0xFF, 0xFF, 0xFF));
+        let black = Color::from(RGB(0x00, 0x00, 0x00));
+        let gray = Color::from(RGB(0x76, 0x76, 0x76));
+
+        let backgrounds = [white, black, gray, Color::from(TwitchColor::Blue)];
+        for background in backgrounds.iter().copied() {
+            for (kind, _) in twitch_colors().iter() {
+                let color = Color::from(*kind).readable_on(background);
+                assert!(
+                    color.contrast_ratio(background) >= Color::READABLE_CONTRAST_RATIO,
+                    "{} on {}",
+                    color,
+                    background
+                );
+            }
+        }
+
+        // already readable colors aren't changed
+        let blue = Color::from(TwitchColor::Blue);
+        assert_eq!(blue.readable_on(white), blue);
+
+        // the hue is preserved
+        let yellow_green = Color::from(TwitchColor::YellowGreen);
+        let (expected, ..) = yellow_green.to_hsl();
+        let (hue, ..) = yellow_green.readable_on(white).to_hsl();
+        assert!((hue - expected).abs() < 2.0);
+
+        // the same color flips to near black or near white
+        let color = white.readable_on(white);
+        assert!(color.luminance() < 0.2);
+        let color = black.readable_on(black);
+        assert!(color.luminance() > 0.2);
+    }
+
     #[test]
     fn default_rgb() {
         let rgb = RGB::default();
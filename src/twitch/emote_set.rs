@@ -0,0 +1,100 @@
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+
+/// An emote set you have access to, from the `emote-sets` tag
+///
+/// Twitch sends these on `GLOBALUSERSTATE` and `USERSTATE`, set `0` is the global emotes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct EmoteSet(String);
+
+impl EmoteSet {
+    /// Create an emote set from its id
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// Parse the comma separated `emote-sets` tag, keeping the order and skipping any duplicates
+    pub fn parse_list(input: &str) -> Vec<Self> {
+        let mut seen = BTreeSet::new();
+        input
+            .split(',')
+            .filter(|id| !id.is_empty() && seen.insert(*id))
+            .map(Self::new)
+            .collect()
+    }
+
+    /// The id of this emote set
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+}
+
+impl core::fmt::Display for EmoteSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The difference between two lists of [emote sets](EmoteSet)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EmoteSetDiff {
+    /// Emote sets that are only in the new list, in the order they appeared
+    pub added: Vec<EmoteSet>,
+    /// Emote sets that are only in the old list, in the order they appeared
+    pub removed: Vec<EmoteSet>,
+}
+
+impl EmoteSetDiff {
+    /// Diff the `old` and `new` emote sets
+    pub fn between(old: &[EmoteSet], new: &[EmoteSet]) -> Self {
+        let only_in = |left: &[EmoteSet], right: &[EmoteSet]| {
+            let right = right.iter().collect::<BTreeSet<_>>();
+            left.iter()
+                .filter(|set| !right.contains(set))
+                .cloned()
+                .collect()
+        };
+
+        Self {
+            added: only_in(new, old),
+            removed: only_in(old, new),
+        }
+    }
+
+    /// Whether the emote sets didn't change
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_list() {
+        let sets = EmoteSet::parse_list("0,33,50,0,237,");
+        let ids = sets.iter().map(EmoteSet::id).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["0", "33", "50", "237"]);
+        assert!(EmoteSet::parse_list("").is_empty());
+    }
+
+    #[test]
+    fn diff() {
+        let old = EmoteSet::parse_list("0,33,50,237");
+        let new = EmoteSet::parse_list("0,50,1234,300374282");
+
+        let diff = EmoteSetDiff::between(&old, &new);
+        assert_eq!(
+            diff,
+            EmoteSetDiff {
+                added: vec![EmoteSet::new("1234"), EmoteSet::new("300374282")],
+                removed: vec![EmoteSet::new("33"), EmoteSet::new("237")],
+            }
+        );
+        assert!(!diff.is_empty());
+
+        assert!(EmoteSetDiff::between(&new, &new).is_empty());
+        assert_eq!(EmoteSetDiff::between(&new, &old).added, diff.removed);
+    }
+}
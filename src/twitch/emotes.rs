@@ -1,4 +1,9 @@
-use std::ops::Range;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::ops::Range;
 
 /**
 Emotes are little pictograms used in-line in Twitch messages
@@ -37,6 +42,42 @@ impl Emotes {
             emotes.into()
         })
     }
+
+    /// Encode emotes back into the `id:range1,range2/id2:range1,..` tag form
+    ///
+    /// Ranges for the same id are grouped together, in the order the ids were first seen.
+    ///
+    /// ```rust
+    /// use twitchchat::twitch::Emotes;
+    ///
+    /// let emotes = Emotes::parse("25:0-4,6-10/33:12-19").collect::<Vec<_>>();
+    /// assert_eq!(Emotes::encode(&emotes), "25:0-4,6-10/33:12-19");
+    /// ```
+    pub fn encode(emotes: &[Self]) -> String {
+        let mut grouped: Vec<(usize, Vec<&Range<u16>>)> = Vec::new();
+        for emote in emotes {
+            match grouped.iter_mut().find(|(id, _)| *id == emote.id) {
+                Some((_, ranges)) => ranges.extend(&emote.ranges),
+                None => grouped.push((emote.id, emote.ranges.iter().collect())),
+            }
+        }
+
+        let mut out = String::new();
+        for (i, (id, ranges)) in grouped.into_iter().enumerate() {
+            if i > 0 {
+                out.push('/');
+            }
+            out.push_str(&id.to_string());
+            out.push(':');
+            for (j, range) in ranges.into_iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                out.push_str(&format!("{}-{}", range.start, range.end));
+            }
+        }
+        out
+    }
 }
 
 #[inline]
@@ -103,4 +144,59 @@ mod tests {
             assert_eq!(emotes, *expect);
         }
     }
+
+    #[test]
+    fn encode_round_trip() {
+        let inputs = &[
+            "25:0-4,6-10,12-16",
+            "25:0-4",
+            "1077966:0-6/25:8-12",
+            "25:0-4,15-19/33:6-13",
+            "33:0-7/25:9-13,15-19",
+            "",
+        ];
+
+        for input in inputs {
+            let emotes = Emotes::parse(input).collect::<Vec<_>>();
+            assert_eq!(Emotes::encode(&emotes), *input);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn encode_groups_by_id() {
+        let emotes = vec![
+            Emotes {
+                id: 25,
+                ranges: vec![0..4],
+            },
+            Emotes {
+                id: 33,
+                ranges: vec![6..13],
+            },
+            Emotes {
+                id: 25,
+                ranges: vec![15..19],
+            },
+        ];
+
+        let encoded = Emotes::encode(&emotes);
+        assert_eq!(encoded, "25:0-4,15-19/33:6-13");
+
+        let mut parsed = Emotes::parse(&encoded).collect::<Vec<_>>();
+        parsed.sort_by_key(|e| e.id);
+        assert_eq!(
+            parsed,
+            vec![
+                Emotes {
+                    id: 25,
+                    ranges: vec![0..4, 15..19]
+                },
+                Emotes {
+                    id: 33,
+                    ranges: vec![6..13]
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,138 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/**
+Flags are AutoMod's classification of parts of a message
+
+They are presented (to the irc connection) in a `start-end:CATEGORY.level/CATEGORY.level,start-end:..` form which marks the character position of the flagged text.
+
+# example:
+`"0-4:P.5"` would be the first word being profanity with a level of `5`
+
+`"0-4:P.5,6-10:A.7/I.6"` would also have the second word being both aggressive and identity-based
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Flag {
+    /// The [Range] in the message that was flagged
+    ///
+    /// [Range]: https://doc.rust-lang.org/std/ops/struct.Range.html
+    pub range: Range<u16>,
+    /// The categories (and their levels) this part of the message was flagged for
+    pub scores: Vec<FlagScore>,
+}
+
+/// A category and level of a [Flag]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct FlagScore {
+    /// The category
+    pub category: FlagCategory,
+    /// How severe it is, generally from `0` to `7`
+    pub level: u8,
+}
+
+/// The categories AutoMod uses for a [Flag]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum FlagCategory {
+    /// Aggressive language -- `A`
+    Aggressive,
+    /// Identity-based hate -- `I`
+    Identity,
+    /// Profanity -- `P`
+    Profanity,
+    /// Sexually explicit language -- `S`
+    Sexual,
+    /// An unknown category
+    Unknown,
+}
+
+impl FlagCategory {
+    fn parse(input: &str) -> Self {
+        match input {
+            "A" => Self::Aggressive,
+            "I" => Self::Identity,
+            "P" => Self::Profanity,
+            "S" => Self::Sexual,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl Flag {
+    /// Parse flags from a string, returning an iterator over each flag
+    pub fn parse(input: &str) -> impl Iterator<Item = Self> + '_ {
+        input.split_terminator(',').filter_map(Self::parse_item)
+    }
+
+    /// Parse a single flag
+    pub fn parse_item(item: &str) -> Option<Self> {
+        let (range, scores) = item.split_once(':')?;
+        let (start, end) = range.split_once('-')?;
+
+        let scores = scores
+            .split_terminator('/')
+            .filter_map(|score| {
+                let (category, level) = score.split_once('.')?;
+                FlagScore {
+                    category: FlagCategory::parse(category),
+                    level: level.parse().ok()?,
+                }
+                .into()
+            })
+            .collect();
+
+        Self {
+            range: Range {
+                start: start.parse().ok()?,
+                end: end.parse().ok()?,
+            },
+            scores,
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let flags = Flag::parse("0-4:P.5,6-10:A.7/I.6,12-15:").collect::<Vec<_>>();
+        assert_eq!(
+            flags,
+            vec![
+                Flag {
+                    range: 0..4,
+                    scores: vec![FlagScore {
+                        category: FlagCategory::Profanity,
+                        level: 5
+                    }]
+                },
+                Flag {
+                    range: 6..10,
+                    scores: vec![
+                        FlagScore {
+                            category: FlagCategory::Aggressive,
+                            level: 7
+                        },
+                        FlagScore {
+                            category: FlagCategory::Identity,
+                            level: 6
+                        }
+                    ]
+                },
+                Flag {
+                    range: 12..15,
+                    scores: vec![]
+                },
+            ]
+        );
+
+        assert_eq!(Flag::parse("").count(), 0);
+        assert_eq!(Flag::parse("garbage").count(), 0);
+    }
+}
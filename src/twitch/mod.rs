@@ -1,5 +1,7 @@
 //! Common Twitch types
 
+use alloc::vec::Vec;
+
 mod capability;
 pub use capability::Capability;
 
@@ -9,13 +11,22 @@ pub use userconfig::{UserConfig, UserConfigBuilder, UserConfigError};
 mod emotes;
 pub use emotes::Emotes;
 
+mod emote_set;
+pub use emote_set::{EmoteSet, EmoteSetDiff};
+
+mod flags;
+pub use flags::{Flag, FlagCategory, FlagScore};
+
 mod badge;
-pub use badge::{Badge, BadgeInfo, BadgeKind};
+pub use badge::{Badge, BadgeInfo, BadgeKind, Role, Roles};
 
 pub mod color;
 #[doc(inline)]
 pub use color::Color;
 
+mod channel;
+pub use channel::normalize_channel;
+
 #[allow(dead_code)]
 pub(crate) fn parse_emotes(input: &str) -> Vec<Emotes> {
     Emotes::parse(input).collect()
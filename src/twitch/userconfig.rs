@@ -1,5 +1,9 @@
 use crate::twitch::Capability;
-use std::collections::BTreeSet;
+use alloc::{
+    collections::BTreeSet,
+    string::{String, ToString},
+    vec::Vec,
+};
 
 /**
 User configuration for 'registering' with Twitch
@@ -25,8 +29,11 @@ let config = UserConfig::builder()
     .build()
     .unwrap();
 ```
+
+# NOTE
+The `Debug` output of this type redacts the token, so it is safe to log.
 */
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserConfig {
     /// Requested name of your user
@@ -37,15 +44,67 @@ pub struct UserConfig {
     pub capabilities: Vec<Capability>,
 }
 
+impl core::fmt::Debug for UserConfig {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UserConfig")
+            .field("name", &self.name)
+            .field("token", &Redacted)
+            .field("capabilities", &self.capabilities)
+            .finish()
+    }
+}
+
 impl UserConfig {
     /// Create a builder to make a [UserConfig]
     pub fn builder() -> UserConfigBuilder {
         UserConfigBuilder::default()
     }
 
+    /// Create a builder from the conventional environment variables
+    ///
+    /// This reads:
+    /// * `TWITCH_NAME` for the name
+    /// * `TWITCH_OAUTH` (or `TWITCH_TOKEN`, if that isn't set) for the token
+    ///
+    /// ```
+    /// # use twitchchat::twitch::{Capability, UserConfig};
+    /// # std::env::set_var("TWITCH_NAME", "foo");
+    /// # std::env::set_var("TWITCH_TOKEN", format!("oauth:{}", "a".repeat(30)));
+    /// let config = UserConfig::from_env()
+    ///     .unwrap()
+    ///     .capabilities(&[Capability::Tags])
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// If a variable is missing (or empty) this returns [UserConfigError::MissingEnvVar] naming it
+    #[cfg(any(not(feature = "no_std"), feature = "async"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "no_std"))))]
+    pub fn from_env() -> Result<UserConfigBuilder, UserConfigError> {
+        fn var(key: &str) -> Option<String> {
+            std::env::var(key).ok().filter(|s| !s.is_empty())
+        }
+
+        let name = var("TWITCH_NAME").ok_or(UserConfigError::MissingEnvVar {
+            name: "TWITCH_NAME",
+        })?;
+
+        let token = var("TWITCH_OAUTH").or_else(|| var("TWITCH_TOKEN")).ok_or(
+            UserConfigError::MissingEnvVar {
+                name: "TWITCH_OAUTH or TWITCH_TOKEN",
+            },
+        )?;
+
+        Ok(UserConfig::builder().name(name).token(token))
+    }
+
     /// Determines whether this config was requested as anonymous
+    ///
+    /// Twitch treats any `justinfan` name (e.g. `justinfan1234` or `justinfan5678`)
+    /// as anonymous. Like with `justinfan1234`, the token has to be the same name.
     pub fn is_anonymous(&self) -> bool {
-        self.name == crate::JUSTINFAN1234 && self.token == crate::JUSTINFAN1234
+        self.name.starts_with(crate::JUSTINFAN) && self.token == self.name
     }
 }
 
@@ -59,10 +118,22 @@ pub enum UserConfigError {
     InvalidToken,
     /// Anonymous login was requested with a user-provided name or token
     PartialAnonymous,
+    /// An environment variable wasn't set. See [UserConfig::from_env]
+    MissingEnvVar {
+        /// The name of the missing variable
+        name: &'static str,
+    },
+    /// A capability that requires authentication was requested with an anonymous login
+    ///
+    /// This is only returned when [UserConfigBuilder::strict] is used.
+    AnonymousCapability {
+        /// The capability that won't do anything
+        capability: Capability,
+    },
 }
 
-impl std::fmt::Display for UserConfigError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for UserConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::InvalidName => f.write_str("invalid name"),
             Self::InvalidToken => {
@@ -71,19 +142,47 @@ impl std::fmt::Display for UserConfigError {
             Self::PartialAnonymous => f.write_str(
                 "user provided name or token provided when an anonymous login was requested",
             ),
+            Self::MissingEnvVar { name } => write!(f, "environment variable not set: {}", name),
+            Self::AnonymousCapability { capability } => write!(
+                f,
+                "capability '{}' requires authentication, but an anonymous login was requested",
+                capability.as_str()
+            ),
         }
     }
 }
 
-impl std::error::Error for UserConfigError {}
+impl core::error::Error for UserConfigError {}
 
 /// Builder for making a [UserConfig]
-#[derive(Default, Debug)]
+#[derive(Default, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserConfigBuilder {
     capabilities: BTreeSet<Capability>,
     name: Option<String>,
     token: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    strict: bool,
+}
+
+impl core::fmt::Debug for UserConfigBuilder {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UserConfigBuilder")
+            .field("capabilities", &self.capabilities)
+            .field("name", &self.name)
+            .field("token", &self.token.as_ref().map(|_| Redacted))
+            .field("strict", &self.strict)
+            .finish()
+    }
+}
+
+// never print the oauth token
+struct Redacted;
+
+impl core::fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\"oauth:***\"")
+    }
 }
 
 impl UserConfigBuilder {
@@ -114,8 +213,26 @@ impl UserConfigBuilder {
 
     /// Capabilities to enable
     ///
+    /// This replaces any capabilities that were previously enabled, duplicates are ignored.
+    ///
+    /// ```
+    /// # use twitchchat::twitch::{Capability, UserConfig};
+    /// let config = UserConfig::builder()
+    ///     .anonymous()
+    ///     .enable_all_capabilities()
+    ///     .capabilities(&[Capability::Tags, Capability::Tags])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(config.capabilities, vec![Capability::Tags]);
+    /// ```
     pub fn capabilities(mut self, caps: &[Capability]) -> Self {
-        self.capabilities.extend(caps);
+        self.capabilities = caps.iter().copied().collect();
+        self
+    }
+
+    /// Enable this capability, along with any previously enabled ones
+    pub fn add_capability(mut self, cap: Capability) -> Self {
+        self.capabilities.insert(cap);
         self
     }
 
@@ -125,11 +242,31 @@ impl UserConfigBuilder {
     ///
     /// [here]: Capability
     pub fn enable_all_capabilities(self) -> Self {
-        self.capabilities(&[
-            Capability::Membership,
-            Capability::Tags,
-            Capability::Commands,
-        ])
+        self.add_capability(Capability::Membership)
+            .add_capability(Capability::Tags)
+            .add_capability(Capability::Commands)
+    }
+
+    /// Reject capabilities that won't do anything with an anonymous login
+    ///
+    /// Without this, requesting a capability that [requires auth] with an
+    /// anonymous login (any `justinfan` name) only logs a warning.
+    ///
+    /// ```
+    /// # use twitchchat::twitch::{Capability, UserConfig, UserConfigError};
+    /// let err = UserConfig::builder()
+    ///     .anonymous()
+    ///     .capabilities(&[Capability::Commands])
+    ///     .strict()
+    ///     .build()
+    ///     .unwrap_err();
+    /// assert!(matches!(err, UserConfigError::AnonymousCapability { .. }));
+    /// ```
+    ///
+    /// [requires auth]: Capability::requires_auth
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
     }
 
     /// Tries to build the UserConfig
@@ -138,6 +275,9 @@ impl UserConfigBuilder {
     ///
     /// If the anonymous `name` OR `token` is used without the other matching one this will return an [error].
     ///
+    /// If [UserConfigBuilder::strict] was used, requesting a capability that
+    /// requires authentication with an anonymous login will also return an [error].
+    ///
     /// [error]: UserConfigError
     pub fn build(self) -> Result<UserConfig, UserConfigError> {
         let name = self
@@ -160,11 +300,28 @@ impl UserConfigBuilder {
             _ => {}
         }
 
-        Ok(UserConfig {
+        let config = UserConfig {
             name,
             token,
             capabilities: self.capabilities.into_iter().collect(),
-        })
+        };
+
+        if config.is_anonymous() {
+            let auth_only = config.capabilities.iter().find(|cap| cap.requires_auth());
+            if let Some(&capability) = auth_only {
+                if self.strict {
+                    return Err(UserConfigError::AnonymousCapability { capability });
+                }
+
+                #[cfg(feature = "log")]
+                log::warn!(
+                    "capability '{}' requires authentication, but an anonymous login was requested",
+                    capability.as_str()
+                );
+            }
+        }
+
+        Ok(config)
     }
 }
 
@@ -175,7 +332,7 @@ const fn validate_name(s: &str) -> bool {
 
 #[inline]
 fn validate_token(s: &str) -> bool {
-    if s == crate::JUSTINFAN1234 {
+    if s.starts_with(crate::JUSTINFAN) {
         return true;
     }
     !s.is_empty() && s.len() == 36 && &s[..6] == "oauth:"
@@ -209,7 +366,7 @@ mod tests {
             .name("foo")
             .token(format!("oauth:{}", "a".repeat(30)))
             .capabilities(&[Capability::Tags, Capability::Tags])
-            .capabilities(&[Capability::Membership])
+            .add_capability(Capability::Membership)
             .build()
             .unwrap();
 
@@ -237,6 +394,129 @@ mod tests {
         );
 
         assert!(config.is_anonymous());
+
+        let config = UserConfig {
+            name: "justinfan5678".to_string(),
+            token: "justinfan5678".to_string(),
+            capabilities: vec![],
+        };
+        assert!(config.is_anonymous());
+    }
+
+    #[test]
+    fn capabilities_subset() {
+        let config = UserConfig::builder()
+            .anonymous()
+            .capabilities(&[
+                Capability::Membership,
+                Capability::Tags,
+                Capability::Membership,
+            ])
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.capabilities,
+            vec![Capability::Membership, Capability::Tags]
+        );
+
+        // this replaces the previous ones
+        let config = UserConfig::builder()
+            .anonymous()
+            .enable_all_capabilities()
+            .capabilities(&[Capability::Commands])
+            .build()
+            .unwrap();
+        assert_eq!(config.capabilities, vec![Capability::Commands]);
+
+        let config = UserConfig::builder()
+            .anonymous()
+            .capabilities(&[Capability::Tags])
+            .add_capability(Capability::Membership)
+            .add_capability(Capability::Tags)
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.capabilities,
+            vec![Capability::Membership, Capability::Tags]
+        );
+
+        let config = UserConfig::builder()
+            .anonymous()
+            .capabilities(&[])
+            .build()
+            .unwrap();
+        assert!(config.capabilities.is_empty());
+    }
+
+    #[test]
+    fn anonymous_auth_capabilities() {
+        // without strict, this is allowed
+        let config = UserConfig::builder()
+            .anonymous()
+            .enable_all_capabilities()
+            .build()
+            .unwrap();
+        assert!(config.is_anonymous());
+
+        let err = UserConfig::builder()
+            .anonymous()
+            .enable_all_capabilities()
+            .strict()
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            UserConfigError::AnonymousCapability {
+                capability: Capability::Commands
+            }
+        ));
+
+        // these are fine when anonymous
+        let config = UserConfig::builder()
+            .anonymous()
+            .capabilities(&[Capability::Tags, Capability::Membership])
+            .strict()
+            .build()
+            .unwrap();
+        assert!(config.is_anonymous());
+
+        // a manual justinfan name is also anonymous
+        let builder = UserConfig::builder()
+            .name("justinfan5678")
+            .token("justinfan5678");
+        let config = builder.clone().strict().build().unwrap();
+        assert!(config.is_anonymous());
+
+        // but not with an oauth token
+        let config = UserConfig::builder()
+            .name("justinfan5678")
+            .token(format!("oauth:{}", "a".repeat(30)))
+            .capabilities(&[Capability::Commands])
+            .strict()
+            .build()
+            .unwrap();
+        assert!(!config.is_anonymous());
+
+        let err = builder
+            .capabilities(&[Capability::Commands])
+            .strict()
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            UserConfigError::AnonymousCapability {
+                capability: Capability::Commands
+            }
+        ));
+
+        // and it's not anonymous without the justinfan prefix
+        UserConfig::builder()
+            .name("museun")
+            .token(format!("oauth:{}", "a".repeat(30)))
+            .capabilities(&[Capability::Commands])
+            .strict()
+            .build()
+            .unwrap();
     }
 
     #[test]
@@ -300,4 +580,93 @@ mod tests {
             .unwrap_err();
         matches!(err, UserConfigError::InvalidToken);
     }
+
+    #[test]
+    #[cfg(any(not(feature = "no_std"), feature = "async"))]
+    fn from_env() {
+        // the environment is process-wide, so these have to be serialized
+        static ENV: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        let _guard = ENV.lock().unwrap_or_else(|err| err.into_inner());
+
+        let set = |name: Option<&str>, oauth: Option<&str>, token: Option<&str>| {
+            for (key, val) in &[
+                ("TWITCH_NAME", name),
+                ("TWITCH_OAUTH", oauth),
+                ("TWITCH_TOKEN", token),
+            ] {
+                match val {
+                    Some(val) => std::env::set_var(key, val),
+                    None => std::env::remove_var(key),
+                }
+            }
+        };
+
+        let oauth = format!("oauth:{}", "a".repeat(30));
+        let token = format!("oauth:{}", "b".repeat(30));
+
+        set(None, Some(&oauth), None);
+        assert!(matches!(
+            UserConfig::from_env().unwrap_err(),
+            UserConfigError::MissingEnvVar {
+                name: "TWITCH_NAME"
+            }
+        ));
+
+        set(Some(""), Some(&oauth), None);
+        assert!(matches!(
+            UserConfig::from_env().unwrap_err(),
+            UserConfigError::MissingEnvVar {
+                name: "TWITCH_NAME"
+            }
+        ));
+
+        set(Some("foo"), None, None);
+        let err = UserConfig::from_env().unwrap_err();
+        assert!(err.to_string().contains("TWITCH_OAUTH"));
+        assert!(err.to_string().contains("TWITCH_TOKEN"));
+
+        set(Some("foo"), None, Some(&token));
+        let config = UserConfig::from_env().unwrap().build().unwrap();
+        assert_eq!(config.name, "foo");
+        assert_eq!(config.token, token);
+
+        // TWITCH_OAUTH is preferred
+        set(Some("foo"), Some(&oauth), Some(&token));
+        let config = UserConfig::from_env()
+            .unwrap()
+            .capabilities(&[Capability::Tags])
+            .build()
+            .unwrap();
+        assert_eq!(config.token, oauth);
+        assert_eq!(config.capabilities, vec![Capability::Tags]);
+
+        set(None, None, None);
+    }
+
+    #[test]
+    fn debug_redacts_token() {
+        let token = format!("oauth:{}", "a".repeat(30));
+        let builder = UserConfig::builder()
+            .name("foo")
+            .token(&token)
+            .capabilities(&[Capability::Tags]);
+
+        let debug = format!("{:?}", builder);
+        assert!(!debug.contains(&token));
+        assert!(debug.contains("oauth:***"));
+
+        let config = builder.build().unwrap();
+        for debug in &[format!("{:?}", config), format!("{:#?}", config)] {
+            assert!(!debug.contains(&token));
+            assert!(debug.contains("oauth:***"));
+            assert!(debug.contains("foo"));
+            assert!(debug.contains("Tags"));
+        }
+
+        #[cfg(any(not(feature = "no_std"), feature = "async"))]
+        {
+            let debug = format!("{:?}", crate::commands::register(&config));
+            assert!(!debug.contains(&token));
+        }
+    }
 }
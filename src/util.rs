@@ -49,6 +49,26 @@ impl Notify {
 }
 
 /// A notify handle for sending a single-shot signal to the 'other side'
+///
+/// This is cheap to clone and can be moved to another thread. [NotifyHandle::notify_sync]
+/// doesn't block, so it can be triggered from outside of the async code -- e.g. from a Ctrl-C handler.
+///
+/// ```no_run
+/// # use twitchchat::{AsyncRunner, Status};
+/// # async fn run(mut runner: AsyncRunner) {
+/// let quit_handle = runner.quit_handle();
+/// std::thread::spawn(move || {
+///     // e.g. wait for Ctrl-C here
+///     quit_handle.notify_sync();
+/// });
+///
+/// while let Ok(status) = runner.next_message().await {
+///     if let Status::Quit = status {
+///         break;
+///     }
+/// }
+/// # }
+/// ```
 #[derive(Clone)]
 pub struct NotifyHandle {
     tx: crate::channel::Sender<()>,
@@ -67,6 +87,21 @@ impl NotifyHandle {
     pub async fn notify(self) -> bool {
         self.tx.send(()).await.is_ok()
     }
+
+    /// Notify the other side without blocking.
+    ///
+    /// This can be called from synchronous code. Calling it more than once is
+    /// the same as calling it once.
+    ///
+    /// Returns false if the other side wasn't around any more
+    pub fn notify_sync(&self) -> bool {
+        use crate::channel::TrySendError;
+        match self.tx.try_send(()) {
+            // a notification is already pending
+            Ok(()) | Err(TrySendError::Full(..)) => true,
+            Err(TrySendError::Closed(..)) => false,
+        }
+    }
 }
 
 pub enum Either<L, R> {
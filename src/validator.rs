@@ -2,6 +2,7 @@ use crate::{
     irc::{IrcMessage, MessageError, TagIndices},
     MaybeOwnedIndex,
 };
+use alloc::string::ToString;
 
 /// This trait is provided as an easy way of defining your own custom events.
 pub trait Validator {
@@ -24,7 +25,11 @@ pub trait Validator {
 impl<'a> Validator for IrcMessage<'a> {
     fn parse_tags(&self) -> TagIndices {
         self.tags
-            .and_then(|index| TagIndices::build_indices(&self.raw[index]).ok())
+            .and_then(|index| {
+                TagIndices::build_indices(&self.raw[index])
+                    .ok()
+                    .map(|indices| indices.offset_by(index.start as usize))
+            })
             .unwrap_or_default()
     }
 
@@ -1,7 +1,10 @@
 use crate::channel::Sender;
 use crate::encoder::AsyncEncoder;
+use crate::rate_limit::RateLimit;
 use crate::Encodable;
 
+use super::PacedWriter;
+
 use futures_lite::AsyncWrite;
 use io::Write;
 use std::io::{self};
@@ -63,14 +66,21 @@ where
     {
         self.inner.encode(msg).await?;
         if self.activity_tx.send(()).await.is_err() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::UnexpectedEof,
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
                 "Runner has closed its receiver",
             ));
         }
         Ok(())
     }
 
+    /// Wrap this writer so each message waits for the [RateLimit] before it is sent
+    ///
+    /// See [PacedWriter] for details.
+    pub fn paced(self, rate_limit: RateLimit) -> PacedWriter<W> {
+        PacedWriter::new(self, rate_limit)
+    }
+
     /// Encode a slice of [Encodable] messages to the writer.
     pub async fn encode_many<'a, I, M>(&mut self, msgs: I) -> io::Result<()>
     where
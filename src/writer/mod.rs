@@ -5,3 +5,6 @@ pub use async_writer::AsyncWriter;
 
 mod mpsc_writer;
 pub use mpsc_writer::MpscWriter;
+
+mod paced_writer;
+pub use paced_writer::PacedWriter;
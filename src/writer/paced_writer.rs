@@ -0,0 +1,126 @@
+use crate::rate_limit::RateLimit;
+use crate::runner::{Clock, SystemClock};
+use crate::Encodable;
+
+use super::AsyncWriter;
+
+use futures_lite::AsyncWrite;
+use std::{io, sync::Arc};
+
+/// An [AsyncWriter] that waits for a [RateLimit] before sending each message
+///
+/// This is created with [AsyncWriter::paced]. Each call to [encode](PacedWriter::encode)
+/// consumes a single token, waiting until one is available. This smooths out bursts
+/// (e.g. sending messages in a loop) rather than tripping Twitch's rate limit.
+///
+/// This is in addition to the limiting done by the [AsyncRunner](crate::AsyncRunner).
+pub struct PacedWriter<W> {
+    inner: AsyncWriter<W>,
+    rate_limit: RateLimit,
+    clock: Arc<dyn Clock>,
+}
+
+impl<W> std::fmt::Debug for PacedWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PacedWriter")
+            .field("rate_limit", &self.rate_limit)
+            .finish()
+    }
+}
+
+impl<W> PacedWriter<W> {
+    pub(crate) fn new(inner: AsyncWriter<W>, rate_limit: RateLimit) -> Self {
+        Self {
+            inner,
+            rate_limit,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Replace the [Clock] used to wait for the rate limit
+    ///
+    /// The default is [SystemClock].
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Arc::new(clock);
+    }
+
+    /// Get the rate limit used by this writer
+    pub fn rate_limit(&self) -> &RateLimit {
+        &self.rate_limit
+    }
+
+    /// Consumes this returning the wrapped [AsyncWriter]
+    pub fn into_inner(self) -> AsyncWriter<W> {
+        self.inner
+    }
+}
+
+impl<W> PacedWriter<W>
+where
+    W: AsyncWrite + Unpin + Send + Sync,
+{
+    /// Encode this [Encodable] message to the writer, waiting for the rate limit first.
+    pub async fn encode<M>(&mut self, msg: M) -> io::Result<()>
+    where
+        M: Encodable + Send + Sync,
+    {
+        while let Err(wait) = self.rate_limit.consume_at(1, self.clock.now()) {
+            log::debug!(target: "twitchchat::rate_limit", "paced writer waiting for {:.2?}", wait);
+            self.clock.delay(wait).await;
+        }
+        self.inner.encode(msg).await
+    }
+
+    /// Encode a slice of [Encodable] messages to the writer, waiting for the rate limit before each one.
+    pub async fn encode_many<'a, I, M>(&mut self, msgs: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a M> + Send + Sync + 'a,
+        I::IntoIter: Send + Sync,
+        M: Encodable + Send + Sync + 'a,
+    {
+        for msg in msgs {
+            self.encode(msg).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::{commands, test::ManualClock};
+    use futures_lite::future::{block_on, poll_once};
+    use std::time::Duration;
+
+    #[test]
+    fn paced_waits_for_rate_limit() {
+        let (activity_tx, activity_rx) = crate::channel::bounded(64);
+        let writer = AsyncWriter::new(futures_lite::io::sink(), activity_tx);
+
+        let clock = ManualClock::new();
+        let mut writer = writer.paced(RateLimit::full(50, Duration::from_secs(30)));
+        writer.set_clock(clock.clone());
+
+        for _ in 0..50 {
+            let res = block_on(poll_once(writer.encode(commands::privmsg("#museun", "hi"))));
+            assert!(matches!(res, Some(Ok(()))));
+        }
+
+        let mut fut = Box::pin(writer.encode(commands::privmsg("#museun", "hi")));
+        assert!(block_on(poll_once(&mut fut)).is_none());
+
+        clock.advance(Duration::from_secs(15));
+        assert!(block_on(poll_once(&mut fut)).is_none());
+
+        clock.advance(Duration::from_secs(16));
+        assert!(matches!(block_on(poll_once(&mut fut)), Some(Ok(()))));
+        drop(fut);
+
+        for _ in 51..60 {
+            let res = block_on(poll_once(writer.encode(commands::privmsg("#museun", "hi"))));
+            assert!(matches!(res, Some(Ok(()))));
+        }
+
+        assert_eq!(std::iter::from_fn(|| activity_rx.try_recv()).count(), 60);
+    }
+}
@@ -0,0 +1,41 @@
+// this lives in its own test binary so the counting allocator doesn't replace the library's allocator
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+use twitchchat::{irc, messages::Privmsg, FromIrcMessage as _};
+
+// counts the allocations made on the current thread
+struct Counting;
+
+std::thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|c| c.set(c.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[test]
+fn from_irc_ref_does_not_allocate() {
+    let input = ":test!test@test PRIVMSG #museun :this is a test\r\n";
+    let (_, msg) = irc::parse_one(input).unwrap();
+
+    let before = ALLOCATIONS.with(|c| c.get());
+    let pm = Privmsg::from_irc_ref(&msg).unwrap();
+    let after = ALLOCATIONS.with(|c| c.get());
+    assert_eq!(before, after);
+
+    assert_eq!(pm.data(), "this is a test");
+    assert!(std::ptr::eq(pm.raw(), msg.get_raw()));
+}